@@ -1,93 +1,54 @@
-export function useAutoHide(isPinned: Ref<boolean>) {
-  const isHidden = ref(false);
-  const windowWidth = 340;
-
-  async function slideIn() {
-    if (isHidden.value || isPinned.value) return;
-
-    if (window.__TAURI__) {
-      try {
-        const { getCurrentWindow } = await import("@tauri-apps/api/window");
-        const win = getCurrentWindow();
-        const position = await win.outerPosition();
-        await win.setPosition({
-          x: -windowWidth - 10,
-          y: position.y,
-          type: "Physical",
-        });
-        isHidden.value = true;
-      } catch (e) {
-        console.warn("slideIn failed:", e);
-      }
-    }
-  }
-
-  async function slideOut() {
-    if (!isHidden.value) return;
-
-    if (window.__TAURI__) {
-      try {
-        const { getCurrentWindow } = await import("@tauri-apps/api/window");
-        const win = getCurrentWindow();
-        const position = await win.outerPosition();
-        await win.setPosition({ x: 0, y: position.y, type: "Physical" });
-        await win.setFocus();
-        isHidden.value = false;
-      } catch (e) {
-        console.warn("slideOut failed:", e);
-      }
+/**
+ * Edge activation for a HUD window. The Rust side tracks the cursor (with
+ * dwell, hysteresis and drag exclusion) and emits `hud-reveal` / `hud-hide`
+ * to this window; we just react to them.
+ */
+export function useAutoHide(options: {
+  enabled: Ref<boolean>;
+  isPinned: Ref<boolean>;
+  onReveal: () => void;
+  onHide: () => void;
+}) {
+  const unlisteners: Array<() => void> = [];
+  const isTauri =
+    typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+  async function register() {
+    if (!isTauri) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("set_edge_hover_enabled", {
+        enabled: options.enabled.value,
+        pinned: options.isPinned.value,
+      });
+    } catch (e) {
+      console.warn("Failed to configure edge hover:", e);
     }
   }
 
-  // Track mouse position for auto-hide
-  let checkInterval: ReturnType<typeof setInterval> | null = null;
-
   async function startTracking() {
-    if (checkInterval) return;
-
-    checkInterval = setInterval(async () => {
-      if (!window.__TAURI__ || isPinned.value) return;
-
-      try {
-        const { getCurrentWindow } = await import("@tauri-apps/api/window");
-        const win = getCurrentWindow();
-        const pos = await win.outerPosition();
-        const size = await win.outerSize();
-
-        // Get cursor position via Tauri
-        const { invoke } = await import("@tauri-apps/api/core");
-        const cursor = await invoke<{ x: number; y: number }>(
-          "get_cursor_position",
-        ).catch(() => null);
-
-        if (!cursor) return;
-
-        const inWindow =
-          cursor.x >= pos.x &&
-          cursor.x <= pos.x + size.width &&
-          cursor.y >= pos.y &&
-          cursor.y <= pos.y + size.height;
-
-        const nearEdge = cursor.x <= 20;
-
-        if (isHidden.value && nearEdge) {
-          slideOut();
-        } else if (!isHidden.value && !inWindow) {
-          slideIn();
-        }
-      } catch (e) {
-        // Ignore
-      }
-    }, 100);
+    if (!isTauri || unlisteners.length) return;
+    const { getCurrentWebviewWindow } =
+      await import("@tauri-apps/api/webviewWindow");
+    const win = getCurrentWebviewWindow();
+    unlisteners.push(await win.listen("hud-reveal", () => options.onReveal()));
+    unlisteners.push(await win.listen("hud-hide", () => options.onHide()));
+    await register();
   }
 
-  function stopTracking() {
-    if (checkInterval) {
-      clearInterval(checkInterval);
-      checkInterval = null;
+  async function stopTracking() {
+    unlisteners.splice(0).forEach((unlisten) => unlisten());
+    if (!isTauri) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("set_edge_hover_enabled", { enabled: false });
+    } catch {
+      /* window may already be closing */
     }
   }
 
+  watch([options.enabled, options.isPinned], register);
+
   onMounted(() => {
     startTracking();
   });
@@ -97,9 +58,6 @@ export function useAutoHide(isPinned: Ref<boolean>) {
   });
 
   return {
-    isHidden,
-    slideIn,
-    slideOut,
     startTracking,
     stopTracking,
   };
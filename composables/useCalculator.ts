@@ -45,6 +45,7 @@ export function useCalculator() {
   const results = ref<CalculatorResults | null>(null)
   const error = ref<string | null>(null)
 
+  // Keep in sync with calculate() in src-tauri/src/sizing.rs
   function calculate() {
     error.value = null
     
@@ -66,6 +66,49 @@ export function useChartAnalyzer() {
     saveHistory();
   }
 
+  // Send an image to the AI provider and parse the JSON result
+  async function runAnalysis(
+    imageBase64: string,
+    provider: string,
+    baseUrl: string,
+    model: string,
+    analysisTypes: string[],
+  ) {
+    const { invoke } = await import("@tauri-apps/api/core");
+    capturedImage.value = imageBase64;
+    status.value = "Analyzing chart...";
+
+    const response = await invoke<string>("analyze_chart", {
+      imageBase64,
+      analysisTypes,
+      provider,
+      baseUrl,
+      model,
+    });
+
+    rawResponse.value = response;
+
+    // Parse JSON from response
+    try {
+      let jsonStr = response;
+      const fenceMatch = jsonStr.match(/```(?:json)?\s*([\s\S]*?)```/);
+      if (fenceMatch) {
+        jsonStr = fenceMatch[1];
+      }
+      const jsonMatch = jsonStr.match(/\{[\s\S]*\}/);
+      if (jsonMatch) {
+        const parsed = JSON.parse(jsonMatch[0]);
+        result.value = parsed;
+        addToHistory(parsed, capturedImage.value || undefined);
+        status.value = "Analysis complete";
+      } else {
+        status.value = "Could not parse response";
+      }
+    } catch {
+      status.value = "Could not parse response";
+    }
+  }
+
   async function captureAndAnalyze(
     region: [number, number, number, number] | null,
     provider: string,
@@ -74,8 +117,9 @@ export function useChartAnalyzer() {
     analysisTypes: string[],
     windowConfig: {
       position: string;
-      monitorIndex: number;
+      monitorId: string | null;
       triggerStyle: string;
+      dockHeight?: number;
     },
   ) {
     if (isAnalyzing.value) return;
@@ -95,13 +139,15 @@ export function useChartAnalyzer() {
 
       const { invoke } = await import("@tauri-apps/api/core");
 
-      // Hide HUD so it doesn't appear in the screenshot
+      // Hide HUD so it doesn't appear in the screenshot. No slide, so the
+      // window is already tucked; the pause only lets the compositor repaint.
       await invoke("tuck_window", {
         position: windowConfig.position,
-        monitorIndex: windowConfig.monitorIndex,
+        monitorId: windowConfig.monitorId,
         triggerStyle: windowConfig.triggerStyle,
+        animate: false,
       });
-      await new Promise((r) => setTimeout(r, 250));
+      await new Promise((r) => setTimeout(r, 50));
 
       // Capture screenshot
       const capture = await invoke<{
@@ -116,42 +162,17 @@ export function useChartAnalyzer() {
       // Show HUD again immediately after capture
       await invoke("show_window", {
         position: windowConfig.position,
-        monitorIndex: windowConfig.monitorIndex,
+        monitorId: windowConfig.monitorId,
+        dockHeight: windowConfig.dockHeight,
       });
 
-      capturedImage.value = capture.image_base64;
-      status.value = "Analyzing chart...";
-
-      // Send to local AI for analysis
-      const response = await invoke<string>("analyze_chart", {
-        imageBase64: capture.image_base64,
-        analysisTypes,
+      await runAnalysis(
+        capture.image_base64,
         provider,
         baseUrl,
         model,
-      });
-
-      rawResponse.value = response;
-
-      // Parse JSON from response
-      try {
-        let jsonStr = response;
-        const fenceMatch = jsonStr.match(/```(?:json)?\s*([\s\S]*?)```/);
-        if (fenceMatch) {
-          jsonStr = fenceMatch[1];
-        }
-        const jsonMatch = jsonStr.match(/\{[\s\S]*\}/);
-        if (jsonMatch) {
-          const parsed = JSON.parse(jsonMatch[0]);
-          result.value = parsed;
-          addToHistory(parsed, capturedImage.value || undefined);
-          status.value = "Analysis complete";
-        } else {
-          status.value = "Could not parse response";
-        }
-      } catch {
-        status.value = "Could not parse response";
-      }
+        analysisTypes,
+      );
     } catch (e: any) {
       const msg = e.message || String(e);
       status.value = msg.length > 80 ? msg.slice(0, 80) + "..." : msg;
@@ -160,7 +181,8 @@ export function useChartAnalyzer() {
         const { invoke } = await import("@tauri-apps/api/core");
         await invoke("show_window", {
           position: windowConfig.position,
-          monitorIndex: windowConfig.monitorIndex,
+          monitorId: windowConfig.monitorId,
+          dockHeight: windowConfig.dockHeight,
         });
       } catch {
         /* ignore */
@@ -170,6 +192,54 @@ export function useChartAnalyzer() {
     }
   }
 
+  // Analyze an image from the clipboard instead of capturing the screen
+  async function pasteAndAnalyze(
+    provider: string,
+    baseUrl: string,
+    model: string,
+    analysisTypes: string[],
+  ) {
+    if (isAnalyzing.value) return;
+
+    isAnalyzing.value = true;
+    status.value = "Reading clipboard...";
+    result.value = null;
+    rawResponse.value = "";
+
+    try {
+      const isTauri =
+        typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+      if (!isTauri) {
+        status.value = "Only works in Tauri";
+        return;
+      }
+
+      const { invoke } = await import("@tauri-apps/api/core");
+      const pasted = await invoke<{
+        image_base64: string;
+        width: number;
+        height: number;
+      } | null>("paste_clipboard_image");
+      if (!pasted) {
+        status.value = "No image on the clipboard";
+        return;
+      }
+
+      await runAnalysis(
+        pasted.image_base64,
+        provider,
+        baseUrl,
+        model,
+        analysisTypes,
+      );
+    } catch (e: any) {
+      const msg = e.message || String(e);
+      status.value = msg.length > 80 ? msg.slice(0, 80) + "..." : msg;
+    } finally {
+      isAnalyzing.value = false;
+    }
+  }
+
   function clearResults() {
     result.value = null;
     rawResponse.value = "";
@@ -188,6 +258,7 @@ export function useChartAnalyzer() {
     capturedImage,
     history,
     captureAndAnalyze,
+    pasteAndAnalyze,
     clearResults,
     deleteHistoryEntry,
     clearHistory,
@@ -1,122 +1,111 @@
 export interface ClipboardEntry {
   id: string;
-  text: string;
+  kind: "text" | "image" | "files";
+  text?: string;
+  file?: string;
+  width?: number;
+  height?: number;
+  paths?: string[];
+  hash: string;
   timestamp: number;
+  first_copied: number;
+  copy_count: number;
+  pinned: boolean;
 }
 
-const CLIPBOARD_KEY = "quanthud_clipboard";
-const MAX_ENTRIES = 50;
+export interface ClipboardSettings {
+  enabled: boolean;
+  capture_images: boolean;
+  max_entries: number;
+  max_images: number;
+  skip_secrets: boolean;
+  exclude_patterns: string[];
+}
+
+export type TransformStep =
+  | { op: "normalize" }
+  | {
+      op: "round_tick";
+      tick?: number;
+      symbol?: string;
+      mode?: "nearest" | "down" | "up";
+    }
+  | { op: "convert"; to: string; from?: string }
+  | {
+      op: "format";
+      locale: "plain" | "en" | "de" | "fr" | "ch";
+      decimals?: number;
+      currency?: boolean;
+    };
 
-function generateId(): string {
-  return Date.now().toString(36) + Math.random().toString(36).slice(2, 8);
+export interface TransformSettings {
+  /** Value of one unit in USD */
+  rates: Record<string, number>;
+  /** Tick size per symbol */
+  ticks: Record<string, number>;
+  decimal_mark: "." | "," | null;
+  presets: Record<string, TransformStep[]>;
+  /** Preset applied automatically to copied numbers */
+  auto_apply: string | null;
 }
 
-// ── Shared singleton state so polling survives component unmount ──
+export interface TransformResult {
+  input: string;
+  output: string;
+  value: number;
+  currency: string | null;
+}
+
+// ── Shared singleton state; the clipboard is watched by the Rust side ──
 const _entries = ref<ClipboardEntry[]>([]);
-let _pollInterval: ReturnType<typeof setInterval> | null = null;
-let _lastClipText = "";
+let _unlisten: (() => void) | null = null;
 let _initialized = false;
 
-async function _load() {
-  try {
-    if (typeof window !== "undefined" && (window as any).__TAURI__) {
-      const { invoke } = await import("@tauri-apps/api/core");
-      const saved = await invoke<string>("load_config");
-      if (saved) {
-        const config = JSON.parse(saved);
-        if (config._clipboardHistory) {
-          _entries.value = config._clipboardHistory;
-          return;
-        }
-      }
-    }
-    const saved = localStorage.getItem(CLIPBOARD_KEY);
-    if (saved) _entries.value = JSON.parse(saved);
-  } catch (e) {
-    console.warn("Failed to load clipboard history:", e);
-  }
+function _isTauri() {
+  return typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
 }
 
-async function _save() {
+async function _load() {
   try {
-    if (typeof window !== "undefined" && (window as any).__TAURI__) {
-      const { invoke } = await import("@tauri-apps/api/core");
-      const raw = await invoke<string>("load_config");
-      const config = raw ? JSON.parse(raw) : {};
-      config._clipboardHistory = _entries.value;
-      await invoke("save_config", { config: JSON.stringify(config) });
-      emitSync("clipboard");
-    } else {
-      localStorage.setItem(CLIPBOARD_KEY, JSON.stringify(_entries.value));
-    }
+    const { invoke } = await import("@tauri-apps/api/core");
+    _entries.value = await invoke<ClipboardEntry[]>("list_clipboard_history");
   } catch (e) {
-    console.warn("Failed to save clipboard history:", e);
-  }
-}
-
-async function _readClipboard(): Promise<string> {
-  try {
-    if (typeof window !== "undefined" && (window as any).__TAURI__) {
-      const { readText } = await import("@tauri-apps/plugin-clipboard-manager");
-      return (await readText()) || "";
-    }
-    return await navigator.clipboard.readText();
-  } catch {
-    return "";
-  }
-}
-
-async function _pollClipboard() {
-  const text = await _readClipboard();
-  if (text && text !== _lastClipText) {
-    _lastClipText = text;
-    // Check if this text already exists anywhere in the history
-    const existingIndex = _entries.value.findIndex((e) => e.text === text);
-    if (existingIndex === 0) {
-      // Already at the top, nothing to do
-      return;
-    }
-    if (existingIndex > 0) {
-      // Remove from old position and move to top with updated timestamp
-      _entries.value.splice(existingIndex, 1);
-    }
-    _entries.value.unshift({ id: generateId(), text, timestamp: Date.now() });
-    if (_entries.value.length > MAX_ENTRIES) _entries.value.pop();
-    _save();
+    console.warn("Failed to load clipboard history:", e);
   }
 }
 
-let _syncCleanup: (() => void) | null = null;
-
 /** Call once at app startup (e.g. in index.vue onMounted) */
-export async function initClipboardPolling() {
-  if (_initialized) return;
+export async function initClipboardHistory() {
+  if (_initialized || !_isTauri()) return;
   _initialized = true;
   await _load();
-  _lastClipText = await _readClipboard();
-  if (!_pollInterval) {
-    _pollInterval = setInterval(_pollClipboard, 1500);
-  }
-  _syncCleanup = await onSyncEvent("clipboard", _load);
+  const { listen } = await import("@tauri-apps/api/event");
+  _unlisten = await listen<ClipboardEntry[]>("clipboard-history", (event) => {
+    _entries.value = event.payload;
+  });
 }
 
 /** Call on app teardown if needed */
-export function disposeClipboardPolling() {
-  if (_pollInterval) {
-    clearInterval(_pollInterval);
-    _pollInterval = null;
-  }
-  _syncCleanup?.();
-  _syncCleanup = null;
+export function disposeClipboardHistory() {
+  _unlisten?.();
+  _unlisten = null;
   _initialized = false;
 }
 
 export function useClipboardHistory() {
+  async function invokeSafe<T>(cmd: string, args?: Record<string, unknown>) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      return await invoke<T>(cmd, args);
+    } catch (e) {
+      console.warn(`${cmd} failed:`, e);
+      return null;
+    }
+  }
+
   async function writeClipboard(text: string) {
     try {
-      // Set _lastClipText BEFORE writing so the poll won't re-detect it as new
-      _lastClipText = text;
-      if (typeof window !== "undefined" && (window as any).__TAURI__) {
+      if (_isTauri()) {
         const { writeText } =
           await import("@tauri-apps/plugin-clipboard-manager");
         await writeText(text);
@@ -128,20 +117,69 @@ export function useClipboardHistory() {
     }
   }
 
-  function removeEntry(id: string) {
-    _entries.value = _entries.value.filter((e) => e.id !== id);
-    _save();
+  /** Put any entry (text, image or files) back on the clipboard */
+  async function copyEntry(id: string) {
+    await invokeSafe("copy_clipboard_entry", { id });
+  }
+
+  /** Base64 PNG for an image entry */
+  async function readEntryImage(id: string) {
+    return invokeSafe<string>("read_clipboard_entry_image", { id });
+  }
+
+  async function setPinned(id: string, pinned: boolean) {
+    await invokeSafe("set_clipboard_entry_pinned", { id, pinned });
+  }
+
+  async function removeEntry(id: string) {
+    await invokeSafe("remove_clipboard_entry", { id });
+  }
+
+  async function clearAll(keepPinned = true) {
+    await invokeSafe("clear_clipboard_history", { keepPinned });
+  }
+
+  async function getSettings() {
+    return invokeSafe<ClipboardSettings>("get_clipboard_settings");
+  }
+
+  async function saveSettings(settings: ClipboardSettings) {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_clipboard_settings", { settings });
+  }
+
+  /** Transform a text entry with explicit steps or a saved preset and copy the result */
+  async function transformEntry(
+    id: string,
+    options: { steps?: TransformStep[]; preset?: string; copy?: boolean },
+  ) {
+    return invokeSafe<TransformResult>("transform_clipboard_entry", {
+      id,
+      ...options,
+    });
+  }
+
+  async function getTransformSettings() {
+    return invokeSafe<TransformSettings>("get_clipboard_transform_settings");
   }
 
-  function clearAll() {
-    _entries.value = [];
-    _save();
+  async function saveTransformSettings(settings: TransformSettings) {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("set_clipboard_transform_settings", { settings });
   }
 
   return {
     entries: _entries,
     writeClipboard,
+    copyEntry,
+    readEntryImage,
+    setPinned,
     removeEntry,
     clearAll,
+    getSettings,
+    saveSettings,
+    transformEntry,
+    getTransformSettings,
+    saveTransformSettings,
   };
 }
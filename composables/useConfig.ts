@@ -1,6 +1,6 @@
 import type { CalculatorInputs } from "./useCalculator";
 
-export type WindowPosition = "left" | "right" | "dual";
+export type WindowPosition = "left" | "right" | "top" | "bottom" | "dual";
 export type ColorTheme = "light" | "dark";
 export type TriggerStyle = "column" | "halfcircle";
 export type ActivationMode = "hover" | "click";
@@ -18,14 +18,35 @@ export type SpeechLanguage =
   | "zh-CN"
   | "pt-BR";
 
+export interface MonitorInfo {
+  /** Stable fingerprint (name + resolution + position) */
+  id: string;
+  index: number;
+  name: string;
+  device_name: string | null;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scale_factor: number;
+  is_primary: boolean;
+}
+
 export interface AppConfig {
   scanRegion: [number, number, number, number] | null;
   calcSettings: Partial<CalculatorInputs>;
   windowPosition: WindowPosition;
+  /** Panel height in logical px when docked to the top or bottom edge */
+  dockHeight: number;
   colorTheme: ColorTheme;
   triggerStyle: TriggerStyle;
   activationMode: ActivationMode;
+  /** Legacy enumeration index; migrated to `monitorId` once monitors are listed */
   monitorIndex: number;
+  /** Stable monitor fingerprint from `get_available_monitors` */
+  monitorId: string | null;
+  /** Module ids the main window shows; empty shows all */
+  modules: string[];
   displayMode: DisplayMode;
   screenshotsFolder: string;
   speechLanguage: SpeechLanguage;
@@ -37,23 +58,32 @@ export interface AppConfig {
 
 const CONFIG_KEY = "quanthub_config";
 
+// ── Shared per-window state, so every component edits the same config and
+// none of them saves back a stale copy ──
+const _config = ref<AppConfig>({
+  scanRegion: null,
+  calcSettings: {},
+  windowPosition: "left",
+  dockHeight: 280,
+  colorTheme: "dark",
+  triggerStyle: "halfcircle",
+  activationMode: "hover",
+  monitorIndex: 0,
+  monitorId: null,
+  modules: [],
+  displayMode: "basic",
+  screenshotsFolder: "",
+  speechLanguage: "system",
+  aiProvider: "ollama",
+  aiBaseUrl: "http://localhost:11434",
+  aiModel: "llava",
+  chartAnalyzerRegion: null,
+});
+let _users = 0;
+let _syncCleanup: (() => void) | null = null;
+
 export function useConfig() {
-  const config = ref<AppConfig>({
-    scanRegion: null,
-    calcSettings: {},
-    windowPosition: "left",
-    colorTheme: "dark",
-    triggerStyle: "halfcircle",
-    activationMode: "hover",
-    monitorIndex: 0,
-    displayMode: "basic",
-    screenshotsFolder: "",
-    speechLanguage: "system",
-    aiProvider: "ollama",
-    aiBaseUrl: "http://localhost:11434",
-    aiModel: "llava",
-    chartAnalyzerRegion: null,
-  });
+  const config = _config;
 
   async function loadConfig() {
     try {
@@ -107,6 +137,11 @@ export function useConfig() {
     saveConfig();
   }
 
+  function setDockHeight(height: number) {
+    config.value.dockHeight = height;
+    saveConfig();
+  }
+
   function setColorTheme(theme: ColorTheme) {
     config.value.colorTheme = theme;
     saveConfig();
@@ -122,8 +157,13 @@ export function useConfig() {
     saveConfig();
   }
 
-  function setMonitorIndex(index: number) {
-    config.value.monitorIndex = index;
+  function setModules(modules: string[]) {
+    config.value.modules = modules;
+    saveConfig();
+  }
+
+  function setMonitorId(id: string | null) {
+    config.value.monitorId = id;
     saveConfig();
   }
 
@@ -164,16 +204,24 @@ export function useConfig() {
     saveConfig();
   }
 
-  let _syncCleanup: (() => void) | null = null;
-
-  // Load on init
+  // Load on first use; other windows' saves arrive through the sync event
   onMounted(async () => {
+    _users += 1;
+    if (_users > 1) return;
     await loadConfig();
-    _syncCleanup = await onSyncEvent("config", loadConfig);
+    const cleanup = await onSyncEvent("config", loadConfig);
+    if (_users > 0 && !_syncCleanup) {
+      _syncCleanup = cleanup;
+    } else {
+      cleanup();
+    }
   });
 
   onUnmounted(() => {
+    _users -= 1;
+    if (_users > 0) return;
     _syncCleanup?.();
+    _syncCleanup = null;
   });
 
   return {
@@ -183,10 +231,12 @@ export function useConfig() {
     setScanRegion,
     setCalcSettings,
     setWindowPosition,
+    setDockHeight,
     setColorTheme,
     setTriggerStyle,
     setActivationMode,
-    setMonitorIndex,
+    setMonitorId,
+    setModules,
     setDisplayMode,
     setScreenshotsFolder,
     setSpeechLanguage,
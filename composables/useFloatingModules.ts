@@ -0,0 +1,100 @@
+/** Window state of a module popped out of the HUD (mirrors floating.rs) */
+export interface FloatingModule {
+  bounds: { x: number; y: number; width: number; height: number } | null;
+  click_through: boolean;
+  /** 0.2–1.0 */
+  opacity: number;
+  open: boolean;
+}
+
+/** Modules that can be popped out into their own window */
+export const FLOATABLE_MODULES: { id: string; label: string }[] = [
+  { id: "position-calc", label: "Position Sizer" },
+  { id: "worldclock", label: "Clock" },
+  { id: "notes", label: "Notes" },
+  { id: "todos", label: "Todo List" },
+  { id: "calendar", label: "Calendar" },
+  { id: "gen-calc", label: "Calculator" },
+  { id: "colorpicker", label: "Color Picker" },
+  { id: "clipboard", label: "Clipboard" },
+  { id: "transcript", label: "Transcript" },
+  { id: "shortcuts", label: "Shortcuts" },
+];
+
+export function isFloatable(id: string) {
+  return FLOATABLE_MODULES.some((m) => m.id === id);
+}
+
+/**
+ * Floating module windows. Rust keeps position, size, click-through and
+ * opacity per module; every window follows `floating-modules-changed`.
+ */
+export function useFloatingModules() {
+  const modules = ref<Record<string, FloatingModule>>({});
+  let unlisten: (() => void) | null = null;
+  const isTauri =
+    typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+  async function invokeSafe<T>(cmd: string, args?: Record<string, unknown>) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      return await invoke<T>(cmd, args);
+    } catch (e) {
+      console.warn(`${cmd} failed:`, e);
+      return null;
+    }
+  }
+
+  async function init() {
+    if (!isTauri) return;
+    modules.value =
+      (await invokeSafe<Record<string, FloatingModule>>(
+        "list_floating_modules",
+      )) ?? {};
+    const { listen } = await import("@tauri-apps/api/event");
+    unlisten = await listen<Record<string, FloatingModule>>(
+      "floating-modules-changed",
+      (e) => {
+        modules.value = e.payload;
+      },
+    );
+  }
+
+  /** Modules with an open floating window */
+  const openModules = computed(() =>
+    FLOATABLE_MODULES.filter((m) => modules.value[m.id]?.open),
+  );
+
+  async function popOut(moduleId: string) {
+    await invokeSafe("open_floating_module", { moduleId });
+  }
+
+  async function close(moduleId: string) {
+    await invokeSafe("close_floating_module", { moduleId });
+  }
+
+  async function setClickThrough(moduleId: string, enabled: boolean) {
+    await invokeSafe("set_floating_click_through", { moduleId, enabled });
+  }
+
+  async function setOpacity(moduleId: string, opacity: number) {
+    const current = modules.value[moduleId];
+    if (current) current.opacity = opacity;
+    await invokeSafe("set_floating_opacity", { moduleId, opacity });
+  }
+
+  onUnmounted(() => {
+    unlisten?.();
+    unlisten = null;
+  });
+
+  return {
+    modules,
+    openModules,
+    init,
+    popOut,
+    close,
+    setClickThrough,
+    setOpacity,
+  };
+}
@@ -0,0 +1,86 @@
+/** Mirrors MIN_CONTENT_WIDTH / MAX_CONTENT_WIDTH in layout.rs */
+export const MIN_HUD_WIDTH = 280;
+export const MAX_HUD_WIDTH = 720;
+
+/**
+ * Panel width of the current HUD window. The width is stored per window on
+ * the Rust side, which also resizes the window; dragging the inner edge
+ * updates it live and persists on release.
+ */
+export function useHudResize(options: { position: Ref<string> }) {
+  const width = ref(320);
+  const isResizing = ref(false);
+  const isTauri =
+    typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+  function clamp(value: number) {
+    return Math.round(Math.min(MAX_HUD_WIDTH, Math.max(MIN_HUD_WIDTH, value)));
+  }
+
+  async function loadWidth() {
+    if (!isTauri) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      width.value = await invoke<number>("get_hud_width");
+    } catch (e) {
+      console.warn("Failed to load HUD width:", e);
+    }
+  }
+
+  async function setWidth(value: number, persist = true) {
+    width.value = clamp(value);
+    if (!isTauri) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      width.value = await invoke<number>("set_hud_width", {
+        width: width.value,
+        persist,
+      });
+    } catch (e) {
+      console.warn("Failed to set HUD width:", e);
+    }
+  }
+
+  /** pointerdown handler for the resize handle on the panel's inner edge */
+  function startResize(e: PointerEvent) {
+    const handle = e.currentTarget as HTMLElement;
+    handle.setPointerCapture(e.pointerId);
+    const startX = e.screenX;
+    const startWidth = width.value;
+    // Docked right, the inner edge is on the left: dragging left grows the panel
+    const direction = options.position.value === "right" ? -1 : 1;
+    let pending = startWidth;
+    let frame = 0;
+    isResizing.value = true;
+
+    function onMove(ev: PointerEvent) {
+      pending = clamp(startWidth + direction * (ev.screenX - startX));
+      if (frame) return;
+      frame = requestAnimationFrame(() => {
+        frame = 0;
+        setWidth(pending, false);
+      });
+    }
+
+    function onUp() {
+      handle.removeEventListener("pointermove", onMove);
+      handle.removeEventListener("pointerup", onUp);
+      handle.removeEventListener("pointercancel", onUp);
+      cancelAnimationFrame(frame);
+      isResizing.value = false;
+      setWidth(pending, true);
+    }
+
+    handle.addEventListener("pointermove", onMove);
+    handle.addEventListener("pointerup", onUp);
+    handle.addEventListener("pointercancel", onUp);
+  }
+
+  return {
+    width,
+    isResizing,
+    loadWidth,
+    setWidth,
+    startResize,
+  };
+}
@@ -0,0 +1,82 @@
+import type { WindowPosition } from "./useConfig";
+
+export interface HudWindowConfig {
+  label: string;
+  placement: Exclude<WindowPosition, "dual"> | "floating";
+  monitor_id: string | null;
+  /** Module ids shown in this window; empty shows all */
+  modules: string[];
+}
+
+/**
+ * Additional HUD windows. The main window keeps its settings in the app
+ * config; every other window reads its own entry via `current`.
+ */
+export function useHudWindows() {
+  const windows = ref<HudWindowConfig[]>([]);
+  /** This window's settings; null for the main and dual-mode windows */
+  const current = ref<HudWindowConfig | null>(null);
+  let unlisten: (() => void) | null = null;
+  const isTauri =
+    typeof window !== "undefined" && "__TAURI_INTERNALS__" in window;
+
+  async function invokeSafe<T>(cmd: string, args?: Record<string, unknown>) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      return await invoke<T>(cmd, args);
+    } catch (e) {
+      console.warn(`${cmd} failed:`, e);
+      return null;
+    }
+  }
+
+  /** Load this window's settings and the window list, then follow changes */
+  async function init() {
+    if (!isTauri) return;
+    current.value = await invokeSafe<HudWindowConfig>("get_hud_window");
+    windows.value = (await invokeSafe<HudWindowConfig[]>("list_hud_windows")) ?? [];
+    const { listen } = await import("@tauri-apps/api/event");
+    unlisten = await listen<HudWindowConfig[]>("hud-windows-changed", (e) => {
+      windows.value = e.payload;
+      if (!current.value) return;
+      const label = current.value.label;
+      current.value = e.payload.find((w) => w.label === label) ?? null;
+    });
+  }
+
+  async function createWindow(
+    placement: HudWindowConfig["placement"],
+    monitorId: string | null,
+    modules: string[] = [],
+  ) {
+    return invokeSafe<HudWindowConfig>("create_hud_window", {
+      placement,
+      monitorId,
+      modules,
+    });
+  }
+
+  async function updateWindow(config: HudWindowConfig) {
+    // Apply locally first so placement calls right after see the change
+    if (current.value?.label === config.label) current.value = config;
+    await invokeSafe("update_hud_window", { config });
+  }
+
+  async function closeWindow(label: string) {
+    await invokeSafe("close_hud_window", { label });
+  }
+
+  onUnmounted(() => {
+    unlisten?.();
+    unlisten = null;
+  });
+
+  return {
+    windows,
+    current,
+    init,
+    createWindow,
+    updateWindow,
+    closeWindow,
+  };
+}
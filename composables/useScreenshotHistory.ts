@@ -1,14 +1,72 @@
-export interface ScreenshotEntry {
+export interface ScreenshotMeta {
+  tags: string[];
+  symbol: string | null;
+  timeframe: string | null;
+  notes: string;
+  ocr_text: string | null;
+}
+
+export interface ScreenshotEntry extends ScreenshotMeta {
   path: string;
   filename: string;
   modified: number; // unix seconds
   image_base64?: string; // loaded on demand
 }
 
+export interface ScreenshotQuery {
+  text?: string;
+  tags?: string[];
+  symbol?: string | null;
+  timeframe?: string | null;
+}
+
+interface ScreenshotPage {
+  total: number;
+  offset: number;
+  entries: ScreenshotEntry[];
+}
+
+/** A file the library watcher or a capture reported */
+interface AddedScreenshot {
+  path: string;
+  filename: string;
+  modified: number;
+}
+
+const PAGE_SIZE = 50;
+
+/** Library order: newest first, then by path */
+function compareEntries(a: { path: string; modified: number }, b: { path: string; modified: number }) {
+  return b.modified - a.modified || (a.path < b.path ? -1 : a.path > b.path ? 1 : 0);
+}
+
 export function useScreenshotHistory(customFolder?: Ref<string>) {
   const entries = ref<ScreenshotEntry[]>([]);
   const loading = ref(false);
+  const loadingMore = ref(false);
+  const total = ref(0);
+  const activeQuery = ref<ScreenshotQuery | null>(null);
+  const tagCounts = ref<Record<string, number>>({});
+  const hasMore = computed(() => entries.value.length < total.value);
+
+  async function fetchPage(offset: number): Promise<ScreenshotPage> {
+    const { invoke } = await import("@tauri-apps/api/core");
+    if (activeQuery.value) {
+      return invoke<ScreenshotPage>("search_screenshots", {
+        query: activeQuery.value,
+        offset,
+        limit: PAGE_SIZE,
+      });
+    }
+    const folder = customFolder?.value || "";
+    return invoke<ScreenshotPage>("list_os_screenshots", {
+      customFolder: folder || null,
+      offset,
+      limit: PAGE_SIZE,
+    });
+  }
 
+  /** Reload from the first page */
   async function loadEntries() {
     try {
       const isTauri =
@@ -18,16 +76,9 @@ export function useScreenshotHistory(customFolder?: Ref<string>) {
         return;
       }
       loading.value = true;
-      const { invoke } = await import("@tauri-apps/api/core");
-      const folder = customFolder?.value || "";
-      const list = await invoke<
-        { path: string; filename: string; modified: number }[]
-      >("list_os_screenshots", { customFolder: folder || null });
-      entries.value = list.map((s) => ({
-        path: s.path,
-        filename: s.filename,
-        modified: s.modified,
-      }));
+      const page = await fetchPage(0);
+      entries.value = page.entries;
+      total.value = page.total;
     } catch (e) {
       console.warn("Failed to list screenshots:", e);
     } finally {
@@ -35,6 +86,53 @@ export function useScreenshotHistory(customFolder?: Ref<string>) {
     }
   }
 
+  /** Append the next page */
+  async function loadMore() {
+    if (loading.value || loadingMore.value || !hasMore.value) return;
+    try {
+      loadingMore.value = true;
+      const page = await fetchPage(entries.value.length);
+      const known = new Set(entries.value.map((e) => e.path));
+      entries.value.push(...page.entries.filter((e) => !known.has(e.path)));
+      total.value = page.total;
+    } catch (e) {
+      console.warn("Failed to load more screenshots:", e);
+    } finally {
+      loadingMore.value = false;
+    }
+  }
+
+  /** Slot new files into the loaded window without a reload */
+  function insertEntries(added: AddedScreenshot[]) {
+    // Search results depend on metadata the new files don't have yet
+    if (activeQuery.value) return;
+    for (const file of added) {
+      if (entries.value.some((e) => e.path === file.path)) continue;
+      total.value += 1;
+      const index = entries.value.findIndex((e) => compareEntries(file, e) < 0);
+      // Past the loaded window; the next page will bring it in
+      if (index === -1 && hasMore.value) continue;
+      const entry: ScreenshotEntry = {
+        path: file.path,
+        filename: file.filename,
+        modified: file.modified,
+        tags: [],
+        symbol: null,
+        timeframe: null,
+        notes: "",
+        ocr_text: null,
+      };
+      entries.value.splice(index === -1 ? entries.value.length : index, 0, entry);
+    }
+  }
+
+  function removeEntries(paths: string[]) {
+    const removed = new Set(paths);
+    const before = entries.value.length;
+    entries.value = entries.value.filter((e) => !removed.has(e.path));
+    total.value = Math.max(0, total.value - (before - entries.value.length));
+  }
+
   async function loadThumbnail(entry: ScreenshotEntry) {
     if (entry.image_base64) return;
     try {
@@ -71,6 +169,50 @@ export function useScreenshotHistory(customFolder?: Ref<string>) {
     }
   }
 
+  /** Page through search results; `null` goes back to the full list */
+  async function search(query: ScreenshotQuery | null) {
+    activeQuery.value = query;
+    await loadEntries();
+  }
+
+  /** Every tag in use with its screenshot count */
+  async function loadTags() {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      tagCounts.value = await invoke<Record<string, number>>("list_screenshot_tags");
+    } catch (e) {
+      console.warn("Failed to list screenshot tags:", e);
+    }
+  }
+
+  async function saveMeta(entry: ScreenshotEntry, meta: Partial<ScreenshotMeta>) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const saved = await invoke<ScreenshotMeta>("set_screenshot_meta", {
+        path: entry.path,
+        meta: {
+          tags: meta.tags ?? entry.tags,
+          symbol: meta.symbol ?? entry.symbol,
+          timeframe: meta.timeframe ?? entry.timeframe,
+          notes: meta.notes ?? entry.notes,
+        },
+      });
+      Object.assign(entry, saved);
+      if (meta.tags) loadTags();
+    } catch (e) {
+      console.warn("Failed to save screenshot metadata:", e);
+    }
+  }
+
+  async function runOcr(entry: ScreenshotEntry) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      entry.ocr_text = await invoke<string>("ocr_screenshot", { path: entry.path });
+    } catch (e) {
+      console.warn("Failed to OCR screenshot:", e);
+    }
+  }
+
   async function openFolder() {
     try {
       const { invoke } = await import("@tauri-apps/api/core");
@@ -81,17 +223,44 @@ export function useScreenshotHistory(customFolder?: Ref<string>) {
     }
   }
 
-  onMounted(() => {
+  const unlisteners: (() => void)[] = [];
+
+  onMounted(async () => {
     loadEntries();
+    if (typeof window !== "undefined" && "__TAURI_INTERNALS__" in window) {
+      loadTags();
+      const { listen } = await import("@tauri-apps/api/event");
+      // Screenshots taken from QuantHUD itself, plus files the library
+      // watcher sees appear or disappear in the watched folders
+      unlisteners.push(
+        await listen<AddedScreenshot>("screenshot-taken", (e) => insertEntries([e.payload])),
+        await listen<AddedScreenshot[]>("screenshot-added", (e) => insertEntries(e.payload)),
+        await listen<string[]>("screenshot-removed", (e) => removeEntries(e.payload)),
+      );
+    }
+  });
+
+  onUnmounted(() => {
+    unlisteners.forEach((unlisten) => unlisten());
   });
 
   return {
     entries,
     loading,
+    loadingMore,
+    total,
+    hasMore,
+    activeQuery,
+    tagCounts,
     loadEntries,
+    loadMore,
     loadThumbnail,
     loadFullImage,
     copyScreenshot,
+    search,
+    loadTags,
+    saveMeta,
+    runOcr,
     openFolder,
   };
 }
@@ -0,0 +1,689 @@
+use crate::capture::{self, CaptureError};
+use crate::clipboard_transform;
+use crate::config::{self, ConfigError};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AlertError {
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("OCR failed: {0}")]
+    Ocr(String),
+    #[error("Tesseract OCR not found at '{0}'. Install Tesseract or set TESSERACT_PATH to the executable")]
+    TesseractMissing(String),
+    #[error("No price found in OCR text: {0:?}")]
+    NoPrice(String),
+}
+
+/// Which crossing direction fires an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossTrigger {
+    #[default]
+    Both,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossDirection {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertLevel {
+    pub id: String,
+    /// e.g. "TP", "Fib 0.5", "Weekly high"
+    pub label: String,
+    pub price: f64,
+    #[serde(default)]
+    pub trigger: CrossTrigger,
+    /// Disarm the level after it fires once
+    #[serde(default)]
+    pub once: bool,
+}
+
+/// Configuration for watching one named capture region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceWatchConfig {
+    pub name: String,
+    /// [x, y, width, height] on the primary screen, containing the last price
+    pub region: [i32; 4],
+    pub interval_ms: u64,
+    pub levels: Vec<AlertLevel>,
+    /// Price must clear the level by this amount before a crossing counts,
+    /// so a price hovering on the level doesn't fire repeatedly
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Consecutive readings on the new side required to confirm a crossing;
+    /// filters out single OCR misreads
+    #[serde(default = "default_confirm_samples")]
+    pub confirm_samples: u32,
+    /// Minimum time between two alerts for the same level
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub sound: bool,
+    /// Decimal separator the chart shows; settles whether "3,151" is 3151 or 3.151
+    #[serde(default)]
+    pub decimal_mark: Option<char>,
+}
+
+fn default_confirm_samples() -> u32 {
+    2
+}
+
+/// Minimum polling interval so a misconfigured watch can't peg the CPU with OCR
+pub const MIN_INTERVAL_MS: u64 = 250;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    pub watch: String,
+    pub level_id: String,
+    pub label: String,
+    pub level: f64,
+    pub price: f64,
+    pub direction: CrossDirection,
+    /// Unix milliseconds
+    pub time: u64,
+}
+
+impl AlertEvent {
+    pub fn message(&self) -> String {
+        let arrow = match self.direction {
+            CrossDirection::Up => "↑",
+            CrossDirection::Down => "↓",
+        };
+        format!("{} {} {} ({}) — {}", self.watch, arrow, self.label, self.level, self.price)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Zone {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Default)]
+struct LevelState {
+    zone: Option<Zone>,
+    pending: Option<(Zone, u32)>,
+    last_fired: Option<Instant>,
+    disarmed: bool,
+}
+
+/// Crossing detection for a set of levels with hysteresis, confirmation and cooldown.
+/// Pure state machine: the caller feeds prices and timestamps.
+pub struct AlertEngine {
+    config: PriceWatchConfig,
+    states: Vec<LevelState>,
+}
+
+impl AlertEngine {
+    pub fn new(config: PriceWatchConfig) -> Self {
+        let states = config.levels.iter().map(|_| LevelState::default()).collect();
+        Self { config, states }
+    }
+
+    /// Replace the levels, keeping state for levels whose id and price are unchanged
+    pub fn set_levels(&mut self, levels: Vec<AlertLevel>) {
+        let mut old: Vec<(AlertLevel, LevelState)> = self
+            .config
+            .levels
+            .drain(..)
+            .zip(self.states.drain(..))
+            .collect();
+        for level in &levels {
+            let kept = old
+                .iter()
+                .position(|(l, _)| l.id == level.id && l.price == level.price)
+                .map(|i| old.swap_remove(i).1);
+            self.states.push(kept.unwrap_or_default());
+        }
+        self.config.levels = levels;
+    }
+
+    pub fn config(&self) -> &PriceWatchConfig {
+        &self.config
+    }
+
+    /// Feed one price reading; returns the alerts it triggers
+    pub fn update(&mut self, price: f64, now: Instant) -> Vec<AlertEvent> {
+        let h = self.config.hysteresis.abs();
+        let confirm = self.config.confirm_samples.max(1);
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        let mut events = Vec::new();
+
+        for (level, state) in self.config.levels.iter().zip(self.states.iter_mut()) {
+            let observed = if price >= level.price + h && price > level.price {
+                Zone::Above
+            } else if price <= level.price - h && price < level.price {
+                Zone::Below
+            } else {
+                // Inside the hysteresis band: no evidence either way
+                state.pending = None;
+                continue;
+            };
+
+            let Some(current) = state.zone else {
+                // First reading only establishes which side we're on
+                state.zone = Some(observed);
+                continue;
+            };
+            if observed == current {
+                state.pending = None;
+                continue;
+            }
+
+            let count = match state.pending {
+                Some((zone, n)) if zone == observed => n + 1,
+                _ => 1,
+            };
+            if count < confirm {
+                state.pending = Some((observed, count));
+                continue;
+            }
+
+            state.zone = Some(observed);
+            state.pending = None;
+
+            let direction = if observed == Zone::Above { CrossDirection::Up } else { CrossDirection::Down };
+            let wanted = match level.trigger {
+                CrossTrigger::Both => true,
+                CrossTrigger::Up => direction == CrossDirection::Up,
+                CrossTrigger::Down => direction == CrossDirection::Down,
+            };
+            let cooled = state.last_fired.is_none_or(|t| now.duration_since(t) >= cooldown);
+            if !wanted || !cooled || state.disarmed {
+                continue;
+            }
+
+            state.last_fired = Some(now);
+            state.disarmed = level.once;
+            events.push(AlertEvent {
+                watch: self.config.name.clone(),
+                level_id: level.id.clone(),
+                label: level.label.clone(),
+                level: level.price,
+                price,
+                direction,
+                time: config::unix_millis(),
+            });
+        }
+        events
+    }
+}
+
+/// Source of region images; the screen in production, replayed fixtures in tests
+pub trait FrameSource: Send {
+    fn capture(&mut self, region: [i32; 4]) -> Result<DynamicImage, AlertError>;
+}
+
+pub struct ScreenSource;
+
+impl FrameSource for ScreenSource {
+    fn capture(&mut self, region: [i32; 4]) -> Result<DynamicImage, AlertError> {
+        Ok(capture::capture_image(Some(region))?)
+    }
+}
+
+/// Turns a captured image into a price
+pub trait PriceReader: Send {
+    fn read_price(&mut self, image: &DynamicImage) -> Result<f64, AlertError>;
+}
+
+/// Numbers scratch images so two watches (or two app instances) never overwrite each other's
+static SCRATCH_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// OCR through the Tesseract CLI (the same install the Position Sizer docs require).
+/// Uses `TESSERACT_PATH` if set, otherwise `tesseract` on PATH; see
+/// [`check_tesseract`] to fail early when neither exists.
+pub struct TesseractReader {
+    scratch: PathBuf,
+    decimal_mark: Option<char>,
+}
+
+impl TesseractReader {
+    pub fn new(decimal_mark: Option<char>) -> Self {
+        let n = SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self {
+            scratch: std::env::temp_dir().join(format!("quanthud_alert_{}_{}.png", std::process::id(), n)),
+            decimal_mark,
+        }
+    }
+
+    fn binary() -> PathBuf {
+        if let Ok(path) = std::env::var("TESSERACT_PATH") {
+            return PathBuf::from(path);
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let default = PathBuf::from(r"C:\Program Files\Tesseract-OCR\tesseract.exe");
+            if default.exists() {
+                return default;
+            }
+        }
+        PathBuf::from("tesseract")
+    }
+}
+
+impl Default for TesseractReader {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Drop for TesseractReader {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.scratch);
+    }
+}
+
+impl PriceReader for TesseractReader {
+    fn read_price(&mut self, image: &DynamicImage) -> Result<f64, AlertError> {
+        // Upscaled grayscale reads small chart labels far more reliably
+        let prepared = image
+            .grayscale()
+            .resize(image.width() * 3, image.height() * 3, image::imageops::FilterType::CatmullRom);
+        prepared
+            .save(&self.scratch)
+            .map_err(|e| AlertError::Ocr(e.to_string()))?;
+
+        let text = tesseract_text(&self.scratch, &["--psm", "7", "-c", "tessedit_char_whitelist=0123456789.,-"])?;
+        extract_last_price(&text, self.decimal_mark).ok_or_else(|| AlertError::NoPrice(text.trim().to_string()))
+    }
+}
+
+fn tesseract_command() -> Command {
+    let cmd = Command::new(TesseractReader::binary());
+    #[cfg(target_os = "windows")]
+    let cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = cmd;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    cmd
+}
+
+fn spawn_error(e: std::io::Error) -> AlertError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AlertError::TesseractMissing(TesseractReader::binary().display().to_string())
+    } else {
+        AlertError::Ocr(format!("Failed to run tesseract: {}", e))
+    }
+}
+
+/// Check that the Tesseract CLI can be run at all
+pub fn check_tesseract() -> Result<(), AlertError> {
+    let output = tesseract_command().arg("--version").output().map_err(spawn_error)?;
+    if !output.status.success() {
+        return Err(AlertError::Ocr(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(())
+}
+
+/// Run the Tesseract CLI on an image file and return the recognized text
+pub fn tesseract_text(image_path: &Path, args: &[&str]) -> Result<String, AlertError> {
+    let output = tesseract_command()
+        .arg(image_path)
+        .arg("stdout")
+        .args(args)
+        .output()
+        .map_err(spawn_error)?;
+    if !output.status.success() {
+        return Err(AlertError::Ocr(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Pick the last number in OCR output, e.g. "BTCUSDT 3,151.25" -> 3151.25.
+/// Separators are read like clipboard values, so "3.151,25" is 3151.25 too.
+pub fn extract_last_price(text: &str, decimal_mark: Option<char>) -> Option<f64> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .filter(|tok| tok.chars().any(|c| c.is_ascii_digit()))
+        .filter_map(|tok| clipboard_transform::parse_digits(tok.trim_matches(['.', ',']), decimal_mark))
+        .map(|(value, _)| value)
+        .rfind(|v| *v > 0.0)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WatchStatus {
+    pub name: String,
+    pub running: bool,
+    pub last_price: Option<f64>,
+    /// Unix milliseconds of the last successful reading
+    pub last_read: Option<u64>,
+    pub last_error: Option<String>,
+    pub alerts_fired: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchInfo {
+    pub config: PriceWatchConfig,
+    pub status: WatchStatus,
+}
+
+/// Handle to a running watch thread
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    engine: Arc<Mutex<AlertEngine>>,
+    status: Arc<Mutex<WatchStatus>>,
+}
+
+impl WatchHandle {
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    pub fn status(&self) -> WatchStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn config(&self) -> PriceWatchConfig {
+        self.engine.lock().unwrap().config().clone()
+    }
+
+    pub fn info(&self) -> WatchInfo {
+        WatchInfo {
+            config: self.config(),
+            status: self.status(),
+        }
+    }
+
+    pub fn set_levels(&self, levels: Vec<AlertLevel>) {
+        self.engine.lock().unwrap().set_levels(levels);
+    }
+}
+
+/// Start polling `source` on a background thread. `on_alert` runs on that
+/// thread for each fired alert; `on_status` after every reading.
+pub fn spawn_watch<S, R>(
+    config: PriceWatchConfig,
+    mut source: S,
+    mut reader: R,
+    on_alert: impl Fn(&AlertEvent, bool) + Send + 'static,
+    on_status: impl Fn(&WatchStatus) + Send + 'static,
+) -> WatchHandle
+where
+    S: FrameSource + 'static,
+    R: PriceReader + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let status = Arc::new(Mutex::new(WatchStatus {
+        name: config.name.clone(),
+        running: true,
+        ..Default::default()
+    }));
+    let engine = Arc::new(Mutex::new(AlertEngine::new(config)));
+
+    let handle = WatchHandle {
+        stop: stop.clone(),
+        engine: engine.clone(),
+        status: status.clone(),
+    };
+
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            let (region, interval, sound) = {
+                let engine = engine.lock().unwrap();
+                let c = engine.config();
+                (c.region, c.interval_ms.max(MIN_INTERVAL_MS), c.sound)
+            };
+
+            let reading = source.capture(region).and_then(|img| reader.read_price(&img));
+            let snapshot = {
+                let mut st = status.lock().unwrap();
+                match reading {
+                    Ok(price) => {
+                        let events = engine.lock().unwrap().update(price, Instant::now());
+                        st.last_price = Some(price);
+                        st.last_read = Some(config::unix_millis());
+                        st.last_error = None;
+                        st.alerts_fired += events.len() as u32;
+                        for event in &events {
+                            on_alert(event, sound);
+                        }
+                    }
+                    Err(e) => st.last_error = Some(e.to_string()),
+                }
+                st.clone()
+            };
+            on_status(&snapshot);
+
+            // Sleep in short slices so stop requests are honoured promptly
+            let deadline = started + Duration::from_millis(interval);
+            while !stop.load(Ordering::SeqCst) && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(50).min(deadline.saturating_duration_since(Instant::now())));
+            }
+        }
+        status.lock().unwrap().running = false;
+    });
+
+    handle
+}
+
+fn watches_path() -> Result<PathBuf, AlertError> {
+    Ok(config::get_app_dir()?.join("price_watches.json"))
+}
+
+/// Load the watches that were running when the app last saved them
+pub fn load_watches() -> Result<Vec<PriceWatchConfig>, AlertError> {
+    let path = watches_path()?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_watches(watches: &[PriceWatchConfig]) -> Result<(), AlertError> {
+    fs::write(watches_path()?, serde_json::to_string_pretty(watches)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use std::collections::VecDeque;
+    use std::sync::mpsc;
+
+    fn level(id: &str, price: f64, trigger: CrossTrigger, once: bool) -> AlertLevel {
+        AlertLevel { id: id.to_string(), label: id.to_uppercase(), price, trigger, once }
+    }
+
+    fn watch(levels: Vec<AlertLevel>, hysteresis: f64, confirm_samples: u32, cooldown_secs: u64) -> PriceWatchConfig {
+        PriceWatchConfig {
+            name: "BTC".to_string(),
+            region: [0, 0, 80, 20],
+            interval_ms: 0,
+            levels,
+            hysteresis,
+            confirm_samples,
+            cooldown_secs,
+            sound: false,
+            decimal_mark: None,
+        }
+    }
+
+    /// Feed prices one second apart and collect (level_id, direction) per reading
+    fn run(engine: &mut AlertEngine, prices: &[f64]) -> Vec<Vec<(String, CrossDirection)>> {
+        let start = Instant::now();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                engine
+                    .update(*price, start + Duration::from_secs(i as u64))
+                    .into_iter()
+                    .map(|e| (e.level_id, e.direction))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn fired(readings: &[Vec<(String, CrossDirection)>]) -> Vec<(usize, CrossDirection)> {
+        readings
+            .iter()
+            .enumerate()
+            .flat_map(|(i, events)| events.iter().map(move |(_, d)| (i, *d)))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_last_price_from_ocr_text() {
+        let cases = [
+            ("3151.25", Some(3151.25)),
+            ("BTCUSDT 3,151.25\n", Some(3151.25)),
+            ("64,210.5 -0.42% 64,198.0", Some(64198.0)),
+            ("1,234,567", Some(1234567.0)),
+            (".5123.", Some(5123.0)),
+            ("0.00001234", Some(0.00001234)),
+            ("0 0.0", None),
+            ("no digits here", None),
+            ("", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(extract_last_price(text, None), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn extracts_comma_decimal_prices() {
+        let cases = [
+            ("59300,5", None, Some(59300.5)),
+            ("3.151,25", None, Some(3151.25)),
+            ("BTC 1.234.567", None, Some(1234567.0)),
+            ("3,151", None, Some(3151.0)),
+            ("3,151", Some(','), Some(3.151)),
+            ("3.151", Some(','), Some(3151.0)),
+            // Misread grouping is skipped, not glued together
+            ("64,21.5", None, None),
+        ];
+        for (text, mark, expected) in cases {
+            assert_eq!(extract_last_price(text, mark), expected, "{text:?} {mark:?}");
+        }
+    }
+
+    #[test]
+    fn first_reading_only_sets_the_side() {
+        let mut engine = AlertEngine::new(watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 0.0, 1, 0));
+        let readings = run(&mut engine, &[105.0, 106.0, 95.0]);
+        assert_eq!(fired(&readings), vec![(2, CrossDirection::Down)]);
+    }
+
+    #[test]
+    fn hysteresis_ignores_prices_hovering_on_the_level() {
+        let mut engine = AlertEngine::new(watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 1.0, 1, 0));
+        let readings = run(&mut engine, &[98.0, 100.5, 99.5, 100.9, 101.0, 99.2, 98.9]);
+        assert_eq!(fired(&readings), vec![(4, CrossDirection::Up), (6, CrossDirection::Down)]);
+    }
+
+    #[test]
+    fn confirmation_filters_single_misreads() {
+        let mut engine = AlertEngine::new(watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 0.0, 3, 0));
+        // One misread (1000 instead of 99), then a real crossing held for three readings
+        let readings = run(&mut engine, &[99.0, 1000.0, 99.0, 101.0, 102.0, 99.5, 103.0, 104.0, 105.0]);
+        assert_eq!(fired(&readings), vec![(8, CrossDirection::Up)]);
+    }
+
+    #[test]
+    fn cooldown_suppresses_repeat_alerts_but_tracks_the_side() {
+        let mut engine = AlertEngine::new(watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 0.0, 1, 5));
+        let readings = run(&mut engine, &[99.0, 101.0, 99.0, 101.0, 101.0, 101.0, 101.0, 99.0]);
+        // t=1 fires, t=2 and t=3 fall inside the 5 s cooldown, t=7 is past it
+        assert_eq!(fired(&readings), vec![(1, CrossDirection::Up), (7, CrossDirection::Down)]);
+    }
+
+    #[test]
+    fn trigger_direction_and_once() {
+        let mut engine = AlertEngine::new(watch(
+            vec![level("up", 100.0, CrossTrigger::Up, false), level("once", 100.0, CrossTrigger::Both, true)],
+            0.0,
+            1,
+            0,
+        ));
+        let readings = run(&mut engine, &[99.0, 101.0, 99.0, 101.0]);
+        let ids: Vec<Vec<&str>> = readings.iter().map(|r| r.iter().map(|(id, _)| id.as_str()).collect()).collect();
+        assert_eq!(ids, vec![vec![], vec!["up", "once"], vec![], vec!["up"]]);
+    }
+
+    #[test]
+    fn set_levels_keeps_state_of_unchanged_levels() {
+        let mut engine = AlertEngine::new(watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 0.0, 1, 0));
+        run(&mut engine, &[99.0]);
+        engine.set_levels(vec![level("tp", 100.0, CrossTrigger::Both, false), level("sl", 90.0, CrossTrigger::Both, false)]);
+        let readings = run(&mut engine, &[101.0]);
+        // "tp" already knew it was below; "sl" only learns its side
+        assert_eq!(readings[0], vec![("tp".to_string(), CrossDirection::Up)]);
+    }
+
+    /// Replays fixture frames, then fails like a capture would
+    struct ReplaySource(VecDeque<DynamicImage>);
+
+    impl FrameSource for ReplaySource {
+        fn capture(&mut self, _region: [i32; 4]) -> Result<DynamicImage, AlertError> {
+            self.0.pop_front().ok_or_else(|| AlertError::Ocr("no more frames".into()))
+        }
+    }
+
+    /// Reads the price a fixture frame encodes in its first pixel
+    struct PixelReader;
+
+    impl PriceReader for PixelReader {
+        fn read_price(&mut self, image: &DynamicImage) -> Result<f64, AlertError> {
+            Ok(image.to_luma8().get_pixel(0, 0)[0] as f64)
+        }
+    }
+
+    fn frame(price: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(4, 2, Luma([price])))
+    }
+
+    #[test]
+    fn watch_replays_frames_into_alerts() {
+        let config = watch(vec![level("tp", 100.0, CrossTrigger::Both, false)], 0.0, 1, 0);
+        let source = ReplaySource([95, 105, 110].into_iter().map(frame).collect());
+        let (alert_tx, alert_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel();
+        let handle = spawn_watch(
+            config,
+            source,
+            PixelReader,
+            move |event, _| alert_tx.send(event.clone()).unwrap(),
+            move |status| {
+                let _ = status_tx.send(status.clone());
+            },
+        );
+
+        let statuses: Vec<WatchStatus> = status_rx.iter().take(4).collect();
+        handle.stop();
+
+        let alerts: Vec<AlertEvent> = alert_rx.try_iter().collect();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].price, 105.0);
+        assert_eq!(alerts[0].direction, CrossDirection::Up);
+
+        assert_eq!(statuses[2].last_price, Some(110.0));
+        assert_eq!(statuses[2].alerts_fired, 1);
+        assert!(statuses[3].last_error.as_deref().is_some_and(|e| e.contains("no more frames")));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::config::{self, ConfigError};
+use crate::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Longest slide the settings allow
+const MAX_DURATION_MS: u64 = 1000;
+
+#[derive(Error, Debug)]
+pub enum AnimationError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    /// Fast start, gentle landing
+    #[default]
+    EaseOutCubic,
+    EaseInOutCubic,
+}
+
+impl Easing {
+    /// Map linear progress 0..1 to eased progress 0..1
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn default_duration_ms() -> u64 {
+    180
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationConfig {
+    /// Length of a full tuck/show slide
+    #[serde(default = "default_duration_ms")]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub easing: Easing,
+    /// Jump straight to the target instead of sliding
+    #[serde(default)]
+    pub reduced_motion: bool,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self { duration_ms: default_duration_ms(), easing: Easing::default(), reduced_motion: false }
+    }
+}
+
+impl AnimationConfig {
+    /// Slide duration, zero with reduced motion
+    pub fn duration(&self) -> Duration {
+        if self.reduced_motion {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(self.duration_ms.min(MAX_DURATION_MS))
+        }
+    }
+}
+
+fn config_path() -> Result<PathBuf, AnimationError> {
+    Ok(config::get_app_dir()?.join("animation.json"))
+}
+
+pub fn load_config() -> Result<AnimationConfig, AnimationError> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(AnimationConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_config(config: &AnimationConfig) -> Result<(), AnimationError> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+fn lerp(from: i32, to: i32, t: f64) -> i32 {
+    (from as f64 + (to - from) as f64 * t).round() as i32
+}
+
+/// Rect between `from` (t = 0) and `to` (t = 1)
+pub fn lerp_rect(from: &Rect, to: &Rect, t: f64) -> Rect {
+    Rect {
+        x: lerp(from.x, to.x, t),
+        y: lerp(from.y, to.y, t),
+        width: lerp(from.width, to.width, t),
+        height: lerp(from.height, to.height, t),
+    }
+}
+
+/// A window slide from one rect to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub from: Rect,
+    pub to: Rect,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Animation {
+    pub fn new(from: Rect, to: Rect, config: &AnimationConfig, now: Instant) -> Self {
+        Self { from, to, start: now, duration: config.duration(), easing: config.easing }
+    }
+
+    /// Linear progress 0..1
+    pub fn progress(&self, now: Instant) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (now.saturating_duration_since(self.start).as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+    }
+
+    /// Rect to show at `now`
+    pub fn frame(&self, now: Instant) -> Rect {
+        lerp_rect(&self.from, &self.to, self.easing.apply(self.progress(now)))
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    /// Head for a new target from wherever this slide is at `now`. Reversing
+    /// takes as long as the slide had been running, so a half-open panel
+    /// closes in half the time.
+    pub fn retarget(&self, to: Rect, config: &AnimationConfig, now: Instant) -> Self {
+        let mut next = Self::new(self.frame(now), to, config, now);
+        if to == self.from {
+            next.duration = next.duration.mul_f64(self.progress(now));
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 3] = [Easing::Linear, Easing::EaseOutCubic, Easing::EaseInOutCubic];
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    fn config(duration_ms: u64, easing: Easing) -> AnimationConfig {
+        AnimationConfig { duration_ms, easing, reduced_motion: false }
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn easing_hits_the_endpoints_and_clamps() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.apply(-0.5), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.5), 1.0, "{easing:?}");
+        }
+        assert_eq!(Easing::EaseInOutCubic.apply(0.5), 0.5);
+        assert!(Easing::EaseOutCubic.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn easing_is_monotonic() {
+        for easing in EASINGS {
+            let mut last = 0.0;
+            for i in 1..=100 {
+                let eased = easing.apply(i as f64 / 100.0);
+                assert!(eased >= last, "{easing:?} drops at {i}%");
+                last = eased;
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_rect_interpolates_every_field() {
+        let from = rect(-400, 0, 340, 1040);
+        let to = rect(0, 100, 20, 48);
+        assert_eq!(lerp_rect(&from, &to, 0.0), from);
+        assert_eq!(lerp_rect(&from, &to, 0.5), rect(-200, 50, 180, 544));
+        assert_eq!(lerp_rect(&from, &to, 1.0), to);
+    }
+
+    #[test]
+    fn zero_duration_is_finished_immediately() {
+        let t0 = Instant::now();
+        let reduced = AnimationConfig { reduced_motion: true, ..AnimationConfig::default() };
+        for config in [config(0, Easing::Linear), reduced] {
+            let slide = Animation::new(rect(0, 0, 340, 1040), rect(0, 0, 20, 1040), &config, t0);
+            assert_eq!(slide.progress(t0), 1.0);
+            assert!(slide.is_finished(t0));
+            assert_eq!(slide.frame(t0), rect(0, 0, 20, 1040));
+        }
+    }
+
+    #[test]
+    fn progress_runs_over_the_duration_and_caps_at_the_maximum() {
+        let t0 = Instant::now();
+        let slide = Animation::new(rect(0, 0, 100, 100), rect(100, 0, 100, 100), &config(200, Easing::Linear), t0);
+        assert_eq!(slide.progress(ms(t0, 50)), 0.25);
+        assert!(!slide.is_finished(ms(t0, 199)));
+        assert!(slide.is_finished(ms(t0, 200)));
+        assert_eq!(slide.frame(ms(t0, 100)), rect(50, 0, 100, 100));
+        assert_eq!(slide.frame(ms(t0, 5000)), rect(100, 0, 100, 100));
+
+        let long = Animation::new(rect(0, 0, 1, 1), rect(1, 1, 1, 1), &config(60_000, Easing::Linear), t0);
+        assert!(long.is_finished(ms(t0, MAX_DURATION_MS)));
+    }
+
+    #[test]
+    fn retarget_back_to_the_start_takes_the_elapsed_time() {
+        let t0 = Instant::now();
+        let config = config(200, Easing::Linear);
+        let (tucked, shown) = (rect(0, 500, 20, 48), rect(0, 0, 340, 1040));
+        let opening = Animation::new(tucked, shown, &config, t0);
+
+        let closing = opening.retarget(tucked, &config, ms(t0, 100));
+        assert_eq!(closing.from, opening.frame(ms(t0, 100)));
+        assert_eq!(closing.to, tucked);
+        assert!(!closing.is_finished(ms(t0, 199)));
+        assert!(closing.is_finished(ms(t0, 200)));
+        assert_eq!(closing.frame(ms(t0, 200)), tucked);
+
+        // A new target elsewhere gets the full duration
+        let elsewhere = opening.retarget(rect(1000, 0, 340, 1040), &config, ms(t0, 100));
+        assert!(!elsewhere.is_finished(ms(t0, 299)));
+        assert!(elsewhere.is_finished(ms(t0, 300)));
+    }
+}
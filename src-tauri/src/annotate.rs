@@ -0,0 +1,779 @@
+use crate::color::{self, ColorError};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AnnotateError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Color(#[from] ColorError),
+    #[error("Crop region is outside the image")]
+    EmptyCrop,
+}
+
+fn default_color() -> String {
+    "#ff3b30".to_string()
+}
+
+fn default_width() -> u32 {
+    3
+}
+
+fn default_text_size() -> u32 {
+    14
+}
+
+fn default_blur_radius() -> u32 {
+    8
+}
+
+fn default_block_size() -> u32 {
+    10
+}
+
+/// One markup operation. Operations apply in order and coordinates are in
+/// pixels of the image as it is at that point (i.e. after any earlier crop).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnnotationOp {
+    Arrow {
+        from: [i32; 2],
+        to: [i32; 2],
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_width")]
+        width: u32,
+    },
+    Rect {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_width")]
+        stroke_width: u32,
+        /// Optional fill, e.g. "#ff3b3040" for a translucent highlight
+        #[serde(default)]
+        fill: Option<String>,
+    },
+    /// Full-width horizontal line with an optional label at the right edge
+    PriceLine {
+        y: i32,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default = "default_color")]
+        color: String,
+        #[serde(default = "default_width")]
+        width: u32,
+        #[serde(default)]
+        dashed: bool,
+    },
+    Text {
+        x: i32,
+        y: i32,
+        text: String,
+        #[serde(default = "default_color")]
+        color: String,
+        /// Approximate cap height in pixels
+        #[serde(default = "default_text_size")]
+        size: u32,
+        #[serde(default)]
+        background: Option<String>,
+    },
+    Blur {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        #[serde(default = "default_blur_radius")]
+        radius: u32,
+    },
+    Pixelate {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        #[serde(default = "default_block_size")]
+        block: u32,
+    },
+    Crop {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// Sidecar stored next to the source image so edits can be reopened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationSet {
+    pub source: String,
+    pub output: String,
+    pub ops: Vec<AnnotationOp>,
+}
+
+/// Parse `#rgb`, `#rrggbb` or `#rrggbbaa`
+pub fn parse_rgba(raw: &str) -> Result<Rgba<u8>, AnnotateError> {
+    let hex = raw.trim().trim_start_matches('#');
+    if hex.len() == 8 {
+        let [r, g, b] = color::parse_hex(&hex[..6])?;
+        let a = u8::from_str_radix(&hex[6..], 16).map_err(|_| ColorError::InvalidColor(raw.to_string()))?;
+        return Ok(Rgba([r, g, b, a]));
+    }
+    let [r, g, b] = color::parse_hex(hex)?;
+    Ok(Rgba([r, g, b, 255]))
+}
+
+/// Widest stroke any op draws with
+const MAX_STROKE_WIDTH: u32 = 64;
+/// Largest text cap height
+const MAX_TEXT_SIZE: u32 = 256;
+
+// Drawing helpers work in i64 so ops with extreme i32 coordinates clip
+// instead of overflowing.
+
+/// Source-over blend of one pixel; out-of-bounds writes are ignored
+fn blend(img: &mut RgbaImage, x: i64, y: i64, c: Rgba<u8>) {
+    if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+        return;
+    }
+    let dst = img.get_pixel_mut(x as u32, y as u32);
+    let a = c.0[3] as u32;
+    if a == 255 {
+        *dst = c;
+        return;
+    }
+    let inv = 255 - a;
+    for i in 0..3 {
+        dst.0[i] = ((c.0[i] as u32 * a + dst.0[i] as u32 * inv + 127) / 255) as u8;
+    }
+    dst.0[3] = (a + (dst.0[3] as u32 * inv + 127) / 255).min(255) as u8;
+}
+
+fn fill_rect(img: &mut RgbaImage, x: i64, y: i64, w: i64, h: i64, c: Rgba<u8>) {
+    let x0 = x.max(0);
+    let y0 = y.max(0);
+    let x1 = (x + w).min(img.width() as i64);
+    let y1 = (y + h).min(img.height() as i64);
+    for py in y0..y1 {
+        for px in x0..x1 {
+            blend(img, px, py, c);
+        }
+    }
+}
+
+/// Liang-Barsky clip of a segment to `[min, max]` on both axes; None when
+/// the segment misses the box entirely
+fn clip_segment(from: [i64; 2], to: [i64; 2], min: [i64; 2], max: [i64; 2]) -> Option<([i64; 2], [i64; 2])> {
+    let (x0, y0) = (from[0] as f64, from[1] as f64);
+    let (dx, dy) = (to[0] as f64 - x0, to[1] as f64 - y0);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-dx, x0 - min[0] as f64),
+        (dx, max[0] as f64 - x0),
+        (-dy, y0 - min[1] as f64),
+        (dy, max[1] as f64 - y0),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let at = |t: f64| [(x0 + dx * t).round() as i64, (y0 + dy * t).round() as i64];
+    Some((if t0 > 0.0 { at(t0) } else { from }, if t1 < 1.0 { at(t1) } else { to }))
+}
+
+/// Points of a Bresenham line, endpoints included
+fn line_points(from: [i64; 2], to: [i64; 2]) -> Vec<[i64; 2]> {
+    let [mut x, mut y] = from;
+    let dx = (to[0] - x).abs();
+    let dy = -(to[1] - y).abs();
+    let sx = if x < to[0] { 1 } else { -1 };
+    let sy = if y < to[1] { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut points = Vec::with_capacity((dx - dy + 1) as usize);
+    loop {
+        points.push([x, y]);
+        if x == to[0] && y == to[1] {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+    points
+}
+
+/// Thick line drawn as a square brush along the Bresenham path, clipped to
+/// the image first. Each covered pixel is painted once so translucent colors stay even.
+fn draw_line(img: &mut RgbaImage, from: [i32; 2], to: [i32; 2], width: u32, c: Rgba<u8>) {
+    let (img_w, img_h) = (img.width() as i64, img.height() as i64);
+    if img_w == 0 || img_h == 0 {
+        return;
+    }
+    let w = width.clamp(1, MAX_STROKE_WIDTH) as i64;
+    let half = w / 2;
+    // Keep brush centres that can still touch the image
+    let Some((from, to)) = clip_segment(
+        from.map(i64::from),
+        to.map(i64::from),
+        [-(w - half), -(w - half)],
+        [img_w - 1 + half, img_h - 1 + half],
+    ) else {
+        return;
+    };
+
+    // Coverage mask over the part of the image the brush can reach
+    let (x0, y0) = ((from[0].min(to[0]) - half).max(0), (from[1].min(to[1]) - half).max(0));
+    let (x1, y1) = ((from[0].max(to[0]) - half + w).min(img_w), (from[1].max(to[1]) - half + w).min(img_h));
+    if x1 <= x0 || y1 <= y0 {
+        return;
+    }
+    let mask_w = x1 - x0;
+    let mut covered = vec![false; (mask_w * (y1 - y0)) as usize];
+    for [x, y] in line_points(from, to) {
+        for py in (y - half).max(y0)..(y - half + w).min(y1) {
+            for px in (x - half).max(x0)..(x - half + w).min(x1) {
+                covered[((py - y0) * mask_w + px - x0) as usize] = true;
+            }
+        }
+    }
+    for (i, _) in covered.iter().enumerate().filter(|(_, c)| **c) {
+        let i = i as i64;
+        blend(img, x0 + i % mask_w, y0 + i / mask_w, c);
+    }
+}
+
+fn fill_triangle(img: &mut RgbaImage, p: [[f64; 2]; 3], c: Rgba<u8>) {
+    let min_x = (p.iter().map(|q| q[0]).fold(f64::MAX, f64::min).floor() as i64).max(0);
+    let max_x = (p.iter().map(|q| q[0]).fold(f64::MIN, f64::max).ceil() as i64).min(img.width() as i64 - 1);
+    let min_y = (p.iter().map(|q| q[1]).fold(f64::MAX, f64::min).floor() as i64).max(0);
+    let max_y = (p.iter().map(|q| q[1]).fold(f64::MIN, f64::max).ceil() as i64).min(img.height() as i64 - 1);
+    let edge = |a: [f64; 2], b: [f64; 2], x: f64, y: f64| (b[0] - a[0]) * (y - a[1]) - (b[1] - a[1]) * (x - a[0]);
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let (cx, cy) = (x as f64 + 0.5, y as f64 + 0.5);
+            let w0 = edge(p[1], p[2], cx, cy);
+            let w1 = edge(p[2], p[0], cx, cy);
+            let w2 = edge(p[0], p[1], cx, cy);
+            if (w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0) || (w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0) {
+                blend(img, x, y, c);
+            }
+        }
+    }
+}
+
+fn draw_arrow(img: &mut RgbaImage, from: [i32; 2], to: [i32; 2], width: u32, c: Rgba<u8>) {
+    let (dx, dy) = (to[0] as f64 - from[0] as f64, to[1] as f64 - from[1] as f64);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < 1.0 {
+        return;
+    }
+    let width = width.clamp(1, MAX_STROKE_WIDTH);
+    let (ux, uy) = (dx / len, dy / len);
+    let head = (width as f64 * 4.0).max(10.0).min(len);
+    let base = [to[0] as f64 - ux * head, to[1] as f64 - uy * head];
+    // Stop the shaft inside the head so its square end never pokes out of the tip
+    let shaft_end = [(base[0] + ux).round() as i32, (base[1] + uy).round() as i32];
+    draw_line(img, from, shaft_end, width, c);
+    let spread = head * 0.5;
+    fill_triangle(
+        img,
+        [
+            [to[0] as f64, to[1] as f64],
+            [base[0] - uy * spread, base[1] + ux * spread],
+            [base[0] + uy * spread, base[1] - ux * spread],
+        ],
+        c,
+    );
+}
+
+/// Classic 5x7 font for printable ASCII, one byte per column, bit 0 at the top
+const FONT_5X7: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5F, 0x00, 0x00], [0x00, 0x07, 0x00, 0x07, 0x00],
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], [0x24, 0x2A, 0x7F, 0x2A, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62],
+    [0x36, 0x49, 0x55, 0x22, 0x50], [0x00, 0x05, 0x03, 0x00, 0x00], [0x00, 0x1C, 0x22, 0x41, 0x00],
+    [0x00, 0x41, 0x22, 0x1C, 0x00], [0x14, 0x08, 0x3E, 0x08, 0x14], [0x08, 0x08, 0x3E, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], [0x00, 0x60, 0x60, 0x00, 0x00],
+    [0x20, 0x10, 0x08, 0x04, 0x02], [0x3E, 0x51, 0x49, 0x45, 0x3E], [0x00, 0x42, 0x7F, 0x40, 0x00],
+    [0x42, 0x61, 0x51, 0x49, 0x46], [0x21, 0x41, 0x45, 0x4B, 0x31], [0x18, 0x14, 0x12, 0x7F, 0x10],
+    [0x27, 0x45, 0x45, 0x45, 0x39], [0x3C, 0x4A, 0x49, 0x49, 0x30], [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x06, 0x49, 0x49, 0x29, 0x1E], [0x00, 0x36, 0x36, 0x00, 0x00],
+    [0x00, 0x56, 0x36, 0x00, 0x00], [0x08, 0x14, 0x22, 0x41, 0x00], [0x14, 0x14, 0x14, 0x14, 0x14],
+    [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x51, 0x09, 0x06], [0x32, 0x49, 0x79, 0x41, 0x3E],
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], [0x7F, 0x49, 0x49, 0x49, 0x36], [0x3E, 0x41, 0x41, 0x41, 0x22],
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], [0x7F, 0x49, 0x49, 0x49, 0x41], [0x7F, 0x09, 0x09, 0x01, 0x01],
+    [0x3E, 0x41, 0x41, 0x51, 0x32], [0x7F, 0x08, 0x08, 0x08, 0x7F], [0x00, 0x41, 0x7F, 0x41, 0x00],
+    [0x20, 0x40, 0x41, 0x3F, 0x01], [0x7F, 0x08, 0x14, 0x22, 0x41], [0x7F, 0x40, 0x40, 0x40, 0x40],
+    [0x7F, 0x02, 0x04, 0x02, 0x7F], [0x7F, 0x04, 0x08, 0x10, 0x7F], [0x3E, 0x41, 0x41, 0x41, 0x3E],
+    [0x7F, 0x09, 0x09, 0x09, 0x06], [0x3E, 0x41, 0x51, 0x21, 0x5E], [0x7F, 0x09, 0x19, 0x29, 0x46],
+    [0x46, 0x49, 0x49, 0x49, 0x31], [0x01, 0x01, 0x7F, 0x01, 0x01], [0x3F, 0x40, 0x40, 0x40, 0x3F],
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], [0x7F, 0x20, 0x18, 0x20, 0x7F], [0x63, 0x14, 0x08, 0x14, 0x63],
+    [0x03, 0x04, 0x78, 0x04, 0x03], [0x61, 0x51, 0x49, 0x45, 0x43], [0x00, 0x7F, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x7F, 0x00], [0x04, 0x02, 0x01, 0x02, 0x04],
+    [0x40, 0x40, 0x40, 0x40, 0x40], [0x00, 0x01, 0x02, 0x04, 0x00], [0x20, 0x54, 0x54, 0x54, 0x78],
+    [0x7F, 0x48, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x20], [0x38, 0x44, 0x44, 0x48, 0x7F],
+    [0x38, 0x54, 0x54, 0x54, 0x18], [0x08, 0x7E, 0x09, 0x01, 0x02], [0x08, 0x14, 0x54, 0x54, 0x3C],
+    [0x7F, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7D, 0x40, 0x00], [0x20, 0x40, 0x44, 0x3D, 0x00],
+    [0x00, 0x7F, 0x10, 0x28, 0x44], [0x00, 0x41, 0x7F, 0x40, 0x00], [0x7C, 0x04, 0x18, 0x04, 0x78],
+    [0x7C, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38], [0x7C, 0x14, 0x14, 0x14, 0x08],
+    [0x08, 0x14, 0x14, 0x18, 0x7C], [0x7C, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3F, 0x44, 0x40, 0x20], [0x3C, 0x40, 0x40, 0x20, 0x7C], [0x1C, 0x20, 0x40, 0x20, 0x1C],
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], [0x44, 0x28, 0x10, 0x28, 0x44], [0x0C, 0x50, 0x50, 0x50, 0x3C],
+    [0x44, 0x64, 0x54, 0x4C, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00], [0x00, 0x00, 0x7F, 0x00, 0x00],
+    [0x00, 0x41, 0x36, 0x08, 0x00], [0x08, 0x04, 0x08, 0x10, 0x08],
+];
+
+/// Integer scale of the 5x7 font for a requested cap height
+fn font_scale(size: u32) -> i64 {
+    ((size.min(MAX_TEXT_SIZE) as f64 / 7.0).round() as i64).max(1)
+}
+
+/// Width and height of rendered text in pixels
+fn measure_text(text: &str, size: u32) -> (i64, i64) {
+    let scale = font_scale(size);
+    let chars = text.chars().count() as i64;
+    ((chars * 6 - 1).max(0) * scale, 7 * scale)
+}
+
+/// Draw text with its top-left corner at (x, y). Non-ASCII renders as '?'.
+fn draw_text(img: &mut RgbaImage, x: i64, y: i64, text: &str, size: u32, c: Rgba<u8>) {
+    let scale = font_scale(size);
+    if y >= img.height() as i64 || y + 7 * scale <= 0 {
+        return;
+    }
+    for (i, ch) in text.chars().enumerate() {
+        let code = if (' '..='~').contains(&ch) { ch as usize } else { '?' as usize };
+        let glyph = FONT_5X7[code - 32];
+        let gx = x + i as i64 * 6 * scale;
+        if gx >= img.width() as i64 {
+            break;
+        }
+        if gx + 5 * scale <= 0 {
+            continue;
+        }
+        for (col, bits) in glyph.iter().enumerate() {
+            for row in 0..7 {
+                if bits & (1 << row) != 0 {
+                    fill_rect(img, gx + col as i64 * scale, y + row * scale, scale, scale, c);
+                }
+            }
+        }
+    }
+}
+
+/// Clip a rectangle to the image; None if nothing is left
+fn clip(img: &RgbaImage, x: i32, y: i32, w: u32, h: u32) -> Option<(u32, u32, u32, u32)> {
+    let x0 = x.max(0) as u32;
+    let y0 = y.max(0) as u32;
+    let x1 = ((x as i64 + w as i64).min(img.width() as i64)).max(0) as u32;
+    let y1 = ((y as i64 + h as i64).min(img.height() as i64)).max(0) as u32;
+    (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Integer box blur, applied three times to approximate a gaussian
+fn blur_region(img: &mut RgbaImage, x: i32, y: i32, w: u32, h: u32, radius: u32) {
+    let Some((x0, y0, w, h)) = clip(img, x, y, w, h) else { return };
+    let r = radius.clamp(1, 100) as i64;
+    let mut region = image::imageops::crop_imm(img, x0, y0, w, h).to_image();
+    for _ in 0..3 {
+        for horizontal in [true, false] {
+            let src = region.clone();
+            let (len, lines) = if horizontal { (w as i64, h) } else { (h as i64, w) };
+            for line in 0..lines {
+                let at = |i: i64| {
+                    let i = i.clamp(0, len - 1) as u32;
+                    if horizontal { src.get_pixel(i, line).0 } else { src.get_pixel(line, i).0 }
+                };
+                let mut sum = [0i64; 4];
+                for i in -r..=r {
+                    let p = at(i);
+                    for ch in 0..4 {
+                        sum[ch] += p[ch] as i64;
+                    }
+                }
+                let window = 2 * r + 1;
+                for i in 0..len {
+                    let out = sum.map(|s| ((s + window / 2) / window) as u8);
+                    let (px, py) = if horizontal { (i as u32, line) } else { (line, i as u32) };
+                    region.put_pixel(px, py, Rgba(out));
+                    let (add, sub) = (at(i + r + 1), at(i - r));
+                    for ch in 0..4 {
+                        sum[ch] += add[ch] as i64 - sub[ch] as i64;
+                    }
+                }
+            }
+        }
+    }
+    image::imageops::replace(img, &region, x0 as i64, y0 as i64);
+}
+
+fn pixelate_region(img: &mut RgbaImage, x: i32, y: i32, w: u32, h: u32, block: u32) {
+    let Some((x0, y0, w, h)) = clip(img, x, y, w, h) else { return };
+    let block = block.max(1);
+    for by in (y0..y0 + h).step_by(block as usize) {
+        for bx in (x0..x0 + w).step_by(block as usize) {
+            let (bw, bh) = (block.min(x0 + w - bx), block.min(y0 + h - by));
+            let mut sum = [0u64; 4];
+            for py in by..by + bh {
+                for px in bx..bx + bw {
+                    for (s, c) in sum.iter_mut().zip(img.get_pixel(px, py).0) {
+                        *s += c as u64;
+                    }
+                }
+            }
+            let n = (bw * bh) as u64;
+            let avg = Rgba(sum.map(|s| ((s + n / 2) / n) as u8));
+            for py in by..by + bh {
+                for px in bx..bx + bw {
+                    img.put_pixel(px, py, avg);
+                }
+            }
+        }
+    }
+}
+
+/// Black or white, whichever reads better on `bg`
+fn label_text_color(bg: Rgba<u8>) -> Rgba<u8> {
+    if color::relative_luminance([bg.0[0], bg.0[1], bg.0[2]]) > 0.4 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) }
+}
+
+const DASH_LENGTH: i64 = 10;
+const DASH_GAP: i64 = 6;
+const LABEL_PADDING: i64 = 4;
+const LABEL_TEXT_SIZE: u32 = 14;
+
+fn draw_price_line(img: &mut RgbaImage, y: i32, label: Option<&str>, width: u32, dashed: bool, c: Rgba<u8>) {
+    let (y, width) = (y as i64, width.clamp(1, MAX_STROKE_WIDTH) as i64);
+    let top = y - width / 2;
+    let img_w = img.width() as i64;
+    if dashed {
+        let mut x = 0;
+        while x < img_w {
+            fill_rect(img, x, top, DASH_LENGTH, width, c);
+            x += DASH_LENGTH + DASH_GAP;
+        }
+    } else {
+        fill_rect(img, 0, top, img_w, width, c);
+    }
+
+    if let Some(label) = label.filter(|l| !l.is_empty()) {
+        let (tw, th) = measure_text(label, LABEL_TEXT_SIZE);
+        let (bw, bh) = (tw + LABEL_PADDING * 2, th + LABEL_PADDING * 2);
+        let bx = img_w - bw;
+        let by = y - bh / 2;
+        let bg = Rgba([c.0[0], c.0[1], c.0[2], 255]);
+        fill_rect(img, bx, by, bw, bh, bg);
+        draw_text(img, bx + LABEL_PADDING, by + LABEL_PADDING, label, LABEL_TEXT_SIZE, label_text_color(bg));
+    }
+}
+
+/// Apply operations in order. Pure and deterministic: the same image and
+/// ops always produce the same pixels.
+pub fn render(source: &RgbaImage, ops: &[AnnotationOp]) -> Result<RgbaImage, AnnotateError> {
+    let mut img = source.clone();
+    for op in ops {
+        match op {
+            AnnotationOp::Arrow { from, to, color, width } => draw_arrow(&mut img, *from, *to, *width, parse_rgba(color)?),
+            AnnotationOp::Rect { x, y, width, height, color, stroke_width, fill } => {
+                let (x, y, w, h) = (*x as i64, *y as i64, *width as i64, *height as i64);
+                if let Some(fill) = fill {
+                    fill_rect(&mut img, x, y, w, h, parse_rgba(fill)?);
+                }
+                let c = parse_rgba(color)?;
+                let s = ((*stroke_width).min(MAX_STROKE_WIDTH) as i64).min(w / 2 + 1).min(h / 2 + 1);
+                if s > 0 {
+                    fill_rect(&mut img, x, y, w, s, c);
+                    fill_rect(&mut img, x, y + h - s, w, s, c);
+                    fill_rect(&mut img, x, y + s, s, h - 2 * s, c);
+                    fill_rect(&mut img, x + w - s, y + s, s, h - 2 * s, c);
+                }
+            }
+            AnnotationOp::PriceLine { y, label, color, width, dashed } => {
+                draw_price_line(&mut img, *y, label.as_deref(), *width, *dashed, parse_rgba(color)?)
+            }
+            AnnotationOp::Text { x, y, text, color, size, background } => {
+                let (x, y) = (*x as i64, *y as i64);
+                if let Some(bg) = background {
+                    let (tw, th) = measure_text(text, *size);
+                    fill_rect(&mut img, x - LABEL_PADDING, y - LABEL_PADDING, tw + LABEL_PADDING * 2, th + LABEL_PADDING * 2, parse_rgba(bg)?);
+                }
+                draw_text(&mut img, x, y, text, *size, parse_rgba(color)?);
+            }
+            AnnotationOp::Blur { x, y, width, height, radius } => blur_region(&mut img, *x, *y, *width, *height, *radius),
+            AnnotationOp::Pixelate { x, y, width, height, block } => pixelate_region(&mut img, *x, *y, *width, *height, *block),
+            AnnotationOp::Crop { x, y, width, height } => {
+                let (cx, cy, cw, ch) = clip(&img, *x, *y, *width, *height).ok_or(AnnotateError::EmptyCrop)?;
+                img = image::imageops::crop_imm(&img, cx, cy, cw, ch).to_image();
+            }
+        }
+    }
+    Ok(img)
+}
+
+/// `chart.png` -> `chart.png.annotations.json`
+pub fn sidecar_path(source: &Path) -> PathBuf {
+    let mut name = source.file_name().unwrap_or_default().to_os_string();
+    name.push(".annotations.json");
+    source.with_file_name(name)
+}
+
+/// `chart.png` -> `chart_annotated.png`
+pub fn output_path(source: &Path) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    source.with_file_name(format!("{}_annotated.png", stem))
+}
+
+/// Previously saved ops for a source image (empty if never annotated)
+pub fn load_annotations(source: &Path) -> Result<Vec<AnnotationOp>, AnnotateError> {
+    let path = sidecar_path(source);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let set: AnnotationSet = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(set.ops)
+}
+
+/// Render the ops onto the original image, save the result as a separate
+/// PNG and store the ops in the sidecar. The source file is never modified.
+pub fn annotate_file(source: &Path, ops: Vec<AnnotationOp>, output: Option<PathBuf>) -> Result<(PathBuf, RgbaImage), AnnotateError> {
+    let original = image::open(source)?.to_rgba8();
+    let rendered = render(&original, &ops)?;
+    let output = output.unwrap_or_else(|| output_path(source));
+    rendered.save_with_format(&output, image::ImageFormat::Png)?;
+
+    let set = AnnotationSet {
+        source: source.to_string_lossy().to_string(),
+        output: output.to_string_lossy().to_string(),
+        ops,
+    };
+    fs::write(sidecar_path(source), serde_json::to_string_pretty(&set)?)?;
+    Ok((output, rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BG: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn canvas(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, BG)
+    }
+
+    /// '.' for untouched pixels, '#' for pure red, '+' for anything else
+    fn ascii(img: &RgbaImage) -> Vec<String> {
+        img.rows()
+            .map(|row| {
+                row.map(|p| match p.0 {
+                    [0, 0, 0, 255] => '.',
+                    [255, 0, 0, 255] => '#',
+                    _ => '+',
+                })
+                .collect()
+            })
+            .collect()
+    }
+
+    fn draw(width: u32, height: u32, ops: &[AnnotationOp]) -> Vec<String> {
+        ascii(&render(&canvas(width, height), ops).unwrap())
+    }
+
+    fn arrow(from: [i32; 2], to: [i32; 2], width: u32) -> AnnotationOp {
+        AnnotationOp::Arrow { from, to, color: "#f00".to_string(), width }
+    }
+
+    fn line(from: [i32; 2], to: [i32; 2], width: u32) -> Vec<String> {
+        let mut img = canvas(10, 8);
+        draw_line(&mut img, from, to, width, Rgba([255, 0, 0, 255]));
+        ascii(&img)
+    }
+
+    fn lines(rows: &[&str]) -> Vec<String> {
+        rows.iter().map(|r| r.to_string()).collect()
+    }
+
+    #[test]
+    fn thin_line_follows_bresenham() {
+        assert_eq!(
+            line([1, 1], [8, 6], 1),
+            lines(&[
+                "..........",
+                ".#........",
+                "..##......",
+                "....#.....",
+                ".....#....",
+                "......##..",
+                "........#.",
+                "..........",
+            ])
+        );
+    }
+
+    #[test]
+    fn thick_line_uses_a_square_brush() {
+        assert_eq!(
+            line([1, 1], [8, 6], 3),
+            lines(&[
+                "###.......",
+                "#####.....",
+                "######....",
+                ".######...",
+                "...######.",
+                "....######",
+                ".....#####",
+                ".......###",
+            ])
+        );
+    }
+
+    #[test]
+    fn far_off_lines_are_clipped_to_the_image() {
+        assert_eq!(line([-1_000_000, 3], [1_000_000, 3], 1)[3], "##########");
+        assert_eq!(
+            line([i32::MIN, i32::MIN], [i32::MAX, i32::MAX], 2),
+            lines(&[
+                "##........",
+                "###.......",
+                ".###......",
+                "..###.....",
+                "...###....",
+                "....###...",
+                ".....###..",
+                "......###.",
+            ])
+        );
+        // Entirely outside: nothing drawn
+        assert!(line([-50, -50], [-10, 100], 3).iter().all(|row| !row.contains('#')));
+    }
+
+    #[test]
+    fn translucent_lines_paint_each_pixel_once() {
+        let mut img = canvas(12, 12);
+        draw_line(&mut img, [1, 2], [10, 9], 4, Rgba([255, 255, 255, 128]));
+        let painted: std::collections::BTreeSet<[u8; 4]> = img.pixels().map(|p| p.0).filter(|p| *p != BG.0).collect();
+        assert_eq!(painted.len(), 1);
+    }
+
+    #[test]
+    fn rect_strokes_inside_its_bounds_over_the_fill() {
+        let ops = [AnnotationOp::Rect {
+            x: 1,
+            y: 1,
+            width: 8,
+            height: 6,
+            color: "#f00".into(),
+            stroke_width: 1,
+            fill: Some("#ffffff80".into()),
+        }];
+        assert_eq!(
+            draw(10, 8, &ops),
+            lines(&[
+                "..........",
+                ".########.",
+                ".#++++++#.",
+                ".#++++++#.",
+                ".#++++++#.",
+                ".#++++++#.",
+                ".########.",
+                "..........",
+            ])
+        );
+    }
+
+    #[test]
+    fn text_and_dashed_price_line() {
+        let text = [AnnotationOp::Text { x: 1, y: 1, text: "Hi".into(), color: "#f00".into(), size: 7, background: None }];
+        assert_eq!(
+            draw(14, 9, &text),
+            lines(&[
+                "..............",
+                ".#...#...#....",
+                ".#...#........",
+                ".#...#..##....",
+                ".#####...#....",
+                ".#...#...#....",
+                ".#...#...#....",
+                ".#...#..###...",
+                "..............",
+            ])
+        );
+
+        let price = [AnnotationOp::PriceLine { y: 2, label: None, color: "#f00".into(), width: 1, dashed: true }];
+        assert_eq!(draw(20, 3, &price), lines(&["....................", "....................", "##########......####"]));
+    }
+
+    #[test]
+    fn extreme_ops_clip_without_overflowing() {
+        let red = || "#f00".to_string();
+        let ops = vec![
+            AnnotationOp::Rect { x: i32::MAX, y: i32::MAX, width: u32::MAX, height: u32::MAX, color: red(), stroke_width: u32::MAX, fill: Some(red()) },
+            AnnotationOp::Rect { x: i32::MIN, y: i32::MIN, width: u32::MAX, height: u32::MAX, color: red(), stroke_width: 2, fill: None },
+            AnnotationOp::Arrow { from: [i32::MIN, 0], to: [i32::MAX, 5], color: red(), width: u32::MAX },
+            AnnotationOp::PriceLine { y: i32::MIN, label: Some("TP".into()), color: red(), width: u32::MAX, dashed: true },
+            AnnotationOp::PriceLine { y: i32::MAX, label: Some("SL".into()), color: red(), width: 3, dashed: false },
+            AnnotationOp::Text { x: i32::MAX, y: 0, text: "x".repeat(10_000), color: red(), size: u32::MAX, background: Some(red()) },
+            AnnotationOp::Text { x: i32::MIN, y: i32::MIN, text: "overflow".into(), color: red(), size: 14, background: Some(red()) },
+            AnnotationOp::Blur { x: i32::MIN, y: i32::MIN, width: u32::MAX, height: u32::MAX, radius: u32::MAX },
+            AnnotationOp::Pixelate { x: i32::MAX, y: 0, width: u32::MAX, height: 4, block: u32::MAX },
+        ];
+        let started = std::time::Instant::now();
+        let img = render(&canvas(64, 48), &ops).unwrap();
+        assert_eq!(img.dimensions(), (64, 48));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let crop = [AnnotationOp::Crop { x: i32::MAX, y: 0, width: u32::MAX, height: 10 }];
+        assert!(matches!(render(&canvas(64, 48), &crop), Err(AnnotateError::EmptyCrop)));
+    }
+
+    #[test]
+    fn composite_matches_golden_image() {
+        let golden = image::load_from_memory(include_bytes!("../tests/fixtures/annotate/composite.png")).unwrap().to_rgba8();
+        let mut source = RgbaImage::new(160, 100);
+        for (x, y, p) in source.enumerate_pixels_mut() {
+            *p = Rgba([(x * 255 / 159) as u8, (y * 255 / 99) as u8, 96, 255]);
+        }
+        let ops: Vec<AnnotationOp> = serde_json::from_str(
+            r##"[
+                { "type": "pixelate", "x": 100, "y": 60, "width": 50, "height": 30, "block": 6 },
+                { "type": "blur", "x": 10, "y": 60, "width": 60, "height": 30, "radius": 3 },
+                { "type": "rect", "x": 8, "y": 8, "width": 60, "height": 40, "stroke_width": 2, "fill": "#ffffff40" },
+                { "type": "arrow", "from": [20, 90], "to": [120, 20], "color": "#00ff00", "width": 3 },
+                { "type": "price_line", "y": 50, "label": "TP 1.25", "color": "#2962ff", "dashed": true, "width": 2 },
+                { "type": "text", "x": 12, "y": 14, "text": "BTC 4h", "size": 7, "background": "#000000a0", "color": "#ffffff" },
+                { "type": "crop", "x": 4, "y": 4, "width": 152, "height": 92 }
+            ]"##,
+        )
+        .unwrap();
+
+        let rendered = render(&source, &ops).unwrap();
+        if rendered != golden {
+            let actual = std::env::temp_dir().join("quanthud_annotate_composite.png");
+            rendered.save(&actual).unwrap();
+            panic!("Rendering differs from the golden image; actual output saved to {}", actual.display());
+        }
+    }
+}
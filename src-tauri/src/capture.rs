@@ -1,6 +1,7 @@
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use image::{DynamicImage, ImageFormat, RgbaImage};
 use screenshots::Screen;
+use serde::Serialize;
 use std::io::Cursor;
 use thiserror::Error;
 
@@ -12,34 +13,127 @@ pub enum CaptureError {
     CaptureFailed(String),
     #[error("Failed to encode image: {0}")]
     EncodeFailed(String),
+    #[error("No screen contains point ({0}, {1})")]
+    NoScreenAtPoint(i32, i32),
+    #[error("Screen index {0} out of range")]
+    ScreenIndexOutOfRange(usize),
 }
 
-/// Capture the primary screen, optionally cropping to a region, return as base64 PNG
-/// When `default_crop` is true and no region is given, crops to right 20% (fib levels).
-/// When false, returns the full screen.
-pub fn capture_screen_base64(region: Option<[i32; 4]>, default_crop: bool) -> Result<(String, u32, u32), CaptureError> {
-    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+/// A display's bounds in physical desktop pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+}
 
-    if screens.is_empty() {
-        return Err(CaptureError::NoScreens);
+impl ScreenRect {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && y >= self.y as f64
+            && x < self.x as f64 + self.width as f64
+            && y < self.y as f64 + self.height as f64
     }
+}
 
-    // Get primary screen (first one)
-    let screen = &screens[0];
+fn physical_rect(screen: &Screen) -> ScreenRect {
+    let info = &screen.display_info;
+    // Windows reports physical pixels; X11 and macOS report logical points
+    let logical = !cfg!(target_os = "windows");
+    to_physical_rect([info.x, info.y], [info.width, info.height], info.scale_factor, logical)
+}
+
+/// Desktop rect in physical pixels from a display's reported position and size
+fn to_physical_rect([x, y]: [i32; 2], [width, height]: [u32; 2], scale_factor: f32, logical: bool) -> ScreenRect {
+    let scale = if logical { scale_factor as f64 } else { 1.0 };
+    ScreenRect {
+        x: (x as f64 * scale).round() as i32,
+        y: (y as f64 * scale).round() as i32,
+        width: (width as f64 * scale).round() as u32,
+        height: (height as f64 * scale).round() as u32,
+        scale_factor,
+    }
+}
+
+/// A full-screen capture together with where that screen sits on the desktop
+pub struct ScreenCapture {
+    pub rect: ScreenRect,
+    pub image: RgbaImage,
+}
+
+impl ScreenCapture {
+    /// Map a physical desktop point to a pixel of the captured image.
+    /// Scales by the image/rect ratio so it holds whatever DPI the backend captured at.
+    pub fn pixel_at(&self, x: f64, y: f64) -> Option<(u32, u32)> {
+        if !self.rect.contains(x, y) || self.image.width() == 0 || self.image.height() == 0 {
+            return None;
+        }
+        let sx = self.image.width() as f64 / self.rect.width.max(1) as f64;
+        let sy = self.image.height() as f64 / self.rect.height.max(1) as f64;
+        let px = ((x - self.rect.x as f64) * sx) as u32;
+        let py = ((y - self.rect.y as f64) * sy) as u32;
+        Some((px.min(self.image.width() - 1), py.min(self.image.height() - 1)))
+    }
+}
 
+/// Find the capture containing a physical desktop point and the pixel under it
+pub fn locate(captures: &[ScreenCapture], x: f64, y: f64) -> Option<(&ScreenCapture, (u32, u32))> {
+    captures.iter().find_map(|c| Some((c, c.pixel_at(x, y)?)))
+}
+
+/// Convert from screenshots' image type to our image crate version
+fn capture_rgba(screen: &Screen) -> Result<RgbaImage, CaptureError> {
     let capture = screen
         .capture()
         .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+    let (width, height) = (capture.width(), capture.height());
+    RgbaImage::from_raw(width, height, capture.into_raw())
+        .ok_or_else(|| CaptureError::CaptureFailed("Failed to create image".into()))
+}
+
+/// Capture every screen
+pub fn capture_all_screens() -> Result<Vec<ScreenCapture>, CaptureError> {
+    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+    if screens.is_empty() {
+        return Err(CaptureError::NoScreens);
+    }
+    screens
+        .iter()
+        .map(|screen| Ok(ScreenCapture { rect: physical_rect(screen), image: capture_rgba(screen)? }))
+        .collect()
+}
 
-    // Convert from screenshots' image type to our image crate version
-    let width = capture.width();
-    let height = capture.height();
-    let raw_pixels: Vec<u8> = capture.into_raw();
+/// Capture one screen by its index in the backend's screen list
+pub fn capture_screen_index(index: usize) -> Result<ScreenCapture, CaptureError> {
+    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+    let screen = screens.get(index).ok_or(CaptureError::ScreenIndexOutOfRange(index))?;
+    Ok(ScreenCapture { rect: physical_rect(screen), image: capture_rgba(screen)? })
+}
+
+/// Capture the screen containing a physical desktop point
+pub fn capture_screen_at(x: f64, y: f64) -> Result<ScreenCapture, CaptureError> {
+    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+    let screen = screens
+        .iter()
+        .find(|s| physical_rect(s).contains(x, y))
+        .ok_or(CaptureError::NoScreenAtPoint(x as i32, y as i32))?;
+    Ok(ScreenCapture { rect: physical_rect(screen), image: capture_rgba(screen)? })
+}
+
+/// Capture the primary screen as an image, optionally cropped to a region [x, y, width, height]
+pub fn capture_image(region: Option<[i32; 4]>) -> Result<DynamicImage, CaptureError> {
+    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+
+    if screens.is_empty() {
+        return Err(CaptureError::NoScreens);
+    }
 
-    let img = RgbaImage::from_raw(width, height, raw_pixels)
-        .ok_or_else(|| CaptureError::CaptureFailed("Failed to create image".into()))?;
+    // Get primary screen (first one)
+    let screen = &screens[0];
 
-    let mut dynamic_img = DynamicImage::ImageRgba8(img);
+    let mut dynamic_img = DynamicImage::ImageRgba8(capture_rgba(screen)?);
 
     // Apply region crop if specified [x, y, width, height]
     if let Some([x, y, w, h]) = region {
@@ -49,8 +143,30 @@ pub fn capture_screen_base64(region: Option<[i32; 4]>, default_crop: bool) -> Re
         let crop_h = h.max(1) as u32;
 
         dynamic_img = dynamic_img.crop_imm(crop_x, crop_y, crop_w, crop_h);
-    } else if default_crop {
+    }
+
+    Ok(dynamic_img)
+}
+
+/// Encode an image as base64 PNG
+pub fn encode_png_base64(img: &DynamicImage) -> Result<String, CaptureError> {
+    let mut png_bytes = Cursor::new(Vec::new());
+    img.write_to(&mut png_bytes, ImageFormat::Png)
+        .map_err(|e| CaptureError::EncodeFailed(e.to_string()))?;
+
+    Ok(STANDARD.encode(png_bytes.into_inner()))
+}
+
+/// Capture the primary screen, optionally cropping to a region, return as base64 PNG
+/// When `default_crop` is true and no region is given, crops to right 20% (fib levels).
+/// When false, returns the full screen.
+pub fn capture_screen_base64(region: Option<[i32; 4]>, default_crop: bool) -> Result<(String, u32, u32), CaptureError> {
+    let mut dynamic_img = capture_image(region)?;
+
+    if region.is_none() && default_crop {
         // Default: crop to right 20% where fib levels typically appear
+        let width = dynamic_img.width();
+        let height = dynamic_img.height();
         let crop_x = (width as f32 * 0.80) as u32;
         dynamic_img = dynamic_img.crop_imm(crop_x, 0, width - crop_x, height);
     }
@@ -59,13 +175,78 @@ pub fn capture_screen_base64(region: Option<[i32; 4]>, default_crop: bool) -> Re
     let final_height = dynamic_img.height();
 
     // Encode to PNG and base64
-    let mut png_bytes = Cursor::new(Vec::new());
-    dynamic_img
-        .write_to(&mut png_bytes, ImageFormat::Png)
-        .map_err(|e| CaptureError::EncodeFailed(e.to_string()))?;
-
-    let base64_data = STANDARD.encode(png_bytes.into_inner());
+    let base64_data = encode_png_base64(&dynamic_img)?;
 
     Ok((base64_data, final_width, final_height))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(rect: ScreenRect, image_size: (u32, u32)) -> ScreenCapture {
+        ScreenCapture { rect, image: RgbaImage::new(image_size.0, image_size.1) }
+    }
+
+    #[test]
+    fn physical_rect_scales_logical_reports_only() {
+        let logical = to_physical_rect([1280, -360], [1280, 720], 1.5, true);
+        assert_eq!((logical.x, logical.y, logical.width, logical.height), (1920, -540, 1920, 1080));
+
+        // Windows already reports physical pixels
+        let physical = to_physical_rect([1920, 0], [2560, 1440], 2.0, false);
+        assert_eq!((physical.x, physical.y, physical.width, physical.height), (1920, 0, 2560, 1440));
+        assert_eq!(physical.scale_factor, 2.0);
+    }
+
+    #[test]
+    fn pixel_at_maps_physical_points_on_an_offset_monitor() {
+        let screen = capture(to_physical_rect([-1920, 0], [1920, 1080], 1.0, true), (1920, 1080));
+        assert_eq!(screen.pixel_at(-1920.0, 0.0), Some((0, 0)));
+        assert_eq!(screen.pixel_at(-1.0, 1079.5), Some((1919, 1079)));
+        // Right and bottom edges belong to the next monitor
+        assert_eq!(screen.pixel_at(0.0, 10.0), None);
+        assert_eq!(screen.pixel_at(-10.0, 1080.0), None);
+    }
+
+    #[test]
+    fn pixel_at_follows_the_captured_resolution() {
+        // Logical 1280x720 at 2x, i.e. physical 2560x1440 starting at x = 2560
+        let rect = to_physical_rect([1280, 0], [1280, 720], 2.0, true);
+
+        let full = capture(rect, (2560, 1440));
+        assert_eq!(full.pixel_at(2560.0 + 101.0, 51.0), Some((101, 51)));
+
+        // Backends that capture at logical size get half the coordinates
+        let logical = capture(rect, (1280, 720));
+        assert_eq!(logical.pixel_at(2560.0 + 101.0, 51.0), Some((50, 25)));
+        assert_eq!(logical.pixel_at(2560.0 + 2559.9, 1439.9), Some((1279, 719)));
+    }
+
+    #[test]
+    fn pixel_at_handles_fractional_scale() {
+        let screen = capture(to_physical_rect([0, -720], [1280, 720], 1.5, true), (1920, 1080));
+        assert_eq!((screen.rect.y, screen.rect.height), (-1080, 1080));
+        assert_eq!(screen.pixel_at(960.0, -540.0), Some((960, 540)));
+        assert_eq!(capture(screen.rect, (0, 0)).pixel_at(960.0, -540.0), None);
+    }
+
+    #[test]
+    fn locate_picks_the_monitor_under_the_point() {
+        let captures = vec![
+            capture(to_physical_rect([0, 0], [1920, 1080], 1.0, true), (1920, 1080)),
+            capture(to_physical_rect([1920, 0], [1280, 720], 2.0, true), (2560, 1440)),
+            capture(to_physical_rect([-1280, 0], [1280, 1024], 1.0, true), (1280, 1024)),
+        ];
+
+        let (screen, pixel) = locate(&captures, 3840.0 + 10.0, 20.0).unwrap();
+        assert_eq!((screen.rect.x, pixel), (3840, (10, 20)));
+
+        let (screen, pixel) = locate(&captures, -1.0, 1023.0).unwrap();
+        assert_eq!((screen.rect.x, pixel), (-1280, (1279, 1023)));
+
+        // Gap between the primary and the offset monitor, and below the short one
+        assert!(locate(&captures, 1920.0 + 100.0, 500.0).is_none());
+        assert!(locate(&captures, 4000.0, 1440.0).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::capture::{self, CaptureError};
+use image::{imageops::FilterType, DynamicImage, Rgba, RgbaImage};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClipboardImageError {
+    #[error("Failed to open image: {0}")]
+    Open(#[from] image::ImageError),
+    #[error("Clipboard image is {len} bytes, expected {width}x{height} RGBA")]
+    InvalidBuffer { width: u32, height: u32, len: usize },
+    #[error(transparent)]
+    Encode(#[from] CaptureError),
+}
+
+/// Longest side put on or taken from the clipboard. Larger images are
+/// downscaled; some clipboard owners and vision models reject huge bitmaps.
+pub const MAX_CLIPBOARD_DIMENSION: u32 = 8192;
+
+/// Size that fits within `max` on both sides, keeping aspect ratio
+pub fn fit_within(width: u32, height: u32, max: u32) -> (u32, u32) {
+    if width <= max && height <= max {
+        return (width, height);
+    }
+    let scale = max as f64 / width.max(height) as f64;
+    (
+        ((width as f64 * scale).round() as u32).clamp(1, max),
+        ((height as f64 * scale).round() as u32).clamp(1, max),
+    )
+}
+
+fn downscale(img: RgbaImage, max: u32) -> RgbaImage {
+    let (w, h) = fit_within(img.width(), img.height(), max);
+    if (w, h) == img.dimensions() {
+        img
+    } else {
+        image::imageops::resize(&img, w, h, FilterType::Triangle)
+    }
+}
+
+/// RGBA8 ready for the clipboard, downscaled if larger than `max` on either side
+pub fn prepare_for_clipboard(img: DynamicImage, max: u32) -> RgbaImage {
+    downscale(img.into_rgba8(), max)
+}
+
+/// Load an image file for copying to the clipboard
+pub fn load_for_clipboard(path: &str) -> Result<RgbaImage, ClipboardImageError> {
+    Ok(prepare_for_clipboard(image::open(path)?, MAX_CLIPBOARD_DIMENSION))
+}
+
+/// Wrap a raw clipboard RGBA buffer. Windows 24/32-bit DIBs often carry an
+/// unused alpha channel of all zeros; those are treated as fully opaque.
+pub fn from_clipboard_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<RgbaImage, ClipboardImageError> {
+    let len = rgba.len();
+    let mut img = RgbaImage::from_raw(width, height, rgba)
+        .filter(|_| len == width as usize * height as usize * 4)
+        .ok_or(ClipboardImageError::InvalidBuffer { width, height, len })?;
+    if img.pixels().all(|p| p[3] == 0) {
+        for p in img.pixels_mut() {
+            p[3] = 255;
+        }
+    }
+    Ok(img)
+}
+
+/// Composite translucent pixels over `background`, leaving an opaque image
+pub fn flatten_alpha(img: &mut RgbaImage, background: [u8; 3]) {
+    for p in img.pixels_mut() {
+        let a = p[3] as u32;
+        if a == 255 {
+            continue;
+        }
+        let blend = |c: u8, bg: u8| ((c as u32 * a + bg as u32 * (255 - a) + 127) / 255) as u8;
+        *p = Rgba([blend(p[0], background[0]), blend(p[1], background[1]), blend(p[2], background[2]), 255]);
+    }
+}
+
+/// A pasted clipboard image as an opaque base64 PNG; returns (base64, width, height)
+pub fn encode_pasted(rgba: Vec<u8>, width: u32, height: u32) -> Result<(String, u32, u32), ClipboardImageError> {
+    let mut img = downscale(from_clipboard_rgba(rgba, width, height)?, MAX_CLIPBOARD_DIMENSION);
+    flatten_alpha(&mut img, [255, 255, 255]);
+    let (w, h) = img.dimensions();
+    Ok((capture::encode_png_base64(&DynamicImage::ImageRgba8(img))?, w, h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_within_keeps_small_images_and_aspect_ratio() {
+        assert_eq!(fit_within(800, 600, 8192), (800, 600));
+        assert_eq!(fit_within(8192, 8192, 8192), (8192, 8192));
+        assert_eq!(fit_within(16384, 8192, 8192), (8192, 4096));
+        assert_eq!(fit_within(3000, 12000, 8192), (2048, 8192));
+        assert_eq!(fit_within(1000, 500, 100), (100, 50));
+    }
+
+    #[test]
+    fn fit_within_never_collapses_a_side_to_zero() {
+        assert_eq!(fit_within(100_000, 1, 8192), (8192, 1));
+        assert_eq!(fit_within(1, 100_000, 100), (1, 100));
+    }
+
+    #[test]
+    fn flatten_alpha_composites_over_background() {
+        let mut img = RgbaImage::from_vec(3, 1, vec![10, 20, 30, 255, 255, 0, 0, 0, 0, 0, 255, 128]).unwrap();
+        flatten_alpha(&mut img, [255, 255, 255]);
+        assert_eq!(img.get_pixel(0, 0), &Rgba([10, 20, 30, 255]));
+        assert_eq!(img.get_pixel(1, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(img.get_pixel(2, 0), &Rgba([127, 127, 255, 255]));
+    }
+
+    #[test]
+    fn from_clipboard_rgba_treats_all_zero_alpha_as_opaque() {
+        let img = from_clipboard_rgba(vec![1, 2, 3, 0, 4, 5, 6, 0], 2, 1).unwrap();
+        assert!(img.pixels().all(|p| p[3] == 255));
+        assert_eq!(img.get_pixel(1, 0), &Rgba([4, 5, 6, 255]));
+    }
+
+    #[test]
+    fn from_clipboard_rgba_keeps_real_alpha() {
+        let img = from_clipboard_rgba(vec![1, 2, 3, 0, 4, 5, 6, 200], 2, 1).unwrap();
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        assert_eq!(img.get_pixel(1, 0)[3], 200);
+    }
+
+    #[test]
+    fn from_clipboard_rgba_rejects_mismatched_buffers() {
+        for (len, width, height) in [(7, 2, 1), (12, 2, 1), (0, 1, 1)] {
+            match from_clipboard_rgba(vec![0; len], width, height) {
+                Err(ClipboardImageError::InvalidBuffer { len: got, .. }) => assert_eq!(got, len),
+                other => panic!("expected InvalidBuffer for {len} bytes, got {other:?}"),
+            }
+        }
+    }
+}
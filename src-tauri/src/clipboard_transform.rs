@@ -0,0 +1,557 @@
+use crate::config::{self, ConfigError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TransformError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Not a number: '{0}'")]
+    NotANumber(String),
+    #[error("Tick size must be positive")]
+    InvalidTick,
+    #[error("No tick size set for '{0}'")]
+    UnknownTick(String),
+    #[error("Source currency unknown; set 'from' on the convert step")]
+    UnknownCurrency,
+    #[error("No conversion rate for '{0}'")]
+    UnknownRate(String),
+    #[error("No transform preset named '{0}'")]
+    UnknownPreset(String),
+}
+
+/// Currency every rate is expressed in
+pub const RATE_BASE: &str = "USD";
+
+/// Digit grouping and decimal mark
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberLocale {
+    /// 1234.56
+    #[default]
+    Plain,
+    /// 1,234.56
+    En,
+    /// 1.234,56
+    De,
+    /// 1 234,56
+    Fr,
+    /// 1'234.56
+    Ch,
+}
+
+impl NumberLocale {
+    fn separators(self) -> (Option<char>, char) {
+        match self {
+            NumberLocale::Plain => (None, '.'),
+            NumberLocale::En => (Some(','), '.'),
+            NumberLocale::De => (Some('.'), ','),
+            NumberLocale::Fr => (Some(' '), ','),
+            NumberLocale::Ch => (Some('\''), '.'),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundMode {
+    #[default]
+    Nearest,
+    Down,
+    Up,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TransformStep {
+    /// Parse only; without a later `format` the output is plain `1234.56`
+    Normalize,
+    /// Round to `tick`, or to the tick size configured for `symbol`
+    RoundTick {
+        #[serde(default)]
+        tick: Option<f64>,
+        #[serde(default)]
+        symbol: Option<String>,
+        #[serde(default)]
+        mode: RoundMode,
+    },
+    /// Convert using the user's rates; `from` defaults to the currency in the text
+    Convert {
+        to: String,
+        #[serde(default)]
+        from: Option<String>,
+    },
+    Format {
+        locale: NumberLocale,
+        /// Keeps the current precision when unset
+        #[serde(default)]
+        decimals: Option<usize>,
+        /// Append the currency code, if known
+        #[serde(default)]
+        currency: bool,
+    },
+}
+
+/// A number read from clipboard text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedValue {
+    pub value: f64,
+    /// Digits after the decimal mark in the source text
+    pub decimals: usize,
+    /// Upper-case code; symbols are mapped ($ -> USD)
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransformResult {
+    pub input: String,
+    pub output: String,
+    pub value: f64,
+    pub currency: Option<String>,
+}
+
+fn value_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^\s*([-+])?\s*(?:([$€£¥])|([A-Za-z]{2,6})\s+)?\s*([-+])?(\d(?:[\d.,'\s\x{a0}\x{202f}]*\d)?)\s*([$€£¥]|[A-Za-z]{2,6})?\s*$",
+        )
+        .unwrap()
+    })
+}
+
+/// Codes accepted around a number. Any other word ("Mar 2024", "Room 101")
+/// means the text is not a price.
+const CURRENCY_CODES: &[&str] = &[
+    "USD", "USDT", "USDC", "BUSD", "DAI", "EUR", "GBP", "JPY", "CHF", "CAD", "AUD", "NZD", "CNY", "HKD", "SGD", "KRW",
+    "INR", "SEK", "NOK", "DKK", "PLN", "TRY", "BRL", "MXN", "ZAR", "BTC", "XBT", "ETH", "SOL", "BNB", "XRP",
+];
+
+fn currency_code(raw: &str) -> String {
+    match raw {
+        "$" => "USD".to_string(),
+        "€" => "EUR".to_string(),
+        "£" => "GBP".to_string(),
+        "¥" => "JPY".to_string(),
+        code => code.to_uppercase(),
+    }
+}
+
+/// Split a digit string into (value, decimals). A lone separator followed by
+/// exactly three digits is ambiguous: `decimal_mark` decides it, otherwise a
+/// comma is read as grouping ("3,151") and a dot as decimal ("0.015").
+/// Grouped digits must come in threes, so dates like "2024.01.05" are rejected.
+pub fn parse_digits(body: &str, decimal_mark: Option<char>) -> Option<(f64, usize)> {
+    let dots = body.matches('.').count();
+    let commas = body.matches(',').count();
+
+    let decimal = match (dots, commas) {
+        (0, 0) => None,
+        (_, 0) | (0, _) => {
+            let sep = if dots > 0 { '.' } else { ',' };
+            if dots + commas > 1 {
+                None
+            } else {
+                let after = body.len() - body.find(sep)? - 1;
+                match decimal_mark {
+                    Some(mark) => (mark == sep).then_some(sep),
+                    None if after == 3 && sep == ',' => None,
+                    None => Some(sep),
+                }
+            }
+        }
+        _ => {
+            // Both present: the last one is the decimal mark
+            let sep = if body.rfind('.')? > body.rfind(',')? { '.' } else { ',' };
+            if body.matches(sep).count() > 1 {
+                return None;
+            }
+            Some(sep)
+        }
+    };
+
+    let (int_part, frac_part) = match decimal {
+        Some(sep) => body.rsplit_once(sep)?,
+        None => (body, ""),
+    };
+    if !frac_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let groups: Vec<&str> = int_part.split(|c: char| !c.is_ascii_digit()).collect();
+    if groups.len() > 1 && (!(1..=3).contains(&groups[0].len()) || groups[1..].iter().any(|g| g.len() != 3)) {
+        return None;
+    }
+    let plain = if frac_part.is_empty() { groups.concat() } else { format!("{}.{}", groups.concat(), frac_part) };
+    let value = plain.parse::<f64>().ok().filter(|v| v.is_finite())?;
+    Some((value, frac_part.len()))
+}
+
+/// Read a price such as "3,151.25", "3.151,25 USD", "$3151.250" or "-0.12 USDT".
+/// The whole text must be the number plus an optional currency symbol or a
+/// code from `CURRENCY_CODES`.
+pub fn parse_value(text: &str, decimal_mark: Option<char>) -> Option<ParsedValue> {
+    let caps = value_regex().captures(text)?;
+    let prefix = caps.get(2).or(caps.get(3)).map(|m| m.as_str());
+    let suffix = caps.get(6).map(|m| m.as_str());
+    if prefix.is_some() && suffix.is_some() {
+        return None;
+    }
+    let currency = prefix.or(suffix).map(currency_code);
+    if currency.as_deref().is_some_and(|code| !CURRENCY_CODES.contains(&code)) {
+        return None;
+    }
+    let negative = [caps.get(1), caps.get(4)].iter().flatten().any(|m| m.as_str() == "-");
+    let (value, decimals) = parse_digits(caps.get(5)?.as_str(), decimal_mark)?;
+    Some(ParsedValue { value: if negative { -value } else { value }, decimals, currency })
+}
+
+/// Decimal places needed to show `tick` exactly, e.g. 0.25 -> 2
+pub fn tick_decimals(tick: f64) -> usize {
+    (0..=10)
+        .find(|&d| {
+            let scaled = tick * 10f64.powi(d as i32);
+            (scaled - scaled.round()).abs() < 1e-9 * scaled.abs().max(1.0)
+        })
+        .unwrap_or(10)
+}
+
+pub fn round_to_tick(value: f64, tick: f64, mode: RoundMode) -> f64 {
+    let steps = value / tick;
+    // Absorb float noise so 3151.25 / 0.01 doesn't floor to 315124
+    let steps = match mode {
+        RoundMode::Nearest => steps.round(),
+        RoundMode::Down => (steps + 1e-9).floor(),
+        RoundMode::Up => (steps - 1e-9).ceil(),
+    };
+    let decimals = tick_decimals(tick) as i32;
+    let factor = 10f64.powi(decimals);
+    (steps * tick * factor).round() / factor
+}
+
+/// Precision after a currency conversion: at least 2 decimals and about
+/// 6 significant digits, capped at 8
+fn converted_decimals(value: f64) -> usize {
+    let magnitude = value.abs().log10().floor();
+    if !magnitude.is_finite() {
+        return 2;
+    }
+    (5 - magnitude as i32).clamp(2, 8) as usize
+}
+
+pub fn format_value(value: f64, decimals: usize, locale: NumberLocale) -> String {
+    let (group, decimal) = locale.separators();
+    let fixed = format!("{:.*}", decimals, value.abs());
+    let (int_part, frac_part) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut out = String::new();
+    if value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.push('-');
+    }
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            if let Some(g) = group {
+                out.push(g);
+            }
+        }
+        out.push(c);
+    }
+    if !frac_part.is_empty() {
+        out.push(decimal);
+        out.push_str(frac_part);
+    }
+    out
+}
+
+/// Rates, tick sizes and saved pipelines
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransformSettings {
+    /// Value of one unit in USD, e.g. {"EUR": 1.08, "BTC": 67000}
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>,
+    /// Tick size per symbol, e.g. {"BTCUSDT": 0.1}
+    #[serde(default)]
+    pub ticks: BTreeMap<String, f64>,
+    /// Forces how an ambiguous "3,151" / "3.151" is read
+    #[serde(default)]
+    pub decimal_mark: Option<char>,
+    #[serde(default)]
+    pub presets: BTreeMap<String, Vec<TransformStep>>,
+    /// Preset applied to every new text copy that is a plain number
+    #[serde(default)]
+    pub auto_apply: Option<String>,
+}
+
+fn settings_path() -> Result<PathBuf, TransformError> {
+    Ok(config::get_app_dir()?.join("clipboard_transforms.json"))
+}
+
+pub fn load_settings() -> Result<TransformSettings, TransformError> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(TransformSettings::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_settings(settings: &TransformSettings) -> Result<(), TransformError> {
+    fs::write(settings_path()?, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+impl TransformSettings {
+    fn rate(&self, code: &str) -> Result<f64, TransformError> {
+        if code == RATE_BASE {
+            return Ok(1.0);
+        }
+        self.rates
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(code))
+            .map(|(_, &v)| v)
+            .filter(|v| *v > 0.0 && v.is_finite())
+            .ok_or_else(|| TransformError::UnknownRate(code.to_string()))
+    }
+
+    fn tick_for(&self, symbol: &str) -> Result<f64, TransformError> {
+        self.ticks
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(symbol.trim()))
+            .map(|(_, &v)| v)
+            .ok_or_else(|| TransformError::UnknownTick(symbol.to_string()))
+    }
+
+    pub fn preset(&self, name: &str) -> Result<&[TransformStep], TransformError> {
+        self.presets.get(name).map(Vec::as_slice).ok_or_else(|| TransformError::UnknownPreset(name.to_string()))
+    }
+
+    /// Run `steps` over `text`
+    pub fn apply(&self, text: &str, steps: &[TransformStep]) -> Result<TransformResult, TransformError> {
+        let parsed = parse_value(text, self.decimal_mark).ok_or_else(|| TransformError::NotANumber(text.trim().to_string()))?;
+        let (mut value, mut decimals, mut currency) = (parsed.value, parsed.decimals, parsed.currency);
+        let mut output = None;
+
+        for step in steps {
+            match step {
+                TransformStep::Normalize => output = None,
+                TransformStep::RoundTick { tick, symbol, mode } => {
+                    let tick = match (tick, symbol) {
+                        (Some(tick), _) => *tick,
+                        (None, Some(symbol)) => self.tick_for(symbol)?,
+                        (None, None) => return Err(TransformError::InvalidTick),
+                    };
+                    if tick <= 0.0 || !tick.is_finite() {
+                        return Err(TransformError::InvalidTick);
+                    }
+                    value = round_to_tick(value, tick, *mode);
+                    decimals = tick_decimals(tick);
+                    output = None;
+                }
+                TransformStep::Convert { to, from } => {
+                    let from = from.clone().or(currency.clone()).ok_or(TransformError::UnknownCurrency)?.to_uppercase();
+                    let to = to.trim().to_uppercase();
+                    if from != to {
+                        value = value * self.rate(&from)? / self.rate(&to)?;
+                        decimals = converted_decimals(value);
+                    }
+                    currency = Some(to);
+                    output = None;
+                }
+                TransformStep::Format { locale, decimals: places, currency: with_currency } => {
+                    if let Some(places) = places {
+                        decimals = *places;
+                    }
+                    let mut text = format_value(value, decimals, *locale);
+                    if let (true, Some(code)) = (with_currency, &currency) {
+                        text = format!("{} {}", text, code);
+                    }
+                    output = Some(text);
+                }
+            }
+        }
+
+        Ok(TransformResult {
+            input: text.to_string(),
+            output: output.unwrap_or_else(|| format_value(value, decimals, NumberLocale::Plain)),
+            value,
+            currency,
+        })
+    }
+
+    /// The auto-apply result for a new clipboard text, if it changes anything
+    pub fn auto_transform(&self, text: &str) -> Option<String> {
+        let steps = self.preset(self.auto_apply.as_deref()?).ok()?;
+        let result = self.apply(text, steps).ok()?;
+        (result.output != text).then_some(result.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(value: f64, decimals: usize, currency: Option<&str>) -> Option<ParsedValue> {
+        Some(ParsedValue { value, decimals, currency: currency.map(str::to_string) })
+    }
+
+    #[test]
+    fn parse_value_reads_prices_with_grouping_and_currency() {
+        let cases: [(&str, f64, usize, Option<&str>); 9] = [
+            ("3,151.25", 3151.25, 2, None),
+            ("3.151,25 USD", 3151.25, 2, Some("USD")),
+            ("$3151.250", 3151.25, 3, Some("USD")),
+            ("-0.12 USDT", -0.12, 2, Some("USDT")),
+            ("€ 1 234,56", 1234.56, 2, Some("EUR")),
+            ("1'234.56", 1234.56, 2, None),
+            ("  64250  ", 64250.0, 0, None),
+            ("eur 12,5", 12.5, 1, Some("EUR")),
+            ("- £0.5", -0.5, 1, Some("GBP")),
+        ];
+        for (text, value, decimals, currency) in cases {
+            assert_eq!(parse_value(text, None), parsed(value, decimals, currency), "{text}");
+        }
+    }
+
+    #[test]
+    fn parse_value_resolves_a_lone_separator_before_three_digits() {
+        let cases = [
+            ("3,151", None, 3151.0, 0),
+            ("3.151", None, 3.151, 3),
+            ("3,151", Some('.'), 3151.0, 0),
+            ("3.151", Some('.'), 3.151, 3),
+            ("3,151", Some(','), 3.151, 3),
+            ("3.151", Some(','), 3151.0, 0),
+            ("3,15", None, 3.15, 2),
+        ];
+        for (text, mark, value, decimals) in cases {
+            assert_eq!(parse_value(text, mark), parsed(value, decimals, None), "{text} with {mark:?}");
+        }
+    }
+
+    #[test]
+    fn parse_value_leaves_other_text_alone() {
+        let cases = [
+            "Mar 2024",
+            "Page 12",
+            "Room 101",
+            "12 apples",
+            "BTCUSDT",
+            "2024.01.05",
+            "1,23,456",
+            "$5 USD",
+            "10:30",
+            "3.151.25",
+            "",
+        ];
+        for text in cases {
+            assert_eq!(parse_value(text, None), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn round_to_tick_follows_the_mode() {
+        let cases = [
+            (3151.27, 0.25, RoundMode::Nearest, 3151.25),
+            (3151.27, 0.25, RoundMode::Down, 3151.25),
+            (3151.27, 0.25, RoundMode::Up, 3151.5),
+            (3151.25, 0.01, RoundMode::Down, 3151.25),
+            (3151.25, 0.01, RoundMode::Up, 3151.25),
+            (64123.46, 0.1, RoundMode::Nearest, 64123.5),
+            (64123.46, 0.1, RoundMode::Down, 64123.4),
+            (-3151.27, 0.25, RoundMode::Down, -3151.5),
+            (-3151.27, 0.25, RoundMode::Up, -3151.25),
+            (1234.0, 5.0, RoundMode::Nearest, 1235.0),
+        ];
+        for (value, tick, mode, expected) in cases {
+            assert_eq!(round_to_tick(value, tick, mode), expected, "{value} to {tick} {mode:?}");
+        }
+        assert_eq!(tick_decimals(0.25), 2);
+        assert_eq!(tick_decimals(0.1), 1);
+        assert_eq!(tick_decimals(5.0), 0);
+        assert_eq!(tick_decimals(0.00001), 5);
+    }
+
+    #[test]
+    fn format_value_uses_the_locale_separators() {
+        let cases = [
+            (NumberLocale::Plain, "1234567.89"),
+            (NumberLocale::En, "1,234,567.89"),
+            (NumberLocale::De, "1.234.567,89"),
+            (NumberLocale::Fr, "1 234 567,89"),
+            (NumberLocale::Ch, "1'234'567.89"),
+        ];
+        for (locale, expected) in cases {
+            assert_eq!(format_value(1234567.891, 2, locale), expected, "{locale:?}");
+        }
+        assert_eq!(format_value(-1234.5, 2, NumberLocale::En), "-1,234.50");
+        assert_eq!(format_value(-0.001, 2, NumberLocale::En), "0.00");
+        assert_eq!(format_value(999.6, 0, NumberLocale::En), "1,000");
+    }
+
+    fn settings() -> TransformSettings {
+        TransformSettings {
+            rates: BTreeMap::from([("EUR".to_string(), 1.08)]),
+            ticks: BTreeMap::from([("BTCUSDT".to_string(), 0.1)]),
+            presets: BTreeMap::from([(
+                "de".to_string(),
+                vec![TransformStep::Format { locale: NumberLocale::De, decimals: None, currency: false }],
+            )]),
+            auto_apply: Some("de".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn convert(to: &str) -> TransformStep {
+        TransformStep::Convert { to: to.to_string(), from: None }
+    }
+
+    #[test]
+    fn apply_converts_rounds_and_formats() {
+        let settings = settings();
+        let format_en = TransformStep::Format { locale: NumberLocale::En, decimals: Some(2), currency: true };
+
+        let result = settings.apply("100 EUR", &[convert("USD")]).unwrap();
+        assert_eq!((result.output.as_str(), result.currency.as_deref()), ("108.000", Some("USD")));
+        assert_eq!(settings.apply("100 EUR", &[convert("usd"), format_en.clone()]).unwrap().output, "108.00 USD");
+        assert_eq!(settings.apply("$1,000", &[convert("EUR")]).unwrap().output, "925.926");
+
+        let by_symbol = TransformStep::RoundTick { tick: None, symbol: Some("btcusdt".to_string()), mode: RoundMode::Nearest };
+        assert_eq!(settings.apply("64,123.46", &[by_symbol]).unwrap().output, "64123.5");
+        let quarter = TransformStep::RoundTick { tick: Some(0.25), symbol: None, mode: RoundMode::Up };
+        assert_eq!(settings.apply("3.151,27", &[quarter, format_en]).unwrap().output, "3,151.50");
+
+        let keep_precision = TransformStep::Format { locale: NumberLocale::De, decimals: None, currency: true };
+        assert_eq!(settings.apply("$3151.250", &[keep_precision]).unwrap().output, "3.151,250 USD");
+        assert_eq!(settings.apply("3,151.25", &[TransformStep::Normalize]).unwrap().output, "3151.25");
+    }
+
+    #[test]
+    fn apply_reports_missing_inputs() {
+        let settings = settings();
+        let unknown_tick = TransformStep::RoundTick { tick: None, symbol: Some("ETHUSDT".to_string()), mode: RoundMode::Nearest };
+        let zero_tick = TransformStep::RoundTick { tick: Some(0.0), symbol: None, mode: RoundMode::Nearest };
+
+        assert!(matches!(settings.apply("Room 101", &[]), Err(TransformError::NotANumber(_))));
+        assert!(matches!(settings.apply("100", &[convert("EUR")]), Err(TransformError::UnknownCurrency)));
+        assert!(matches!(settings.apply("100 GBP", &[convert("USD")]), Err(TransformError::UnknownRate(c)) if c == "GBP"));
+        assert!(matches!(settings.apply("100", &[unknown_tick]), Err(TransformError::UnknownTick(_))));
+        assert!(matches!(settings.apply("100", &[zero_tick]), Err(TransformError::InvalidTick)));
+    }
+
+    #[test]
+    fn auto_transform_only_rewrites_numbers_it_changes() {
+        let settings = settings();
+        assert_eq!(settings.auto_transform("3,151.25"), Some("3.151,25".to_string()));
+        assert_eq!(settings.auto_transform("3.151,25"), None);
+        assert_eq!(settings.auto_transform("Mar 2024"), None);
+        assert_eq!(settings.auto_transform("Page 12"), None);
+        assert_eq!(TransformSettings::default().auto_transform("3,151.25"), None);
+    }
+}
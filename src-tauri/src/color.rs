@@ -0,0 +1,586 @@
+use crate::capture::{self, CaptureError};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ColorError {
+    #[error("Invalid color '{0}'")]
+    InvalidColor(String),
+    #[error("Failed to open image: {0}")]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+}
+
+/// Largest NxN area averaged by a single sample
+pub const MAX_SAMPLE_SIZE: u32 = 31;
+
+/// A color in the formats the color module displays
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColorSample {
+    pub hex: String,
+    pub rgb: [u8; 3],
+    /// Hue in degrees, saturation and lightness in percent
+    pub hsl: [f64; 3],
+    /// OKLCH lightness (0-1), chroma and hue in degrees
+    pub oklch: [f64; 3],
+}
+
+impl ColorSample {
+    pub fn from_rgb(rgb: [u8; 3]) -> Self {
+        Self {
+            hex: to_hex(rgb),
+            rgb,
+            hsl: rgb_to_hsl(rgb),
+            oklch: rgb_to_oklch(rgb),
+        }
+    }
+}
+
+pub fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Parse `#rgb` or `#rrggbb` (the `#` is optional)
+pub fn parse_hex(raw: &str) -> Result<[u8; 3], ColorError> {
+    let hex = raw.trim().trim_start_matches('#');
+    let invalid = || ColorError::InvalidColor(raw.to_string());
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return Err(invalid()),
+    };
+    let channel = |i: usize| u8::from_str_radix(expanded.get(i..i + 2).ok_or_else(invalid)?, 16).map_err(|_| invalid());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn round_to(v: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (v * factor).round() / factor
+}
+
+/// Average the NxN block centered on (x, y). Even sizes are rounded up to
+/// the next odd size and the block is clipped at the image edges.
+pub fn average_area(img: &RgbaImage, x: u32, y: u32, size: u32) -> [u8; 3] {
+    let half = size.clamp(1, MAX_SAMPLE_SIZE) / 2;
+    let x0 = x.saturating_sub(half);
+    let y0 = y.saturating_sub(half);
+    let x1 = (x + half).min(img.width().saturating_sub(1));
+    let y1 = (y + half).min(img.height().saturating_sub(1));
+
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for py in y0..=y1 {
+        for px in x0..=x1 {
+            let p = img.get_pixel(px, py);
+            for (s, c) in sum.iter_mut().zip(p.0) {
+                *s += c as u64;
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return [0, 0, 0];
+    }
+    sum.map(|s| ((s as f64) / (count as f64)).round() as u8)
+}
+
+pub fn rgb_to_hsl([r, g, b]: [u8; 3]) -> [f64; 3] {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return [0.0, 0.0, round_to(l * 100.0, 1)];
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    [round_to(h, 1), round_to(s * 100.0, 1), round_to(l * 100.0, 1)]
+}
+
+/// sRGB channel (0-255) to linear light (0-1)
+pub fn srgb_to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// Linear light (0-1) to an sRGB channel, clamped
+pub fn linear_to_srgb(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let v = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (v * 255.0).round() as u8
+}
+
+pub fn rgb_to_oklab(rgb: [u8; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+pub fn rgb_to_oklch(rgb: [u8; 3]) -> [f64; 3] {
+    let [l, a, b] = rgb_to_oklab(rgb);
+    let c = (a * a + b * b).sqrt();
+    // Hue is meaningless for achromatic colors
+    let h = if c < 1e-4 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    [round_to(l, 4), round_to(c, 4), round_to(h, 2)]
+}
+
+/// Largest loupe grid side
+pub const MAX_LOUPE_SIZE: u32 = 41;
+
+/// Zoomed pixel grid around a point, row by row. Pixels beyond the screen edge are `None`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Loupe {
+    pub size: u32,
+    pub pixels: Vec<Option<[u8; 3]>>,
+    /// The exact pixel under the cursor
+    pub center: ColorSample,
+}
+
+/// Build a size x size grid centered on (x, y). Even sizes are rounded up.
+pub fn loupe(img: &RgbaImage, x: u32, y: u32, size: u32) -> Loupe {
+    let half = size.clamp(1, MAX_LOUPE_SIZE) / 2;
+    let side = half * 2 + 1;
+    let mut pixels = Vec::with_capacity((side * side) as usize);
+    for dy in 0..side {
+        for dx in 0..side {
+            let px = (x + dx).checked_sub(half);
+            let py = (y + dy).checked_sub(half);
+            pixels.push(match (px, py) {
+                (Some(px), Some(py)) if px < img.width() && py < img.height() => {
+                    let [r, g, b, _] = img.get_pixel(px, py).0;
+                    Some([r, g, b])
+                }
+                _ => None,
+            });
+        }
+    }
+    let [r, g, b, _] = img.get_pixel(x, y).0;
+    Loupe { size: side, pixels, center: ColorSample::from_rgb([r, g, b]) }
+}
+
+/// Images are downscaled to this many pixels on the long side before quantizing
+const PALETTE_SAMPLE_DIM: u32 = 256;
+pub const MAX_PALETTE_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteColor {
+    pub color: ColorSample,
+    /// Fraction of sampled pixels in this color's bucket
+    pub share: f64,
+}
+
+/// Dominant colors by median cut, most common first. Deterministic for a
+/// given image; fully transparent pixels are ignored.
+pub fn extract_palette(img: &RgbaImage, count: usize) -> Vec<PaletteColor> {
+    let count = count.clamp(1, MAX_PALETTE_SIZE);
+    let long_side = img.width().max(img.height());
+    let sample = if long_side > PALETTE_SAMPLE_DIM {
+        let scale = |v: u32| ((v as u64 * PALETTE_SAMPLE_DIM as u64) / long_side as u64).max(1) as u32;
+        image::imageops::thumbnail(img, scale(img.width()), scale(img.height()))
+    } else {
+        img.clone()
+    };
+
+    let pixels: Vec<[u8; 3]> = sample.pixels().filter(|p| p.0[3] > 0).map(|p| [p.0[0], p.0[1], p.0[2]]).collect();
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+    let total = pixels.len() as f64;
+
+    let mut buckets = vec![pixels];
+    while buckets.len() < count {
+        // Split the bucket with the widest channel range
+        let Some((index, channel, range)) = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (i, channel, range)
+            })
+            .max_by_key(|&(i, _, range)| (range, std::cmp::Reverse(i)))
+        else {
+            break;
+        };
+        if range == 0 {
+            break;
+        }
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_unstable_by_key(|p| (p[channel], p[(channel + 1) % 3], p[(channel + 2) % 3]));
+        // Split at the value boundary closest to the median so one color never lands in both halves
+        let mid = bucket.len() / 2;
+        let split = (1..bucket.len())
+            .filter(|&i| bucket[i - 1][channel] != bucket[i][channel])
+            .min_by_key(|&i| i.abs_diff(mid))
+            .unwrap_or(mid);
+        let upper = bucket.split_off(split);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    let mut palette: Vec<PaletteColor> = buckets
+        .iter()
+        .filter(|b| !b.is_empty())
+        .map(|b| {
+            let mut sum = [0u64; 3];
+            for p in b {
+                for (s, &c) in sum.iter_mut().zip(p) {
+                    *s += c as u64;
+                }
+            }
+            let avg = sum.map(|s| (s as f64 / b.len() as f64).round() as u8);
+            PaletteColor { color: ColorSample::from_rgb(avg), share: round_to(b.len() as f64 / total, 4) }
+        })
+        .collect();
+    palette.sort_by(|a, b| b.share.total_cmp(&a.share).then_with(|| a.color.hex.cmp(&b.color.hex)));
+    palette
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(lo, hi), p| (lo.min(p[c]), hi.max(p[c])));
+            (c, max.saturating_sub(min))
+        })
+        .max_by_key(|&(c, range)| (range, std::cmp::Reverse(c)))
+        .unwrap_or((0, 0))
+}
+
+/// Palette of an image file, optionally cropped to [x, y, width, height]
+pub fn palette_from_path(path: &str, region: Option<[i32; 4]>, count: usize) -> Result<Vec<PaletteColor>, ColorError> {
+    let mut img = image::open(path)?;
+    if let Some([x, y, w, h]) = region {
+        img = img.crop_imm(x.max(0) as u32, y.max(0) as u32, w.max(1) as u32, h.max(1) as u32);
+    }
+    Ok(extract_palette(&img.to_rgba8(), count))
+}
+
+/// Palette of a live screen capture region
+pub fn palette_from_screen(region: Option<[i32; 4]>, count: usize) -> Result<Vec<PaletteColor>, ColorError> {
+    Ok(extract_palette(&capture::capture_image(region)?.to_rgba8(), count))
+}
+
+/// WCAG 2 relative luminance
+pub fn relative_luminance(rgb: [u8; 3]) -> f64 {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// WCAG 2 contrast ratio, 1.0 to 21.0
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorVision {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    Achromatopsia,
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 4] = [
+        ColorVision::Protanopia,
+        ColorVision::Deuteranopia,
+        ColorVision::Tritanopia,
+        ColorVision::Achromatopsia,
+    ];
+}
+
+/// Simulate how a color appears with a color vision deficiency.
+/// Dichromacies use the Machado et al. (2009) full-severity matrices.
+pub fn simulate(rgb: [u8; 3], vision: ColorVision) -> [u8; 3] {
+    let lin = rgb.map(srgb_to_linear);
+    let matrix: [[f64; 3]; 3] = match vision {
+        ColorVision::Protanopia => [
+            [0.152286, 1.052583, -0.204868],
+            [0.114503, 0.786281, 0.099216],
+            [-0.003882, -0.048116, 1.051998],
+        ],
+        ColorVision::Deuteranopia => [
+            [0.367322, 0.860646, -0.227968],
+            [0.280085, 0.672501, 0.047413],
+            [-0.011820, 0.042940, 0.968881],
+        ],
+        ColorVision::Tritanopia => [
+            [1.255528, -0.076749, -0.178779],
+            [-0.078411, 0.930809, 0.147602],
+            [0.004733, 0.691367, 0.303900],
+        ],
+        ColorVision::Achromatopsia => {
+            let y = relative_luminance(rgb);
+            return [linear_to_srgb(y); 3];
+        }
+    };
+    matrix.map(|row| linear_to_srgb(row[0] * lin[0] + row[1] * lin[1] + row[2] * lin[2]))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedPair {
+    pub vision: ColorVision,
+    pub foreground: String,
+    pub background: String,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContrastReport {
+    pub foreground: ColorSample,
+    pub background: ColorSample,
+    pub ratio: f64,
+    /// WCAG AA: 4.5 for normal text, 3 for large text
+    pub aa_normal: bool,
+    pub aa_large: bool,
+    /// WCAG AAA: 7 for normal text, 4.5 for large text
+    pub aaa_normal: bool,
+    pub aaa_large: bool,
+    pub simulations: Vec<SimulatedPair>,
+}
+
+pub fn contrast_report(foreground: [u8; 3], background: [u8; 3]) -> ContrastReport {
+    let ratio = contrast_ratio(foreground, background);
+    let simulations = ColorVision::ALL
+        .iter()
+        .map(|&vision| {
+            let (fg, bg) = (simulate(foreground, vision), simulate(background, vision));
+            SimulatedPair {
+                vision,
+                foreground: to_hex(fg),
+                background: to_hex(bg),
+                ratio: round_to(contrast_ratio(fg, bg), 2),
+            }
+        })
+        .collect();
+    ContrastReport {
+        foreground: ColorSample::from_rgb(foreground),
+        background: ColorSample::from_rgb(background),
+        ratio: round_to(ratio, 2),
+        aa_normal: ratio >= 4.5,
+        aa_large: ratio >= 3.0,
+        aaa_normal: ratio >= 7.0,
+        aaa_large: ratio >= 4.5,
+        simulations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+
+    /// Image filled with horizontal bands of (color, rows)
+    fn bands(width: u32, bands: &[([u8; 4], u32)]) -> RgbaImage {
+        let height = bands.iter().map(|(_, rows)| rows).sum();
+        let mut img = RgbaImage::new(width, height);
+        let mut y0 = 0;
+        for &(color, rows) in bands {
+            for y in y0..y0 + rows {
+                for x in 0..width {
+                    img.put_pixel(x, y, Rgba(color));
+                }
+            }
+            y0 += rows;
+        }
+        img
+    }
+
+    fn summary(palette: &[PaletteColor]) -> Vec<(&str, f64)> {
+        palette.iter().map(|p| (p.color.hex.as_str(), p.share)).collect()
+    }
+
+    /// r grows with x and g with y, 30 per pixel
+    fn gradient(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| Rgba([(x * 30) as u8, (y * 30) as u8, 100, 255]))
+    }
+
+    #[test]
+    fn average_area_averages_the_block_around_the_point() {
+        let img = gradient(5, 5);
+        assert_eq!(average_area(&img, 2, 2, 1), [60, 60, 100]);
+        assert_eq!(average_area(&img, 2, 2, 3), [60, 60, 100]);
+        // 3x3 around (1, 3): x 0..=2, y 2..=4
+        assert_eq!(average_area(&img, 1, 3, 3), [30, 90, 100]);
+        // Zero is treated as a single pixel
+        assert_eq!(average_area(&img, 4, 0, 0), [120, 0, 100]);
+    }
+
+    #[test]
+    fn average_area_clips_at_the_edges_and_rounds_even_sizes_up() {
+        let img = gradient(5, 5);
+        // Only the 2x2 corner is inside the image
+        assert_eq!(average_area(&img, 0, 0, 3), [15, 15, 100]);
+        assert_eq!(average_area(&img, 4, 4, 3), [105, 105, 100]);
+        assert_eq!(average_area(&img, 1, 1, 2), average_area(&img, 1, 1, 3));
+        assert_eq!(average_area(&img, 2, 2, 4), average_area(&img, 2, 2, 5));
+        // Larger than the image: the whole image
+        assert_eq!(average_area(&img, 2, 2, 500), [60, 60, 100]);
+
+        // Halves round to the nearest value
+        let mut two = RgbaImage::new(2, 1);
+        two.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        two.put_pixel(1, 0, Rgba([255, 1, 2, 255]));
+        assert_eq!(average_area(&two, 0, 0, 3), [128, 1, 1]);
+    }
+
+    #[test]
+    fn loupe_grid_is_centered_with_empty_cells_past_the_edges() {
+        let img = gradient(5, 5);
+        let grid = loupe(&img, 2, 2, 3);
+        assert_eq!(grid.size, 3);
+        assert_eq!(grid.pixels[0], Some([30, 30, 100]));
+        assert_eq!(grid.pixels[4], Some([60, 60, 100]));
+        assert_eq!(grid.center.rgb, [60, 60, 100]);
+
+        // Top-left corner: the first row and column fall outside
+        let corner = loupe(&img, 0, 0, 3);
+        assert_eq!(corner.pixels, vec![None, None, None, None, Some([0, 0, 100]), Some([30, 0, 100]), None, Some([0, 30, 100]), Some([30, 30, 100])]);
+        // Bottom-right corner: the last row and column fall outside
+        let corner = loupe(&img, 4, 4, 3);
+        assert_eq!(corner.pixels.iter().filter(|p| p.is_none()).count(), 5);
+        assert_eq!(corner.pixels[4], Some([120, 120, 100]));
+        assert_eq!(corner.pixels[8], None);
+    }
+
+    #[test]
+    fn loupe_rounds_even_sizes_up_and_caps_the_size() {
+        let img = gradient(5, 5);
+        let even = loupe(&img, 2, 2, 4);
+        assert_eq!(even.size, 5);
+        assert_eq!(even.pixels.len(), 25);
+        assert_eq!(even.pixels[12], Some([60, 60, 100]));
+        assert!(even.pixels.iter().all(Option::is_some));
+
+        assert_eq!(loupe(&img, 2, 2, 0).pixels, vec![Some([60, 60, 100])]);
+        let huge = loupe(&img, 2, 2, 1000);
+        assert_eq!(huge.size, MAX_LOUPE_SIZE);
+        assert_eq!(huge.pixels.iter().filter(|p| p.is_some()).count(), 25);
+    }
+
+    #[test]
+    fn hsl_matches_known_colors() {
+        let cases = [
+            ([255, 0, 0], [0.0, 100.0, 50.0]),
+            ([0, 255, 0], [120.0, 100.0, 50.0]),
+            ([0, 0, 255], [240.0, 100.0, 50.0]),
+            ([255, 255, 255], [0.0, 0.0, 100.0]),
+            ([0, 0, 0], [0.0, 0.0, 0.0]),
+            ([128, 128, 128], [0.0, 0.0, 50.2]),
+            ([0x33, 0x66, 0x99], [210.0, 50.0, 40.0]),
+            ([255, 0, 128], [329.9, 100.0, 50.0]),
+        ];
+        for (rgb, hsl) in cases {
+            assert_eq!(rgb_to_hsl(rgb), hsl, "{}", to_hex(rgb));
+        }
+    }
+
+    #[test]
+    fn oklch_matches_known_colors() {
+        let cases = [
+            ([255, 0, 0], [0.628, 0.2577, 29.23]),
+            ([0, 255, 0], [0.8664, 0.2948, 142.5]),
+            ([0, 0, 255], [0.452, 0.3132, 264.05]),
+            ([255, 255, 255], [1.0, 0.0, 0.0]),
+            ([0, 0, 0], [0.0, 0.0, 0.0]),
+            ([128, 128, 128], [0.5999, 0.0, 0.0]),
+        ];
+        for (rgb, oklch) in cases {
+            let actual = rgb_to_oklch(rgb);
+            assert!(actual.iter().zip(oklch).all(|(a, e)| (a - e).abs() < 0.0011), "{}: {actual:?}", to_hex(rgb));
+        }
+        assert_eq!(ColorSample::from_rgb([255, 0, 0]).hex, "#ff0000");
+    }
+
+    #[test]
+    fn contrast_matches_known_wcag_pairs() {
+        let cases = [
+            // (foreground, background, ratio, aa_normal, aa_large, aaa_normal)
+            ("#000000", "#ffffff", 21.0, true, true, true),
+            ("#767676", "#ffffff", 4.54, true, true, false),
+            ("#777777", "#ffffff", 4.48, false, true, false),
+            ("#595959", "#ffffff", 7.0, true, true, true),
+            ("#ffffff", "#0000ff", 8.59, true, true, true),
+            ("#ff0000", "#ffffff", 4.0, false, true, false),
+            ("#336699", "#336699", 1.0, false, false, false),
+        ];
+        for (fg, bg, ratio, aa_normal, aa_large, aaa_normal) in cases {
+            let report = contrast_report(parse_hex(fg).unwrap(), parse_hex(bg).unwrap());
+            assert_eq!(report.ratio, ratio, "{fg} on {bg}");
+            assert_eq!((report.aa_normal, report.aa_large, report.aaa_normal), (aa_normal, aa_large, aaa_normal), "{fg} on {bg}");
+            assert_eq!(report.aaa_large, report.aa_normal);
+        }
+    }
+
+    #[test]
+    fn contrast_ratio_is_symmetric() {
+        let (a, b) = (parse_hex("#1e90ff").unwrap(), parse_hex("#222").unwrap());
+        assert_eq!(contrast_ratio(a, b), contrast_ratio(b, a));
+    }
+
+    #[test]
+    fn simulations_cover_every_vision_type() {
+        let report = contrast_report(BLACK, WHITE);
+        let visions: Vec<ColorVision> = report.simulations.iter().map(|s| s.vision).collect();
+        assert_eq!(visions, ColorVision::ALL);
+        // Black on white stays (close to) maximum contrast for everyone
+        assert!(report.simulations.iter().all(|s| s.foreground == "#000000" && s.ratio > 20.0), "{:?}", report.simulations);
+
+        // Red and green lose most of their difference on the OKLab red-green axis
+        let red_green_gap = |vision: Option<ColorVision>| {
+            let see = |rgb: [u8; 3]| rgb_to_oklab(vision.map_or(rgb, |v| simulate(rgb, v)))[1];
+            (see([255, 0, 0]) - see([0, 255, 0])).abs()
+        };
+        for vision in [ColorVision::Protanopia, ColorVision::Deuteranopia] {
+            assert!(red_green_gap(Some(vision)) < red_green_gap(None) / 2.0, "{vision:?}");
+        }
+        let gray = simulate([200, 40, 40], ColorVision::Achromatopsia);
+        assert!(gray[0] == gray[1] && gray[1] == gray[2]);
+    }
+
+    #[test]
+    fn palette_finds_known_colors_and_shares() {
+        let img = bands(100, &[([255, 0, 0, 255], 50), ([0, 0, 255, 255], 30), ([0, 255, 0, 255], 20)]);
+        let palette = extract_palette(&img, 3);
+        assert_eq!(summary(&palette), vec![("#ff0000", 0.5), ("#0000ff", 0.3), ("#00ff00", 0.2)]);
+        assert_eq!(palette[0].color.rgb, [255, 0, 0]);
+
+        // Asking for more colors than the image has returns only the real ones
+        assert_eq!(extract_palette(&img, 8).len(), 3);
+        // Asking for fewer merges the least distinct buckets
+        assert_eq!(extract_palette(&img, 1).len(), 1);
+    }
+
+    #[test]
+    fn palette_ignores_transparent_pixels() {
+        let img = bands(10, &[([0, 0, 0, 0], 90), ([10, 20, 30, 255], 10)]);
+        assert_eq!(summary(&extract_palette(&img, 4)), vec![("#0a141e", 1.0)]);
+        assert!(extract_palette(&bands(4, &[([255, 255, 255, 0], 4)]), 4).is_empty());
+    }
+
+    #[test]
+    fn palette_downscales_large_images_deterministically() {
+        let img = bands(1024, &[([18, 18, 18, 255], 384), ([240, 185, 11, 255], 128)]);
+        let palette = extract_palette(&img, 2);
+        assert_eq!(summary(&palette), vec![("#121212", 0.75), ("#f0b90b", 0.25)]);
+        assert_eq!(summary(&extract_palette(&img, 2)), summary(&palette));
+    }
+}
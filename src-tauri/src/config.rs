@@ -1,5 +1,6 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,7 +11,9 @@ pub enum ConfigError {
     Io(#[from] std::io::Error),
 }
 
-fn get_config_path() -> Result<PathBuf, ConfigError> {
+/// Get the app's config directory, creating it if needed.
+/// Other modules keep their own data files alongside config.json here.
+pub fn get_app_dir() -> Result<PathBuf, ConfigError> {
     let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
     let app_dir = config_dir.join("quanthub");
     
@@ -18,7 +21,27 @@ fn get_config_path() -> Result<PathBuf, ConfigError> {
         fs::create_dir_all(&app_dir)?;
     }
     
-    Ok(app_dir.join("config.json"))
+    Ok(app_dir)
+}
+
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Rename an unreadable data file to `<name>.bad-<secs>`, so a truncated or
+/// hand-edited file isn't overwritten by the next save. Returns the new path.
+pub fn move_aside(path: &Path) -> std::io::Result<PathBuf> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let moved = path.with_file_name(format!("{}.bad-{}", name, unix_millis() / 1000));
+    fs::rename(path, &moved)?;
+    Ok(moved)
+}
+
+fn get_config_path() -> Result<PathBuf, ConfigError> {
+    Ok(get_app_dir()?.join("config.json"))
 }
 
 pub fn load_config() -> Result<String, ConfigError> {
@@ -0,0 +1,356 @@
+use crate::config::{self, ConfigError};
+use crate::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EdgeHoverError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Cursor position in physical desktop pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PointerState {
+    pub x: i32,
+    pub y: i32,
+    /// Left, right or middle button held
+    pub buttons_down: bool,
+}
+
+/// Reads the global pointer. Holds the X11 connection on Linux so polling
+/// doesn't reconnect every tick. `read` returns None where the platform has
+/// no backend (macOS, Wayland); callers fall back to Tauri's cursor position.
+pub struct PointerReader {
+    #[cfg(target_os = "linux")]
+    x11: Option<(x11rb::rust_connection::RustConnection, u32)>,
+}
+
+impl PointerReader {
+    pub fn new() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            use x11rb::connection::Connection;
+
+            let x11 = x11rb::connect(None).ok().map(|(conn, screen)| {
+                let root = conn.setup().roots[screen].root;
+                (conn, root)
+            });
+            Self { x11 }
+        }
+        #[cfg(not(target_os = "linux"))]
+        Self {}
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn read(&mut self) -> Option<PointerState> {
+        #[repr(C)]
+        struct POINT {
+            x: i32,
+            y: i32,
+        }
+
+        extern "system" {
+            fn GetCursorPos(lpPoint: *mut POINT) -> i32;
+            fn GetAsyncKeyState(vKey: i32) -> i16;
+        }
+
+        const VK_LBUTTON: i32 = 0x01;
+        const VK_RBUTTON: i32 = 0x02;
+        const VK_MBUTTON: i32 = 0x04;
+
+        let mut point = POINT { x: 0, y: 0 };
+        if unsafe { GetCursorPos(&mut point) } == 0 {
+            return None;
+        }
+        // High bit set = key currently down
+        let buttons_down = [VK_LBUTTON, VK_RBUTTON, VK_MBUTTON]
+            .iter()
+            .any(|&vk| unsafe { GetAsyncKeyState(vk) } < 0);
+        Some(PointerState { x: point.x, y: point.y, buttons_down })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn read(&mut self) -> Option<PointerState> {
+        use x11rb::protocol::xproto::{ConnectionExt, KeyButMask};
+
+        let (conn, root) = self.x11.as_ref()?;
+        let reply = conn.query_pointer(*root).ok()?.reply().ok()?;
+        let buttons = KeyButMask::BUTTON1 | KeyButMask::BUTTON2 | KeyButMask::BUTTON3;
+        Some(PointerState {
+            x: reply.root_x as i32,
+            y: reply.root_y as i32,
+            buttons_down: u16::from(reply.mask) & u16::from(buttons) != 0,
+        })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    pub fn read(&mut self) -> Option<PointerState> {
+        None
+    }
+}
+
+impl Default for PointerReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_dwell_ms() -> u64 {
+    120
+}
+
+fn default_hide_delay_ms() -> u64 {
+    300
+}
+
+fn default_hysteresis() -> f64 {
+    24.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeHoverConfig {
+    /// How long the cursor must rest on the trigger before the HUD reveals
+    #[serde(default = "default_dwell_ms")]
+    pub dwell_ms: u64,
+    /// How long the cursor must be away from the panel before it hides
+    #[serde(default = "default_hide_delay_ms")]
+    pub hide_delay_ms: u64,
+    /// Logical px around the revealed panel that still count as inside
+    #[serde(default = "default_hysteresis")]
+    pub hysteresis: f64,
+    /// Neither reveal nor hide while a mouse button is held, e.g. when
+    /// dragging a drawing tool across the screen edge
+    #[serde(default = "default_true")]
+    pub ignore_while_dragging: bool,
+}
+
+impl Default for EdgeHoverConfig {
+    fn default() -> Self {
+        Self {
+            dwell_ms: default_dwell_ms(),
+            hide_delay_ms: default_hide_delay_ms(),
+            hysteresis: default_hysteresis(),
+            ignore_while_dragging: true,
+        }
+    }
+}
+
+fn config_path() -> Result<PathBuf, EdgeHoverError> {
+    Ok(config::get_app_dir()?.join("edge_hover.json"))
+}
+
+pub fn load_config() -> Result<EdgeHoverConfig, EdgeHoverError> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(EdgeHoverConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_config(config: &EdgeHoverConfig) -> Result<(), EdgeHoverError> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HoverEvent {
+    Reveal,
+    Hide,
+}
+
+impl HoverEvent {
+    pub fn event_name(self) -> &'static str {
+        match self {
+            HoverEvent::Reveal => "hud-reveal",
+            HoverEvent::Hide => "hud-hide",
+        }
+    }
+}
+
+/// A HUD window as seen by the tracker on one tick
+#[derive(Debug, Clone, Copy)]
+pub struct WindowSnapshot {
+    pub bounds: Rect,
+    pub tucked: bool,
+    pub pinned: bool,
+    pub scale_factor: f64,
+}
+
+/// Per-window reveal/hide decisions. A tucked window is its own trigger
+/// zone; a revealed one hides once the cursor leaves its bounds plus the
+/// hysteresis margin.
+#[derive(Debug, Default)]
+pub struct HoverTracker {
+    /// Event the cursor currently calls for, and since when
+    pending: Option<(HoverEvent, Instant)>,
+    /// Last event fired; not repeated until the condition clears
+    fired: Option<HoverEvent>,
+}
+
+impl HoverTracker {
+    pub fn update(
+        &mut self,
+        config: &EdgeHoverConfig,
+        pointer: PointerState,
+        window: &WindowSnapshot,
+        now: Instant,
+    ) -> Option<HoverEvent> {
+        let want = if window.tucked {
+            window.bounds.contains(pointer.x, pointer.y).then_some(HoverEvent::Reveal)
+        } else {
+            let margin = (config.hysteresis * window.scale_factor).round() as i32;
+            (!window.pinned && !window.bounds.inflate(margin).contains(pointer.x, pointer.y))
+                .then_some(HoverEvent::Hide)
+        };
+        if self.fired != want {
+            self.fired = None;
+        }
+        if config.ignore_while_dragging && pointer.buttons_down {
+            self.pending = None;
+            return None;
+        }
+        let Some(event) = want else {
+            self.pending = None;
+            return None;
+        };
+        if self.fired == Some(event) {
+            return None;
+        }
+
+        let since = match self.pending {
+            Some((pending, since)) if pending == event => since,
+            _ => {
+                self.pending = Some((event, now));
+                now
+            }
+        };
+        let delay = match event {
+            HoverEvent::Reveal => config.dwell_ms,
+            HoverEvent::Hide => config.hide_delay_ms,
+        };
+        if now.duration_since(since) < Duration::from_millis(delay) {
+            return None;
+        }
+        self.pending = None;
+        self.fired = Some(event);
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EdgeHoverConfig {
+        EdgeHoverConfig { dwell_ms: 100, hide_delay_ms: 300, hysteresis: 20.0, ignore_while_dragging: true }
+    }
+
+    fn window(tucked: bool, scale_factor: f64) -> WindowSnapshot {
+        WindowSnapshot { bounds: Rect { x: 0, y: 100, width: 200, height: 400 }, tucked, pinned: false, scale_factor }
+    }
+
+    fn at(x: i32, y: i32) -> PointerState {
+        PointerState { x, y, buttons_down: false }
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn reveal_waits_for_the_dwell_and_fires_once() {
+        let (config, tucked, t0) = (config(), window(true, 1.0), Instant::now());
+        let mut tracker = HoverTracker::default();
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, t0), None);
+        assert_eq!(tracker.update(&config, at(6, 210), &tucked, ms(t0, 99)), None);
+        assert_eq!(tracker.update(&config, at(6, 220), &tucked, ms(t0, 100)), Some(HoverEvent::Reveal));
+        assert_eq!(tracker.update(&config, at(6, 220), &tucked, ms(t0, 500)), None);
+    }
+
+    #[test]
+    fn leaving_the_trigger_resets_the_dwell() {
+        let (config, tucked, t0) = (config(), window(true, 1.0), Instant::now());
+        let mut tracker = HoverTracker::default();
+        tracker.update(&config, at(5, 200), &tucked, t0);
+        tracker.update(&config, at(500, 200), &tucked, ms(t0, 60));
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, ms(t0, 120)), None);
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, ms(t0, 219)), None);
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, ms(t0, 220)), Some(HoverEvent::Reveal));
+    }
+
+    #[test]
+    fn hide_waits_for_the_hide_delay() {
+        let (config, shown, t0) = (config(), window(false, 1.0), Instant::now());
+        let mut tracker = HoverTracker::default();
+        assert_eq!(tracker.update(&config, at(600, 300), &shown, t0), None);
+        assert_eq!(tracker.update(&config, at(600, 300), &shown, ms(t0, 299)), None);
+        assert_eq!(tracker.update(&config, at(600, 300), &shown, ms(t0, 300)), Some(HoverEvent::Hide));
+        // Coming back and leaving again fires again
+        assert_eq!(tracker.update(&config, at(100, 300), &shown, ms(t0, 400)), None);
+        assert_eq!(tracker.update(&config, at(600, 300), &shown, ms(t0, 500)), None);
+        assert_eq!(tracker.update(&config, at(600, 300), &shown, ms(t0, 800)), Some(HoverEvent::Hide));
+    }
+
+    #[test]
+    fn hysteresis_margin_scales_with_the_monitor() {
+        let (config, t0) = (config(), Instant::now());
+        for (scale, inside, outside) in [(1.0, 219, 220), (1.5, 229, 230), (2.0, 239, 240)] {
+            let shown = window(false, scale);
+            let mut tracker = HoverTracker::default();
+            tracker.update(&config, at(inside, 300), &shown, t0);
+            assert_eq!(tracker.update(&config, at(inside, 300), &shown, ms(t0, 1000)), None, "scale {scale}");
+            tracker.update(&config, at(outside, 300), &shown, ms(t0, 1000));
+            assert_eq!(
+                tracker.update(&config, at(outside, 300), &shown, ms(t0, 1300)),
+                Some(HoverEvent::Hide),
+                "scale {scale}"
+            );
+        }
+    }
+
+    #[test]
+    fn pinned_windows_never_hide() {
+        let (config, t0) = (config(), Instant::now());
+        let pinned = WindowSnapshot { pinned: true, ..window(false, 1.0) };
+        let mut tracker = HoverTracker::default();
+        tracker.update(&config, at(900, 300), &pinned, t0);
+        assert_eq!(tracker.update(&config, at(900, 300), &pinned, ms(t0, 5000)), None);
+    }
+
+    #[test]
+    fn dragging_suspends_reveal_and_hide() {
+        let (config, t0) = (config(), Instant::now());
+        let drag = |x, y| PointerState { x, y, buttons_down: true };
+
+        let tucked = window(true, 1.0);
+        let mut tracker = HoverTracker::default();
+        tracker.update(&config, at(5, 200), &tucked, t0);
+        assert_eq!(tracker.update(&config, drag(5, 200), &tucked, ms(t0, 200)), None);
+        // The dwell restarts once the button is released
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, ms(t0, 250)), None);
+        assert_eq!(tracker.update(&config, at(5, 200), &tucked, ms(t0, 350)), Some(HoverEvent::Reveal));
+
+        let shown = window(false, 1.0);
+        let mut tracker = HoverTracker::default();
+        assert_eq!(tracker.update(&config, drag(900, 300), &shown, t0), None);
+        assert_eq!(tracker.update(&config, drag(900, 300), &shown, ms(t0, 1000)), None);
+
+        let mut tracker = HoverTracker::default();
+        let config = EdgeHoverConfig { ignore_while_dragging: false, ..config };
+        tracker.update(&config, drag(900, 300), &shown, t0);
+        assert_eq!(tracker.update(&config, drag(900, 300), &shown, ms(t0, 300)), Some(HoverEvent::Hide));
+    }
+}
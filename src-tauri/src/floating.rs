@@ -0,0 +1,205 @@
+use crate::config::{self, ConfigError};
+use crate::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Window label prefix; the module id follows, e.g. `module-worldclock`
+const LABEL_PREFIX: &str = "module-";
+
+/// Initial size of a floating module window, in logical pixels
+pub const DEFAULT_WIDTH: f64 = 320.0;
+pub const DEFAULT_HEIGHT: f64 = 420.0;
+/// Smallest size a floating module window can be resized to
+pub const MIN_WIDTH: f64 = 200.0;
+pub const MIN_HEIGHT: f64 = 120.0;
+
+/// Lower bound so a window never fades out completely
+const MIN_OPACITY: f64 = 0.2;
+
+#[derive(Error, Debug)]
+pub enum FloatingError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid module id '{0}'")]
+    InvalidModule(String),
+}
+
+/// Window state of a module popped out of the HUD
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatingModule {
+    /// Last outer position and inner size; None until first moved/resized
+    #[serde(default)]
+    pub bounds: Option<Rect>,
+    /// Mouse input passes through to the windows below
+    #[serde(default)]
+    pub click_through: bool,
+    /// 0.2–1.0, applied by the page
+    #[serde(default = "default_opacity")]
+    pub opacity: f64,
+    /// Reopened at startup
+    #[serde(default)]
+    pub open: bool,
+}
+
+fn default_opacity() -> f64 {
+    1.0
+}
+
+impl Default for FloatingModule {
+    fn default() -> Self {
+        Self { bounds: None, click_through: false, opacity: default_opacity(), open: false }
+    }
+}
+
+/// Floating module windows keyed by module id, saved in floating_modules.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FloatingModules {
+    #[serde(default)]
+    pub modules: HashMap<String, FloatingModule>,
+}
+
+impl FloatingModules {
+    fn path() -> Result<PathBuf, FloatingError> {
+        Ok(config::get_app_dir()?.join("floating_modules.json"))
+    }
+
+    pub fn load() -> Result<Self, FloatingError> {
+        Self::load_from(&Self::path()?)
+    }
+
+    /// An unreadable file is moved aside so the next bounds save doesn't
+    /// reset every module
+    fn load_from(path: &Path) -> Result<Self, FloatingError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        match serde_json::from_str(&fs::read_to_string(path)?) {
+            Ok(modules) => Ok(modules),
+            Err(e) => {
+                let moved = config::move_aside(path)?;
+                eprintln!("Unreadable floating modules moved to {}: {}", moved.display(), e);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), FloatingError> {
+        fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, module_id: &str) -> FloatingModule {
+        self.modules.get(module_id).cloned().unwrap_or_default()
+    }
+
+    /// Entry for a module, created with defaults on first use
+    pub fn entry(&mut self, module_id: &str) -> &mut FloatingModule {
+        self.modules.entry(module_id.to_string()).or_default()
+    }
+
+    /// Ids of the modules that were open when last saved
+    pub fn open_ids(&self) -> Vec<String> {
+        self.modules.iter().filter(|(_, m)| m.open).map(|(id, _)| id.clone()).collect()
+    }
+}
+
+pub fn clamp_opacity(opacity: f64) -> f64 {
+    opacity.clamp(MIN_OPACITY, 1.0)
+}
+
+/// Module ids end up in window labels and URLs, so keep them simple
+pub fn validate_id(module_id: &str) -> Result<(), FloatingError> {
+    let valid = !module_id.is_empty() && module_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(FloatingError::InvalidModule(module_id.to_string()))
+    }
+}
+
+pub fn label(module_id: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, module_id)
+}
+
+/// Saved bounds if they're still at least partly on one of the monitors;
+/// otherwise the window is centered again
+pub fn visible_bounds(bounds: Option<Rect>, monitors: &[Rect]) -> Option<Rect> {
+    let bounds = bounds.filter(|b| !b.is_empty())?;
+    monitors.iter().any(|m| m.intersect(&bounds).is_some()).then_some(bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: Rect = Rect { x: 0, y: 0, width: 1920, height: 1080 };
+    const LEFT: Rect = Rect { x: -2560, y: 0, width: 2560, height: 1440 };
+
+    #[test]
+    fn validate_id_accepts_only_simple_ids() {
+        for id in ["worldclock", "chart-analyzer", "module2"] {
+            assert!(validate_id(id).is_ok(), "{id}");
+        }
+        for id in ["", "../settings", "a b", "a/b", "x?y=1", "módulo", "calc_v2"] {
+            assert!(matches!(validate_id(id), Err(FloatingError::InvalidModule(_))), "{id}");
+        }
+        assert_eq!(label("worldclock"), "module-worldclock");
+    }
+
+    #[test]
+    fn visible_bounds_recenters_windows_left_off_screen() {
+        let on_left = Rect { x: -800, y: 200, width: 320, height: 420 };
+        let straddling = Rect { x: 1800, y: 900, width: 320, height: 420 };
+        assert_eq!(visible_bounds(Some(on_left), &[PRIMARY, LEFT]), Some(on_left));
+        assert_eq!(visible_bounds(Some(straddling), &[PRIMARY]), Some(straddling));
+
+        // The left monitor was unplugged
+        assert_eq!(visible_bounds(Some(on_left), &[PRIMARY]), None);
+        assert_eq!(visible_bounds(Some(Rect { x: 100, y: 100, width: 0, height: 420 }), &[PRIMARY]), None);
+        assert_eq!(visible_bounds(None, &[PRIMARY]), None);
+        assert_eq!(visible_bounds(Some(on_left), &[]), None);
+    }
+
+    #[test]
+    fn clamp_opacity_keeps_windows_visible() {
+        assert_eq!(clamp_opacity(0.0), MIN_OPACITY);
+        assert_eq!(clamp_opacity(-1.0), MIN_OPACITY);
+        assert_eq!(clamp_opacity(0.65), 0.65);
+        assert_eq!(clamp_opacity(3.0), 1.0);
+    }
+
+    #[test]
+    fn open_ids_lists_only_open_modules() {
+        let mut modules = FloatingModules::default();
+        modules.entry("worldclock").open = true;
+        modules.entry("calculator").opacity = 0.5;
+        assert_eq!(modules.open_ids(), vec!["worldclock".to_string()]);
+        assert_eq!(modules.get("calculator").opacity, 0.5);
+        assert_eq!(modules.get("missing"), FloatingModule::default());
+    }
+
+    #[test]
+    fn load_from_moves_a_malformed_file_aside() {
+        let path = std::env::temp_dir().join(format!("quanthud_floating_{}.json", std::process::id()));
+        fs::write(&path, r#"{"modules": {"worldclock": {"open": tru"#).unwrap();
+        assert!(FloatingModules::load_from(&path).unwrap().modules.is_empty());
+        assert!(!path.exists());
+
+        let prefix = format!("{}.bad-", path.file_name().unwrap().to_string_lossy());
+        let moved: Vec<PathBuf> = fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix)))
+            .collect();
+        assert_eq!(moved.len(), 1);
+        let _ = fs::remove_file(&moved[0]);
+    }
+}
@@ -0,0 +1,488 @@
+use crate::config::{self, ConfigError};
+use crate::layout::Rect;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FullscreenError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullscreenConfig {
+    /// Hide the edge triggers and popups while a fullscreen app is in front
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Apps (executable or window class, e.g. `obs64.exe` or `zoom`) that
+    /// suppress the HUD whenever they're in front, fullscreen or not
+    #[serde(default)]
+    pub suppressed_apps: Vec<String>,
+}
+
+impl Default for FullscreenConfig {
+    fn default() -> Self {
+        Self { enabled: true, suppressed_apps: Vec::new() }
+    }
+}
+
+fn config_path() -> Result<PathBuf, FullscreenError> {
+    Ok(config::get_app_dir()?.join("fullscreen.json"))
+}
+
+pub fn load_config() -> Result<FullscreenConfig, FullscreenError> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(FullscreenConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_config(config: &FullscreenConfig) -> Result<(), FullscreenError> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+/// The focused top-level window as the OS reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForegroundWindow {
+    /// Outer bounds in physical desktop pixels
+    pub bounds: Rect,
+    /// Executable path on Windows, WM_CLASS on X11
+    pub app_name: Option<String>,
+    pub pid: Option<u32>,
+    /// The OS itself flags the window or session as fullscreen
+    /// (`_NET_WM_STATE_FULLSCREEN`, Windows presentation/D3D mode)
+    pub fullscreen_hint: bool,
+    /// Maximized windows fill the monitor too (on Windows their frame even
+    /// overhangs it) but aren't fullscreen
+    pub maximized: bool,
+    /// The desktop or a panel rather than an app: WorkerW/Progman behind the
+    /// wallpaper on Windows, `_NET_WM_WINDOW_TYPE_DESKTOP`/`DOCK` on X11.
+    /// The desktop covers its monitor but never means an app is fullscreen.
+    pub desktop: bool,
+}
+
+/// Why the HUD is currently held back
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Suppression {
+    /// `monitor` is the monitor the fullscreen window is on
+    Fullscreen { app_name: Option<String>, monitor: Option<Rect> },
+    App { app_name: String },
+}
+
+impl Suppression {
+    /// Whether HUD windows on `monitor` are held back. A fullscreen window
+    /// only affects its own monitor; a listed app affects every monitor.
+    pub fn affects(&self, monitor: &Rect) -> bool {
+        match self {
+            Suppression::Fullscreen { monitor: Some(fullscreen), .. } => fullscreen.intersect(monitor).is_some(),
+            _ => true,
+        }
+    }
+}
+
+/// Source of the foreground window; the OS in production, a fake in tests
+pub trait WindowStateProvider: Send {
+    fn foreground(&mut self) -> Option<ForegroundWindow>;
+}
+
+/// Whether a window covers a whole monitor
+fn covers_monitor(bounds: &Rect, monitor: &Rect) -> bool {
+    bounds.x <= monitor.x && bounds.y <= monitor.y && bounds.right() >= monitor.right() && bounds.bottom() >= monitor.bottom()
+}
+
+/// The monitor holding most of `bounds`
+fn monitor_of(bounds: &Rect, monitors: &[Rect]) -> Option<Rect> {
+    monitors
+        .iter()
+        .filter_map(|m| bounds.intersect(m).map(|overlap| (*m, overlap.width as i64 * overlap.height as i64)))
+        .max_by_key(|(_, area)| *area)
+        .map(|(m, _)| m)
+}
+
+/// Case-insensitive match on the full name, the file name, or the file name
+/// without extension, so `obs64`, `obs64.exe` and the full path all work
+pub fn matches_app(app_name: &str, entry: &str) -> bool {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return false;
+    }
+    let file_name = app_name.rsplit(['/', '\\']).next().unwrap_or(app_name);
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    [app_name, file_name, stem].iter().any(|name| name.eq_ignore_ascii_case(entry))
+}
+
+/// Suppression for a foreground window, if any
+pub fn evaluate(window: &ForegroundWindow, monitors: &[Rect], config: &FullscreenConfig) -> Option<Suppression> {
+    if !config.enabled || window.desktop {
+        return None;
+    }
+    if let Some(app_name) = &window.app_name {
+        if config.suppressed_apps.iter().any(|entry| matches_app(app_name, entry)) {
+            return Some(Suppression::App { app_name: app_name.clone() });
+        }
+    }
+    let fullscreen =
+        window.fullscreen_hint || (!window.maximized && monitors.iter().any(|m| covers_monitor(&window.bounds, m)));
+    fullscreen.then(|| Suppression::Fullscreen {
+        app_name: window.app_name.clone(),
+        monitor: monitor_of(&window.bounds, monitors),
+    })
+}
+
+/// Polls a provider and keeps the last state while one of our own windows
+/// is in front, so clicking the HUD doesn't flip suppression off
+pub struct FullscreenDetector<P: WindowStateProvider> {
+    provider: P,
+    own_pid: u32,
+    current: Option<Suppression>,
+}
+
+impl<P: WindowStateProvider> FullscreenDetector<P> {
+    pub fn new(provider: P) -> Self {
+        Self { provider, own_pid: std::process::id(), current: None }
+    }
+
+    /// Current suppression after looking at the foreground window
+    pub fn poll(&mut self, monitors: &[Rect], config: &FullscreenConfig) -> Option<Suppression> {
+        if !config.enabled {
+            self.current = None;
+            return None;
+        }
+        match self.provider.foreground() {
+            Some(window) if window.pid == Some(self.own_pid) => {}
+            Some(window) => self.current = evaluate(&window, monitors, config),
+            None => self.current = None,
+        }
+        self.current.clone()
+    }
+}
+
+/// Foreground window of the running desktop. Holds the X11 connection on
+/// Linux; reports nothing on platforms without a backend (macOS, Wayland).
+pub struct SystemProvider {
+    #[cfg(target_os = "linux")]
+    x11: Option<(x11rb::rust_connection::RustConnection, u32)>,
+}
+
+impl SystemProvider {
+    pub fn new() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            use x11rb::connection::Connection;
+
+            let x11 = x11rb::connect(None).ok().map(|(conn, screen)| {
+                let root = conn.setup().roots[screen].root;
+                (conn, root)
+            });
+            Self { x11 }
+        }
+        #[cfg(not(target_os = "linux"))]
+        Self {}
+    }
+}
+
+impl Default for SystemProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowStateProvider for SystemProvider {
+    #[cfg(target_os = "windows")]
+    fn foreground(&mut self) -> Option<ForegroundWindow> {
+        type HWND = *mut std::ffi::c_void;
+        type HANDLE = *mut std::ffi::c_void;
+
+        #[repr(C)]
+        struct RECT {
+            left: i32,
+            top: i32,
+            right: i32,
+            bottom: i32,
+        }
+
+        extern "system" {
+            fn GetForegroundWindow() -> HWND;
+            fn GetDesktopWindow() -> HWND;
+            fn GetShellWindow() -> HWND;
+            fn GetClassNameW(hwnd: HWND, name: *mut u16, size: i32) -> i32;
+            fn GetWindowRect(hwnd: HWND, rect: *mut RECT) -> i32;
+            fn IsZoomed(hwnd: HWND) -> i32;
+            fn GetWindowThreadProcessId(hwnd: HWND, pid: *mut u32) -> u32;
+            fn OpenProcess(access: u32, inherit: i32, pid: u32) -> HANDLE;
+            fn QueryFullProcessImageNameW(process: HANDLE, flags: u32, name: *mut u16, size: *mut u32) -> i32;
+            fn CloseHandle(handle: HANDLE) -> i32;
+        }
+
+        #[link(name = "shell32")]
+        extern "system" {
+            fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+        }
+
+        const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+        // QUNS_BUSY, QUNS_RUNNING_D3D_FULL_SCREEN, QUNS_PRESENTATION_MODE
+        const FULLSCREEN_STATES: [i32; 3] = [2, 3, 4];
+        // The wallpaper (clicked, or after Win+D) and the taskbars
+        const DESKTOP_CLASSES: [&str; 4] = ["WorkerW", "Progman", "Shell_TrayWnd", "Shell_SecondaryTrayWnd"];
+
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_null() {
+                return None;
+            }
+            let mut class = [0u16; 256];
+            let class_len = GetClassNameW(hwnd, class.as_mut_ptr(), class.len() as i32).max(0) as usize;
+            let class = String::from_utf16_lossy(&class[..class_len]);
+            let desktop = hwnd == GetDesktopWindow() || hwnd == GetShellWindow() || DESKTOP_CLASSES.contains(&class.as_str());
+            let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+            if GetWindowRect(hwnd, &mut rect) == 0 {
+                return None;
+            }
+
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            let mut app_name = None;
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if !process.is_null() {
+                let mut buffer = [0u16; 1024];
+                let mut size = buffer.len() as u32;
+                if QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size) != 0 {
+                    app_name = Some(String::from_utf16_lossy(&buffer[..size as usize]));
+                }
+                CloseHandle(process);
+            }
+
+            let mut state = 0i32;
+            let fullscreen_hint = SHQueryUserNotificationState(&mut state) == 0 && FULLSCREEN_STATES.contains(&state);
+
+            Some(ForegroundWindow {
+                bounds: Rect { x: rect.left, y: rect.top, width: rect.right - rect.left, height: rect.bottom - rect.top },
+                app_name,
+                pid: (pid != 0).then_some(pid),
+                fullscreen_hint,
+                maximized: IsZoomed(hwnd) != 0,
+                desktop,
+            })
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn foreground(&mut self) -> Option<ForegroundWindow> {
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        let (conn, root) = self.x11.as_ref()?;
+        let atom = |name: &str| -> Option<u32> {
+            let atom = conn.intern_atom(true, name.as_bytes()).ok()?.reply().ok()?.atom;
+            (atom != u32::from(AtomEnum::NONE)).then_some(atom)
+        };
+        let values = |window: u32, property: u32, kind: AtomEnum| -> Vec<u32> {
+            conn.get_property(false, window, property, kind, 0, u32::MAX)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .and_then(|reply| reply.value32().map(|values| values.collect()))
+                .unwrap_or_default()
+        };
+
+        let window = *values(*root, atom("_NET_ACTIVE_WINDOW")?, AtomEnum::WINDOW).first()?;
+        if window == 0 {
+            return None;
+        }
+        let geometry = conn.get_geometry(window).ok()?.reply().ok()?;
+        let origin = conn.translate_coordinates(window, *root, 0, 0).ok()?.reply().ok()?;
+
+        let states = atom("_NET_WM_STATE").map(|state| values(window, state, AtomEnum::ATOM)).unwrap_or_default();
+        let has_state = |name: &str| atom(name).is_some_and(|a| states.contains(&a));
+        let fullscreen_hint = has_state("_NET_WM_STATE_FULLSCREEN");
+        let maximized = has_state("_NET_WM_STATE_MAXIMIZED_VERT") && has_state("_NET_WM_STATE_MAXIMIZED_HORZ");
+        let types = atom("_NET_WM_WINDOW_TYPE").map(|kind| values(window, kind, AtomEnum::ATOM)).unwrap_or_default();
+        let desktop = ["_NET_WM_WINDOW_TYPE_DESKTOP", "_NET_WM_WINDOW_TYPE_DOCK"]
+            .iter()
+            .any(|name| atom(name).is_some_and(|a| types.contains(&a)));
+        let pid = atom("_NET_WM_PID").and_then(|p| values(window, p, AtomEnum::CARDINAL).first().copied());
+        // WM_CLASS is "instance\0class\0"; the class is the stable app name
+        let app_name = conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 1024)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| {
+                let text = String::from_utf8_lossy(&reply.value).to_string();
+                let mut parts = text.split('\0').filter(|part| !part.is_empty());
+                let instance = parts.next().map(str::to_string);
+                parts.next().map(str::to_string).or(instance)
+            });
+
+        Some(ForegroundWindow {
+            bounds: Rect {
+                x: origin.dst_x as i32,
+                y: origin.dst_y as i32,
+                width: geometry.width as i32,
+                height: geometry.height as i32,
+            },
+            app_name,
+            pid,
+            fullscreen_hint,
+            maximized,
+            desktop,
+        })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    fn foreground(&mut self) -> Option<ForegroundWindow> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Replays a scripted sequence of foreground windows
+    struct FakeProvider(VecDeque<Option<ForegroundWindow>>);
+
+    impl WindowStateProvider for FakeProvider {
+        fn foreground(&mut self) -> Option<ForegroundWindow> {
+            self.0.pop_front().flatten()
+        }
+    }
+
+    const PRIMARY: Rect = Rect { x: 0, y: 0, width: 1920, height: 1080 };
+    const SECONDARY: Rect = Rect { x: 1920, y: -200, width: 2560, height: 1440 };
+
+    fn window(bounds: Rect, app_name: &str) -> ForegroundWindow {
+        ForegroundWindow { bounds, app_name: Some(app_name.to_string()), pid: Some(4242), fullscreen_hint: false, maximized: false, desktop: false }
+    }
+
+    fn config(suppressed_apps: &[&str]) -> FullscreenConfig {
+        FullscreenConfig { enabled: true, suppressed_apps: suppressed_apps.iter().map(|s| s.to_string()).collect() }
+    }
+
+    fn fullscreen_on(monitor: Rect, app_name: &str) -> Option<Suppression> {
+        Some(Suppression::Fullscreen { app_name: Some(app_name.to_string()), monitor: Some(monitor) })
+    }
+
+    #[test]
+    fn matches_app_by_path_file_name_or_stem() {
+        let path = r"C:\Program Files\obs-studio\bin\64bit\obs64.exe";
+        for entry in [path, "obs64.exe", "OBS64.EXE", "obs64", " obs64 "] {
+            assert!(matches_app(path, entry), "{entry}");
+        }
+        for entry in ["obs", "obs64.ex", "", "  ", "64bit"] {
+            assert!(!matches_app(path, entry), "{entry}");
+        }
+        assert!(matches_app("/usr/bin/zoom", "zoom"));
+        assert!(matches_app("zoom", "ZOOM"));
+    }
+
+    #[test]
+    fn evaluate_uses_the_hint_or_the_geometry() {
+        let monitors = [PRIMARY, SECONDARY];
+        let config = config(&[]);
+
+        let borderless = window(SECONDARY, "game.exe");
+        assert_eq!(evaluate(&borderless, &monitors, &config), fullscreen_on(SECONDARY, "game.exe"));
+
+        let hinted = ForegroundWindow { fullscreen_hint: true, ..window(Rect { x: 100, y: 100, width: 800, height: 600 }, "slides") };
+        assert_eq!(evaluate(&hinted, &monitors, &config), fullscreen_on(PRIMARY, "slides"));
+
+        let windowed = window(Rect { x: 100, y: 100, width: 800, height: 600 }, "chrome.exe");
+        assert_eq!(evaluate(&windowed, &monitors, &config), None);
+    }
+
+    #[test]
+    fn evaluate_ignores_maximized_windows() {
+        // Windows reports a maximized frame 8px past every monitor edge
+        let overhang = Rect { x: -8, y: -8, width: 1936, height: 1096 };
+        let maximized = ForegroundWindow { maximized: true, ..window(overhang, "chrome.exe") };
+        assert_eq!(evaluate(&maximized, &[PRIMARY], &config(&[])), None);
+
+        let presenting = ForegroundWindow { fullscreen_hint: true, ..maximized };
+        assert!(evaluate(&presenting, &[PRIMARY], &config(&[])).is_some());
+    }
+
+    #[test]
+    fn evaluate_suppresses_listed_apps_anywhere() {
+        let small = window(Rect { x: 10, y: 10, width: 300, height: 200 }, "/opt/zoom/zoom");
+        assert_eq!(
+            evaluate(&small, &[PRIMARY], &config(&["zoom"])),
+            Some(Suppression::App { app_name: "/opt/zoom/zoom".into() })
+        );
+        let disabled = FullscreenConfig { enabled: false, ..config(&["zoom"]) };
+        assert_eq!(evaluate(&small, &[PRIMARY], &disabled), None);
+    }
+
+    #[test]
+    fn suppression_affects_only_the_fullscreen_monitor() {
+        let on_secondary = fullscreen_on(SECONDARY, "game.exe").unwrap();
+        assert!(on_secondary.affects(&SECONDARY));
+        assert!(!on_secondary.affects(&PRIMARY));
+        assert!(Suppression::Fullscreen { app_name: None, monitor: None }.affects(&PRIMARY));
+        assert!(Suppression::App { app_name: "zoom".into() }.affects(&PRIMARY));
+    }
+
+    #[test]
+    fn poll_keeps_state_while_our_own_window_is_in_front() {
+        let own = ForegroundWindow { pid: Some(std::process::id()), ..window(Rect { x: 0, y: 0, width: 20, height: 48 }, "quanthud") };
+        let script = [
+            Some(window(PRIMARY, "game.exe")),
+            Some(own.clone()),
+            Some(own),
+            Some(window(Rect { x: 0, y: 0, width: 800, height: 600 }, "chrome")),
+        ];
+        let mut detector = FullscreenDetector::new(FakeProvider(script.into_iter().collect()));
+        let (monitors, config) = ([PRIMARY], config(&[]));
+
+        assert_eq!(detector.poll(&monitors, &config), fullscreen_on(PRIMARY, "game.exe"));
+        assert_eq!(detector.poll(&monitors, &config), fullscreen_on(PRIMARY, "game.exe"));
+        assert_eq!(detector.poll(&monitors, &config), fullscreen_on(PRIMARY, "game.exe"));
+        assert_eq!(detector.poll(&monitors, &config), None);
+
+        // No foreground window at all clears it too
+        let mut detector = FullscreenDetector::new(FakeProvider([Some(window(PRIMARY, "game.exe")), None].into_iter().collect()));
+        assert!(detector.poll(&monitors, &config).is_some());
+        assert_eq!(detector.poll(&monitors, &config), None);
+    }
+
+    #[test]
+    fn poll_ignores_the_desktop_and_panels() {
+        // Clicking the wallpaper focuses a window as big as the monitor
+        let wallpaper = ForegroundWindow { desktop: true, ..window(PRIMARY, r"C:\Windows\explorer.exe") };
+        let panel = ForegroundWindow { desktop: true, ..window(Rect { x: 0, y: 1032, width: 1920, height: 48 }, "Xfce4-panel") };
+        let script = [Some(window(PRIMARY, "game.exe")), Some(wallpaper.clone()), Some(panel), Some(wallpaper)];
+        let mut detector = FullscreenDetector::new(FakeProvider(script.into_iter().collect()));
+        let (monitors, config) = ([PRIMARY], config(&["explorer"]));
+
+        assert_eq!(detector.poll(&monitors, &config), fullscreen_on(PRIMARY, "game.exe"));
+        assert_eq!(detector.poll(&monitors, &config), None);
+        assert_eq!(detector.poll(&monitors, &config), None);
+        // Not even a listed app name holds the HUD back from the desktop
+        assert_eq!(detector.poll(&monitors, &config), None);
+    }
+
+    #[test]
+    fn poll_clears_state_when_disabled() {
+        let script = [Some(window(PRIMARY, "game.exe")), Some(window(PRIMARY, "game.exe")), Some(window(PRIMARY, "game.exe"))];
+        let mut detector = FullscreenDetector::new(FakeProvider(script.into_iter().collect()));
+        let enabled = config(&[]);
+        let disabled = FullscreenConfig { enabled: false, ..config(&[]) };
+
+        assert!(detector.poll(&[PRIMARY], &enabled).is_some());
+        assert_eq!(detector.poll(&[PRIMARY], &disabled), None);
+        // Nothing stale comes back while our own window is in front after re-enabling
+        detector.provider = FakeProvider([Some(ForegroundWindow { pid: Some(std::process::id()), ..window(PRIMARY, "quanthud") })].into_iter().collect());
+        assert_eq!(detector.poll(&[PRIMARY], &enabled), None);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::config::{self, ConfigError};
+use crate::layout::HudPlacement;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The window from tauri.conf.json; configured through the app settings
+pub const MAIN_LABEL: &str = "main";
+/// Right-hand window of dual mode, created by the main window on demand
+pub const DUAL_LABEL: &str = "dual-right";
+
+#[derive(Error, Debug)]
+pub enum HudWindowError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("HUD window '{0}' not found")]
+    NotFound(String),
+}
+
+/// An additional HUD window, restored at startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HudWindowConfig {
+    pub label: String,
+    #[serde(default)]
+    pub placement: HudPlacement,
+    /// Fingerprint from `monitors::fingerprint`; None = primary monitor
+    #[serde(default)]
+    pub monitor_id: Option<String>,
+    /// Module ids shown in this window; empty shows all
+    #[serde(default)]
+    pub modules: Vec<String>,
+}
+
+/// Additional HUD windows, saved in hud_windows.json. The main window and
+/// the dual-mode window aren't listed here.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HudWindows {
+    #[serde(default)]
+    pub windows: Vec<HudWindowConfig>,
+}
+
+impl HudWindows {
+    fn path() -> Result<PathBuf, HudWindowError> {
+        Ok(config::get_app_dir()?.join("hud_windows.json"))
+    }
+
+    pub fn load() -> Result<Self, HudWindowError> {
+        Self::load_from(&Self::path()?)
+    }
+
+    /// An unreadable file is moved aside so the next save doesn't forget
+    /// every extra window
+    fn load_from(path: &Path) -> Result<Self, HudWindowError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        match serde_json::from_str(&fs::read_to_string(path)?) {
+            Ok(windows) => Ok(windows),
+            Err(e) => {
+                let moved = config::move_aside(path)?;
+                eprintln!("Unreadable HUD windows moved to {}: {}", moved.display(), e);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), HudWindowError> {
+        fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, label: &str) -> Option<&HudWindowConfig> {
+        self.windows.iter().find(|w| w.label == label)
+    }
+
+    /// Whether a window label belongs to a HUD (as opposed to overlays/previews)
+    pub fn is_hud(&self, label: &str) -> bool {
+        label == MAIN_LABEL || label == DUAL_LABEL || self.get(label).is_some()
+    }
+
+    /// First free `hud-N` label
+    fn next_label(&self) -> String {
+        (1..)
+            .map(|n| format!("hud-{}", n))
+            .find(|label| self.get(label).is_none())
+            .unwrap()
+    }
+
+    pub fn add(&mut self, placement: HudPlacement, monitor_id: Option<String>, modules: Vec<String>) -> HudWindowConfig {
+        let config = HudWindowConfig { label: self.next_label(), placement, monitor_id, modules };
+        self.windows.push(config.clone());
+        config
+    }
+
+    /// Replace a window's settings; the label identifies it
+    pub fn update(&mut self, config: HudWindowConfig) -> Result<(), HudWindowError> {
+        let existing = self.windows
+            .iter_mut()
+            .find(|w| w.label == config.label)
+            .ok_or_else(|| HudWindowError::NotFound(config.label.clone()))?;
+        *existing = config;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, label: &str) -> Option<HudWindowConfig> {
+        let index = self.windows.iter().position(|w| w.label == label)?;
+        Some(self.windows.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(windows: &mut HudWindows) -> String {
+        windows.add(HudPlacement::default(), None, vec![]).label
+    }
+
+    #[test]
+    fn add_reuses_the_first_free_label() {
+        let mut windows = HudWindows::default();
+        assert_eq!([add(&mut windows), add(&mut windows), add(&mut windows)], ["hud-1", "hud-2", "hud-3"]);
+
+        assert_eq!(windows.remove("hud-2").map(|w| w.label), Some("hud-2".to_string()));
+        assert_eq!(windows.remove("hud-2"), None);
+        assert_eq!(add(&mut windows), "hud-2");
+        assert_eq!(add(&mut windows), "hud-4");
+    }
+
+    #[test]
+    fn update_replaces_settings_by_label() {
+        let mut windows = HudWindows::default();
+        let mut config = windows.add(HudPlacement::default(), None, vec![]);
+        config.modules = vec!["calculator".to_string()];
+        config.monitor_id = Some("DP-1|2560x1440|1920,0".to_string());
+        windows.update(config.clone()).unwrap();
+        assert_eq!(windows.get("hud-1"), Some(&config));
+
+        let missing = HudWindowConfig { label: "hud-9".to_string(), ..config };
+        assert!(matches!(windows.update(missing), Err(HudWindowError::NotFound(label)) if label == "hud-9"));
+        assert_eq!(windows.windows.len(), 1);
+    }
+
+    #[test]
+    fn is_hud_covers_main_dual_and_listed_windows() {
+        let mut windows = HudWindows::default();
+        add(&mut windows);
+        for label in [MAIN_LABEL, DUAL_LABEL, "hud-1"] {
+            assert!(windows.is_hud(label), "{label}");
+        }
+        for label in ["hud-2", "color-picker-overlay", "floating-calculator"] {
+            assert!(!windows.is_hud(label), "{label}");
+        }
+    }
+
+    #[test]
+    fn load_from_moves_a_malformed_file_aside() {
+        let path = std::env::temp_dir().join(format!("quanthud_hud_windows_{}.json", std::process::id()));
+        fs::write(&path, r#"{"windows": [{"label": "hud-1""#).unwrap();
+        assert!(HudWindows::load_from(&path).unwrap().windows.is_empty());
+        assert!(!path.exists());
+
+        let prefix = format!("{}.bad-", path.file_name().unwrap().to_string_lossy());
+        let moved: Vec<PathBuf> = fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix)))
+            .collect();
+        assert_eq!(moved.len(), 1);
+        let _ = fs::remove_file(&moved[0]);
+    }
+}
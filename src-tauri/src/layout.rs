@@ -0,0 +1,421 @@
+use crate::config::{self, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LayoutError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Where a HUD window lives. `Dual` is a left window plus a right one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HudPlacement {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Dual,
+    Floating,
+}
+
+impl HudPlacement {
+    /// Screen edge the window docks to. In dual mode the secondary window
+    /// takes the right edge. Floating windows don't dock.
+    pub fn edge(self, secondary: bool) -> Option<Edge> {
+        match self {
+            HudPlacement::Left => Some(Edge::Left),
+            HudPlacement::Right => Some(Edge::Right),
+            HudPlacement::Top => Some(Edge::Top),
+            HudPlacement::Bottom => Some(Edge::Bottom),
+            HudPlacement::Dual if secondary => Some(Edge::Right),
+            HudPlacement::Dual => Some(Edge::Left),
+            HudPlacement::Floating => None,
+        }
+    }
+}
+
+/// Last placement applied to a window, re-applied when monitors change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowPlacement {
+    pub placement: HudPlacement,
+    /// Fingerprint from `monitors::fingerprint`; None = the window's current monitor
+    pub monitor_id: Option<String>,
+    pub trigger_style: TriggerStyle,
+    pub dock_height: Option<f64>,
+    pub tucked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// Top and bottom docks run along the width of the screen
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Edge::Top | Edge::Bottom)
+    }
+}
+
+/// What stays on screen while the HUD is tucked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerStyle {
+    /// A small tab centered on the edge; clicks pass through the rest
+    #[default]
+    #[serde(rename = "halfcircle")]
+    HalfCircle,
+    /// A strip along the whole edge
+    #[serde(rename = "column")]
+    Column,
+}
+
+/// Bounds in physical desktop pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    pub fn inflate(&self, by: i32) -> Rect {
+        Rect { x: self.x - by, y: self.y - by, width: self.width + 2 * by, height: self.height + 2 * by }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// Overlap of two rects, if any
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let rect = Rect { x, y, width: self.right().min(other.right()) - x, height: self.bottom().min(other.bottom()) - y };
+        (!rect.is_empty()).then_some(rect)
+    }
+}
+
+/// A monitor's full bounds and the part not covered by taskbars/panels,
+/// both in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorGeometry {
+    pub bounds: Rect,
+    pub work_area: Rect,
+    pub scale_factor: f64,
+}
+
+impl MonitorGeometry {
+    fn px(&self, logical: f64) -> i32 {
+        (logical * self.scale_factor) as i32
+    }
+}
+
+/// Allowed range for the panel width when docked left/right
+pub const MIN_CONTENT_WIDTH: f64 = 280.0;
+pub const MAX_CONTENT_WIDTH: f64 = 720.0;
+
+/// Allowed range for the panel height when docked top/bottom
+pub const MIN_DOCK_HEIGHT: f64 = 120.0;
+pub const MAX_DOCK_HEIGHT: f64 = 800.0;
+
+/// HUD dimensions in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HudMetrics {
+    /// Panel width when docked left/right
+    pub content: f64,
+    /// Panel height when docked top/bottom
+    pub content_height: f64,
+    /// Trigger strip thickness
+    pub trigger: f64,
+    /// Length of the half-circle tab along the edge
+    pub tab_length: f64,
+}
+
+impl Default for HudMetrics {
+    fn default() -> Self {
+        // 48 = CSS 44px tab + border padding
+        Self { content: 320.0, content_height: 280.0, trigger: 20.0, tab_length: 48.0 }
+    }
+}
+
+impl HudMetrics {
+    /// Use the window's panel width, if set
+    pub fn with_width(mut self, width: Option<f64>) -> Self {
+        if let Some(width) = width.filter(|w| w.is_finite()) {
+            self.content = width.clamp(MIN_CONTENT_WIDTH, MAX_CONTENT_WIDTH);
+        }
+        self
+    }
+
+    /// Use the user's top/bottom panel height, if set
+    pub fn with_dock_height(mut self, height: Option<f64>) -> Self {
+        if let Some(height) = height.filter(|h| h.is_finite()) {
+            self.content_height = height.clamp(MIN_DOCK_HEIGHT, MAX_DOCK_HEIGHT);
+        }
+        self
+    }
+
+    /// Panel plus trigger, measured across `edge`
+    pub fn thickness(&self, edge: Edge) -> f64 {
+        let content = if edge.is_horizontal() { self.content_height } else { self.content };
+        content + self.trigger
+    }
+}
+
+/// Panel plus trigger, spanning the work area along `edge`. Taskbars and
+/// panels on that edge are already outside the work area, so the HUD sits
+/// next to them rather than underneath.
+pub fn shown_rect(edge: Edge, monitor: &MonitorGeometry, metrics: &HudMetrics) -> Rect {
+    let area = monitor.work_area;
+    let span = if edge.is_horizontal() { area.height } else { area.width };
+    let thickness = monitor.px(metrics.thickness(edge)).min(span);
+    match edge {
+        Edge::Left => Rect { x: area.x, y: area.y, width: thickness, height: area.height },
+        Edge::Right => Rect { x: area.x + area.width - thickness, y: area.y, width: thickness, height: area.height },
+        Edge::Top => Rect { x: area.x, y: area.y, width: area.width, height: thickness },
+        Edge::Bottom => Rect { x: area.x, y: area.y + area.height - thickness, width: area.width, height: thickness },
+    }
+}
+
+/// Only the trigger: a full-length strip, or a tab centered on the edge
+pub fn tucked_rect(edge: Edge, monitor: &MonitorGeometry, metrics: &HudMetrics, style: TriggerStyle) -> Rect {
+    let area = monitor.work_area;
+    let thickness = monitor.px(metrics.trigger);
+    let span = if edge.is_horizontal() { area.width } else { area.height };
+    let length = match style {
+        TriggerStyle::HalfCircle => monitor.px(metrics.tab_length).min(span),
+        TriggerStyle::Column => span,
+    };
+    let offset = (span - length) / 2;
+    match edge {
+        Edge::Left => Rect { x: area.x, y: area.y + offset, width: thickness, height: length },
+        Edge::Right => Rect { x: area.x + area.width - thickness, y: area.y + offset, width: thickness, height: length },
+        Edge::Top => Rect { x: area.x + offset, y: area.y, width: length, height: thickness },
+        Edge::Bottom => Rect { x: area.x + offset, y: area.y + area.height - thickness, width: length, height: thickness },
+    }
+}
+
+/// A window no thicker than the trigger is tucked, whichever edge it's on
+pub fn is_tucked(width: u32, height: u32, scale_factor: f64, metrics: &HudMetrics) -> bool {
+    width.min(height) <= (metrics.trigger * scale_factor) as u32
+}
+
+/// Panel width per window label, saved in hud_layout.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HudSizes {
+    #[serde(default)]
+    pub widths: HashMap<String, f64>,
+}
+
+impl HudSizes {
+    fn path() -> Result<PathBuf, LayoutError> {
+        Ok(config::get_app_dir()?.join("hud_layout.json"))
+    }
+
+    pub fn load() -> Result<Self, LayoutError> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<(), LayoutError> {
+        fs::write(Self::path()?, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Panel width for a window, default if never resized
+    pub fn width(&self, label: &str) -> f64 {
+        HudMetrics::default().with_width(self.widths.get(label).copied()).content
+    }
+
+    pub fn remove(&mut self, label: &str) {
+        self.widths.remove(label);
+    }
+
+    /// Store a width clamped to the allowed range and return it
+    pub fn set_width(&mut self, label: &str, width: f64) -> f64 {
+        let width = HudMetrics::default().with_width(Some(width)).content;
+        self.widths.insert(label.to_string(), width);
+        width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDGES: [Edge; 4] = [Edge::Left, Edge::Right, Edge::Top, Edge::Bottom];
+    const STYLES: [TriggerStyle; 2] = [TriggerStyle::HalfCircle, TriggerStyle::Column];
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    fn monitor(bounds: Rect, work_area: Rect, scale_factor: f64) -> MonitorGeometry {
+        MonitorGeometry { bounds, work_area, scale_factor }
+    }
+
+    /// Primary with a bottom taskbar, a negative-origin monitor above-left,
+    /// a 4K monitor with a left taskbar, and one with a top panel
+    fn monitors() -> Vec<MonitorGeometry> {
+        vec![
+            monitor(rect(0, 0, 1920, 1080), rect(0, 0, 1920, 1040), 1.0),
+            monitor(rect(-2560, -200, 2560, 1440), rect(-2560, -200, 2560, 1440), 1.5),
+            monitor(rect(1920, 0, 3840, 2160), rect(2016, 0, 3744, 2160), 2.0),
+            monitor(rect(0, 1080, 1920, 1080), rect(0, 1128, 1920, 1032), 1.0),
+        ]
+    }
+
+    fn along(edge: Edge, r: &Rect) -> i32 {
+        if edge.is_horizontal() {
+            r.width
+        } else {
+            r.height
+        }
+    }
+
+    fn across(edge: Edge, r: &Rect) -> i32 {
+        if edge.is_horizontal() {
+            r.height
+        } else {
+            r.width
+        }
+    }
+
+    fn hugs_edge(edge: Edge, r: &Rect, area: &Rect) -> bool {
+        match edge {
+            Edge::Left => r.x == area.x,
+            Edge::Right => r.right() == area.right(),
+            Edge::Top => r.y == area.y,
+            Edge::Bottom => r.bottom() == area.bottom(),
+        }
+    }
+
+    #[test]
+    fn shown_rect_docks_inside_the_work_area() {
+        let metrics = HudMetrics::default();
+        for m in monitors() {
+            for edge in EDGES {
+                let shown = shown_rect(edge, &m, &metrics);
+                let area = m.work_area;
+                assert_eq!(shown.intersect(&area), Some(shown), "{edge:?} on {m:?}");
+                assert!(hugs_edge(edge, &shown, &area), "{edge:?} on {m:?}");
+                assert_eq!(along(edge, &shown), along(edge, &area), "{edge:?} on {m:?}");
+                assert_eq!(across(edge, &shown), (metrics.thickness(edge) * m.scale_factor) as i32, "{edge:?} on {m:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn tucked_rect_is_the_trigger_of_the_shown_rect() {
+        let metrics = HudMetrics::default();
+        for m in monitors() {
+            for edge in EDGES {
+                let shown = shown_rect(edge, &m, &metrics);
+                for style in STYLES {
+                    let tucked = tucked_rect(edge, &m, &metrics, style);
+                    let case = format!("{edge:?} {style:?} on {m:?}");
+                    assert_eq!(shown.intersect(&tucked), Some(tucked), "{case}");
+                    assert!(hugs_edge(edge, &tucked, &m.work_area), "{case}");
+                    assert_eq!(across(edge, &tucked), (metrics.trigger * m.scale_factor) as i32, "{case}");
+
+                    let span = along(edge, &m.work_area);
+                    let length = along(edge, &tucked);
+                    match style {
+                        TriggerStyle::Column => assert_eq!(length, span, "{case}"),
+                        TriggerStyle::HalfCircle => assert_eq!(length, (metrics.tab_length * m.scale_factor) as i32, "{case}"),
+                    }
+                    // Centered along the edge
+                    let (start, area_start) =
+                        if edge.is_horizontal() { (tucked.x, m.work_area.x) } else { (tucked.y, m.work_area.y) };
+                    assert_eq!(start - area_start, (span - length) / 2, "{case}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rects_match_known_positions() {
+        let metrics = HudMetrics::default();
+        let [primary, negative, left_taskbar, top_panel] = monitors().try_into().unwrap();
+
+        assert_eq!(shown_rect(Edge::Left, &primary, &metrics), rect(0, 0, 340, 1040));
+        assert_eq!(shown_rect(Edge::Bottom, &primary, &metrics), rect(0, 740, 1920, 300));
+        assert_eq!(tucked_rect(Edge::Right, &primary, &metrics, TriggerStyle::HalfCircle), rect(1900, 496, 20, 48));
+
+        assert_eq!(shown_rect(Edge::Right, &negative, &metrics), rect(-510, -200, 510, 1440));
+        assert_eq!(tucked_rect(Edge::Top, &negative, &metrics, TriggerStyle::HalfCircle), rect(-1316, -200, 72, 30));
+
+        assert_eq!(shown_rect(Edge::Left, &left_taskbar, &metrics), rect(2016, 0, 680, 2160));
+        assert_eq!(tucked_rect(Edge::Left, &left_taskbar, &metrics, TriggerStyle::Column), rect(2016, 0, 40, 2160));
+
+        assert_eq!(shown_rect(Edge::Top, &top_panel, &metrics), rect(0, 1128, 1920, 300));
+        assert_eq!(tucked_rect(Edge::Top, &top_panel, &metrics, TriggerStyle::Column), rect(0, 1128, 1920, 20));
+    }
+
+    #[test]
+    fn shown_rect_never_exceeds_a_small_work_area() {
+        let metrics = HudMetrics::default().with_width(Some(MAX_CONTENT_WIDTH));
+        let small = monitor(rect(0, 0, 640, 480), rect(0, 0, 640, 480), 2.0);
+        assert_eq!(shown_rect(Edge::Left, &small, &metrics), rect(0, 0, 640, 480));
+        assert_eq!(shown_rect(Edge::Bottom, &small, &metrics), rect(0, 0, 640, 480));
+    }
+
+    #[test]
+    fn is_tucked_compares_the_thin_side_to_the_trigger() {
+        let metrics = HudMetrics::default();
+        for m in monitors() {
+            for edge in EDGES {
+                let shown = shown_rect(edge, &m, &metrics);
+                assert!(!is_tucked(shown.width as u32, shown.height as u32, m.scale_factor, &metrics), "{edge:?} on {m:?}");
+                for style in STYLES {
+                    let tucked = tucked_rect(edge, &m, &metrics, style);
+                    assert!(is_tucked(tucked.width as u32, tucked.height as u32, m.scale_factor, &metrics), "{edge:?} on {m:?}");
+                }
+            }
+        }
+        assert!(is_tucked(30, 72, 1.5, &metrics));
+        assert!(!is_tucked(31, 72, 1.5, &metrics));
+    }
+
+    #[test]
+    fn intersect_handles_overlap_containment_and_gaps() {
+        let a = rect(-100, -50, 200, 100);
+        assert_eq!(a.intersect(&rect(50, 0, 200, 200)), Some(rect(50, 0, 50, 50)));
+        assert_eq!(a.intersect(&rect(-50, -25, 10, 10)), Some(rect(-50, -25, 10, 10)));
+        assert_eq!(a.intersect(&a), Some(a));
+        // Touching edges share no pixels
+        assert_eq!(a.intersect(&rect(100, -50, 10, 100)), None);
+        assert_eq!(a.intersect(&rect(-100, 50, 200, 10)), None);
+        assert_eq!(a.intersect(&rect(500, 500, 10, 10)), None);
+    }
+}
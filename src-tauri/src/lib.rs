@@ -1,5 +1,26 @@
+mod alerts;
+mod animation;
+mod annotate;
 mod capture;
+mod clipboard_history;
+mod clipboard_image;
+mod clipboard_transform;
+mod color;
 mod config;
+mod edge_hover;
+mod floating;
+mod fullscreen;
+mod hud_windows;
+mod layout;
+mod library;
+mod market_data;
+mod monitors;
+mod positions;
+mod screenshot;
+mod sizing;
+mod trade_import;
+mod trade_plan;
+mod work_area;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use serde::{Deserialize, Serialize};
@@ -9,44 +30,38 @@ use tauri::{
     tray::TrayIconBuilder,
 };
 
-/// Get the actual work-area height for a monitor by querying the OS.
-/// Falls back to screen_height - 48*scale on non-Windows or on failure.
-fn get_work_area_height(monitor: &tauri::Monitor) -> i32 {
+/// Full monitor bounds in physical pixels
+fn monitor_bounds(monitor: &tauri::Monitor) -> layout::Rect {
+    let position = monitor.position();
+    let size = monitor.size();
+    layout::Rect { x: position.x, y: position.y, width: size.width as i32, height: size.height as i32 }
+}
+
+/// Work area (screen minus taskbars/panels/dock) for a monitor, in physical pixels
+fn get_work_area(app: &tauri::AppHandle, monitor: &tauri::Monitor) -> layout::Rect {
+    let bounds = monitor_bounds(monitor);
     let scale_factor = monitor.scale_factor();
-    let screen_height = monitor.size().height as i32;
 
-    #[cfg(target_os = "windows")]
+    // AppKit only answers on the main thread; runs inline if we're already on it
+    #[cfg(target_os = "macos")]
     {
-        #[repr(C)]
-        struct RECT { left: i32, top: i32, right: i32, bottom: i32 }
-
-        extern "system" {
-            fn SystemParametersInfoW(uiAction: u32, uiParam: u32, pvParam: *mut std::ffi::c_void, fWinIni: u32) -> i32;
-        }
-
-        const SPI_GETWORKAREA: u32 = 0x0030;
-        let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
-        let ok = unsafe {
-            SystemParametersInfoW(
-                SPI_GETWORKAREA,
-                0,
-                &mut rect as *mut RECT as *mut std::ffi::c_void,
-                0,
-            )
-        };
-        if ok != 0 {
-            // rect is in logical pixels for the primary monitor work area.
-            // Scale to physical pixels to match monitor.size() which is physical.
-            let work_h = ((rect.bottom - rect.top) as f64 * scale_factor) as i32;
-            // Only use it when it looks sane (positive and smaller than full screen)
-            if work_h > 0 && work_h <= screen_height {
-                return work_h;
-            }
-        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let asked = app.run_on_main_thread(move || {
+            let _ = tx.send(work_area::detect(bounds, scale_factor));
+        });
+        return asked
+            .ok()
+            .and_then(|_| rx.recv_timeout(std::time::Duration::from_millis(250)).ok().flatten())
+            .filter(|area| work_area::is_plausible(&bounds, area))
+            .unwrap_or_else(|| work_area::fallback(bounds, scale_factor));
     }
 
-    // Fallback: assume 48 logical-px taskbar
-    screen_height - (48.0 * scale_factor) as i32
+    // Only macOS needs the main thread
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        work_area::work_area(bounds, scale_factor)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -69,230 +84,302 @@ async fn capture_screen(region: Option<[i32; 4]>, default_crop: Option<bool>) ->
     })
 }
 
-const CONTENT_WIDTH: i32 = 320; // Width of main content area
-const TRIGGER_WIDTH: i32 = 20;  // Width of trigger bar
-const TOTAL_WIDTH: i32 = CONTENT_WIDTH + TRIGGER_WIDTH; // 340px
-const HALF_CIRCLE_HEIGHT: i32 = 48; // Height of the half-circle trigger tab (CSS 44px + border padding)
-
-// Tucking resizes window to only show trigger zone (20px)
-// Showing expands window to full width (340px)
-// Position parameter determines which edge the window sticks to
-
-/// Tuck window - shrink to trigger zone width only
-/// When trigger_style is "halfcircle", the window shrinks to just the tab size
-/// so clicks pass through the rest of the screen edge.
-#[tauri::command]
-async fn tuck_window(window: WebviewWindow, position: String, monitor_index: Option<usize>, trigger_style: Option<String>) -> Result<(), String> {
-    let app = window.app_handle();
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let monitor = if let Some(idx) = monitor_index {
-        monitors.into_iter().nth(idx).ok_or("Monitor index out of range")?
-    } else {
-        window.current_monitor()
-            .map_err(|e| e.to_string())?
-            .ok_or("No monitor found")?
-    };
+/// Monitor bounds, work area and scale in the form the layout engine takes
+fn monitor_geometry(app: &tauri::AppHandle, monitor: &tauri::Monitor) -> layout::MonitorGeometry {
+    layout::MonitorGeometry {
+        bounds: monitor_bounds(monitor),
+        work_area: get_work_area(app, monitor),
+        scale_factor: monitor.scale_factor(),
+    }
+}
 
-    let scale_factor = monitor.scale_factor();
-    let screen_width = monitor.size().width as i32;
-    let work_area_height = get_work_area_height(&monitor);
+/// Every connected monitor with its stable id, in enumeration order
+fn list_monitors(app: &tauri::AppHandle) -> Result<Vec<(tauri::Monitor, monitors::MonitorInfo)>, String> {
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?.map(|m| monitor_bounds(&m));
+    Ok(app.available_monitors()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let bounds = monitor_bounds(&monitor);
+            let info = monitors::MonitorInfo::new(
+                index,
+                monitor.name().cloned(),
+                bounds,
+                monitor.scale_factor(),
+                primary == Some(bounds),
+            );
+            (monitor, info)
+        })
+        .collect())
+}
 
-    let is_halfcircle = trigger_style.as_deref() == Some("halfcircle");
+/// Monitor matching a saved id, or the primary one if it's gone (or no id is given)
+fn find_monitor(app: &tauri::AppHandle, monitor_id: Option<&str>) -> Result<tauri::Monitor, String> {
+    let mut list = list_monitors(app)?;
+    let infos: Vec<_> = list.iter().map(|(_, info)| info.clone()).collect();
+    let index = monitor_id
+        .and_then(|id| monitors::find(&infos, id))
+        .or_else(|| monitors::primary(&infos))
+        .ok_or("No monitor found")?;
+    Ok(list.swap_remove(index).0)
+}
 
-    // For half-circle mode: window is only as tall as the tab
-    // For column mode: window spans the full work area height
-    let window_height = if is_halfcircle {
-        (HALF_CIRCLE_HEIGHT as f64 * scale_factor) as i32
-    } else {
-        work_area_height
-    };
+/// Monitor matching a saved id, or the one the window is currently on when no id is given
+fn resolve_monitor(window: &WebviewWindow, monitor_id: Option<&str>) -> Result<tauri::Monitor, String> {
+    if monitor_id.is_none() {
+        if let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? {
+            return Ok(monitor);
+        }
+    }
+    find_monitor(window.app_handle(), monitor_id)
+}
 
-    // Set new size with correct height (in physical pixels)
-    window.set_size(tauri::PhysicalSize::new(
-        (TRIGGER_WIDTH as f64 * scale_factor) as u32,
-        window_height as u32
-    )).map_err(|e| e.to_string())?;
+/// Edge this window docks to; in dual mode the dual-right window takes the right edge
+fn window_edge(window: &WebviewWindow, placement: layout::HudPlacement) -> Option<layout::Edge> {
+    placement.edge(window.label() == hud_windows::DUAL_LABEL)
+}
 
-    // Reposition to correct edge (in physical pixels)
-    let x = if position == "right" {
-        screen_width - (TRIGGER_WIDTH as f64 * scale_factor) as i32
+/// Move and resize a window to `rect`. When growing, move first so the window
+/// doesn't momentarily overflow onto an adjacent monitor; when shrinking, resize first.
+fn apply_window_rect(window: &WebviewWindow, rect: layout::Rect, move_first: bool) -> Result<(), String> {
+    let position = PhysicalPosition::new(rect.x, rect.y);
+    let size = tauri::PhysicalSize::new(rect.width.max(1) as u32, rect.height.max(1) as u32);
+    if move_first {
+        window.set_position(position).map_err(|e| e.to_string())?;
+        window.set_size(size).map_err(|e| e.to_string())?;
     } else {
-        0
-    };
+        window.set_size(size).map_err(|e| e.to_string())?;
+        window.set_position(position).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
 
-    // Get monitor position offset
-    let monitor_x = monitor.position().x;
-    let monitor_y = monitor.position().y;
+/// Last placement applied to each HUD window, by label
+struct WindowLayoutState(Mutex<std::collections::HashMap<String, layout::WindowPlacement>>);
 
-    // For half-circle: center vertically in the work area
-    let y = if is_halfcircle {
-        (work_area_height - window_height) / 2
-    } else {
-        0
-    };
+/// Update and return the window's recorded placement
+fn update_placement(window: &WebviewWindow, update: impl FnOnce(&mut layout::WindowPlacement)) -> layout::WindowPlacement {
+    let state = window.state::<WindowLayoutState>();
+    let mut placements = state.0.lock().unwrap();
+    let placement = placements.entry(window.label().to_string()).or_default();
+    update(placement);
+    placement.clone()
+}
 
-    window.set_position(PhysicalPosition::new(monitor_x + x, monitor_y + y))
-        .map_err(|e| e.to_string())?;
+/// Panel width per window label
+struct HudSizesState(Mutex<layout::HudSizes>);
 
-    Ok(())
+/// HUD metrics for a window: its own panel width plus the top/bottom dock height
+fn window_metrics(window: &WebviewWindow, dock_height: Option<f64>) -> layout::HudMetrics {
+    let width = window.state::<HudSizesState>().0.lock().unwrap().width(window.label());
+    layout::HudMetrics::default().with_width(Some(width)).with_dock_height(dock_height)
 }
 
-/// Show window - expand to full width
-#[tauri::command]
-async fn show_window(window: WebviewWindow, position: String, monitor_index: Option<usize>) -> Result<(), String> {
-    let app = window.app_handle();
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let monitor = if let Some(idx) = monitor_index {
-        monitors.into_iter().nth(idx).ok_or("Monitor index out of range")?
-    } else {
-        window.current_monitor()
-            .map_err(|e| e.to_string())?
-            .ok_or("No monitor found")?
+/// Target rect for a window's placement, and whether to move before resizing.
+/// None for floating windows, which keep their own bounds.
+fn placement_rect(window: &WebviewWindow, placement: &layout::WindowPlacement) -> Result<Option<(layout::Rect, bool)>, String> {
+    let Some(edge) = window_edge(window, placement.placement) else {
+        return Ok(None);
     };
+    let monitor = monitor_geometry(window.app_handle(), &resolve_monitor(window, placement.monitor_id.as_deref())?);
+    let metrics = window_metrics(window, placement.dock_height);
+    Ok(Some(if placement.tucked {
+        (layout::tucked_rect(edge, &monitor, &metrics, placement.trigger_style), false)
+    } else {
+        (layout::shown_rect(edge, &monitor, &metrics), true)
+    }))
+}
 
-    let scale_factor = monitor.scale_factor();
-    let screen_width = monitor.size().width as i32;
-    let window_height = get_work_area_height(&monitor);
+/// Size and position a window for its placement on its monitor, immediately
+fn apply_placement(window: &WebviewWindow, placement: &layout::WindowPlacement) -> Result<(), String> {
+    let Some((rect, move_first)) = placement_rect(window, placement)? else {
+        return Ok(());
+    };
+    cancel_animation(window);
+    apply_window_rect(window, rect, move_first)
+}
 
-    // Reposition FIRST so the window doesn't momentarily overflow
-    // onto an adjacent monitor before the resize completes
-    let x = if position == "right" {
-        screen_width - (TOTAL_WIDTH as f64 * scale_factor) as i32
-    } else {
-        0
+/// Slide a window to its placement; used when tucking and showing
+fn animate_placement(window: &WebviewWindow, placement: &layout::WindowPlacement) -> Result<(), String> {
+    let Some((rect, move_first)) = placement_rect(window, placement)? else {
+        return Ok(());
     };
+    animate_window(window, rect, move_first)
+}
 
-    let monitor_x = monitor.position().x;
-    let monitor_y = monitor.position().y;
+/// Slide to the placement, or jump there when `animate` is false (e.g. tucking
+/// out of a screenshot, where the window must be gone when the command returns)
+fn move_to_placement(window: &WebviewWindow, placement: &layout::WindowPlacement, animate: Option<bool>) -> Result<(), String> {
+    if animate.unwrap_or(true) {
+        animate_placement(window, placement)
+    } else {
+        apply_placement(window, placement)
+    }
+}
 
-    window.set_position(PhysicalPosition::new(monitor_x + x, monitor_y))
-        .map_err(|e| e.to_string())?;
+// Tucking shrinks the window to the trigger zone, showing expands it to
+// panel + trigger. The geometry itself lives in layout.rs.
 
-    // Then expand to full width
-    window.set_size(tauri::PhysicalSize::new(
-        (TOTAL_WIDTH as f64 * scale_factor) as u32,
-        window_height as u32
-    )).map_err(|e| e.to_string())?;
+/// Tuck window - shrink to trigger zone only
+/// With the half-circle trigger the window shrinks to just the tab,
+/// so clicks pass through the rest of the screen edge.
+/// `animate: false` skips the slide.
+#[tauri::command]
+async fn tuck_window(
+    window: WebviewWindow,
+    position: layout::HudPlacement,
+    monitor_id: Option<String>,
+    trigger_style: Option<layout::TriggerStyle>,
+    animate: Option<bool>,
+) -> Result<(), String> {
+    let placement = update_placement(&window, |p| {
+        p.placement = position;
+        p.monitor_id = monitor_id;
+        p.trigger_style = trigger_style.unwrap_or(layout::TriggerStyle::Column);
+        p.tucked = true;
+    });
+    move_to_placement(&window, &placement, animate)
+}
 
-    Ok(())
+/// Show window - expand to panel + trigger. `dock_height` is the panel
+/// height when docked top/bottom. `animate: false` skips the slide.
+#[tauri::command]
+async fn show_window(
+    window: WebviewWindow,
+    position: layout::HudPlacement,
+    monitor_id: Option<String>,
+    dock_height: Option<f64>,
+    animate: Option<bool>,
+) -> Result<(), String> {
+    let placement = update_placement(&window, |p| {
+        p.placement = position;
+        p.monitor_id = monitor_id;
+        p.dock_height = dock_height;
+        p.tucked = false;
+    });
+    move_to_placement(&window, &placement, animate)
 }
 
 /// Check if window is tucked
 #[tauri::command]
 async fn is_window_tucked(window: WebviewWindow) -> Result<bool, String> {
+    window_is_tucked(&window)
+}
+
+fn window_is_tucked(window: &WebviewWindow) -> Result<bool, String> {
+    // Mid-slide the size says little; the recorded placement is the target
+    if let Some(placement) = window.state::<WindowLayoutState>().0.lock().unwrap().get(window.label()) {
+        return Ok(placement.tucked);
+    }
     let monitor = window.current_monitor()
         .map_err(|e| e.to_string())?
         .ok_or("No monitor found")?;
-    let scale_factor = monitor.scale_factor();
     let size = window.outer_size().map_err(|e| e.to_string())?;
-    Ok(size.width <= (TRIGGER_WIDTH as f64 * scale_factor) as u32)
+    Ok(layout::is_tucked(size.width, size.height, monitor.scale_factor(), &layout::HudMetrics::default()))
 }
 
 /// Setup window for full height (call on startup)
 #[tauri::command]
-async fn setup_window_size(window: WebviewWindow, monitor_index: Option<usize>) -> Result<(), String> {
-    use tauri::PhysicalSize;
-
-    // Get monitor work area
-    let app = window.app_handle();
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let monitor = if let Some(idx) = monitor_index {
-        monitors.into_iter().nth(idx).ok_or("Monitor index out of range")?
-    } else {
-        window.current_monitor()
-            .map_err(|e| e.to_string())?
-            .ok_or("No monitor found")?
-    };
-
-    let scale_factor = monitor.scale_factor();
-    let window_height = get_work_area_height(&monitor);
-    let window_width = (TOTAL_WIDTH as f64 * scale_factor) as u32;
-
-    window.set_size(PhysicalSize::new(window_width, window_height as u32))
-        .map_err(|e| e.to_string())?;
-
+async fn setup_window_size(window: WebviewWindow, monitor_id: Option<String>) -> Result<(), String> {
+    let monitor = monitor_geometry(window.app_handle(), &resolve_monitor(&window, monitor_id.as_deref())?);
     // Position window at left edge by default
-    let monitor_x = monitor.position().x;
-    let monitor_y = monitor.position().y;
-
-    window.set_position(PhysicalPosition::new(monitor_x, monitor_y))
-        .map_err(|e| e.to_string())?;
-
+    let rect = layout::shown_rect(layout::Edge::Left, &monitor, &window_metrics(&window, None));
+    cancel_animation(&window);
+    apply_window_rect(&window, rect, false)?;
     window.show().map_err(|e| e.to_string())?;
-
     Ok(())
 }
 
-/// Set window position to left or right edge
+/// Move the window to its placement's edge, keeping its current size
 #[tauri::command]
-async fn set_window_position(window: WebviewWindow, position: String, monitor_index: Option<usize>) -> Result<(), String> {
-    let app = window.app_handle();
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let monitor = if let Some(idx) = monitor_index {
-        monitors.into_iter().nth(idx).ok_or("Monitor index out of range")?
-    } else {
-        window.current_monitor()
-            .map_err(|e| e.to_string())?
-            .ok_or("No monitor found")?
-    };
-
-    let scale_factor = monitor.scale_factor();
-    let screen_width = monitor.size().width as i32;
-
-    let x = if position == "right" {
-        screen_width - (TOTAL_WIDTH as f64 * scale_factor) as i32
-    } else {
-        0
+async fn set_window_position(
+    window: WebviewWindow,
+    position: layout::HudPlacement,
+    monitor_id: Option<String>,
+    dock_height: Option<f64>,
+) -> Result<(), String> {
+    update_placement(&window, |p| {
+        p.placement = position;
+        p.monitor_id = monitor_id.clone();
+    });
+    let Some(edge) = window_edge(&window, position) else {
+        return Ok(());
     };
-
-    // Get monitor position offset
-    let monitor_x = monitor.position().x;
-    let monitor_y = monitor.position().y;
-
-    window.set_position(PhysicalPosition::new(monitor_x + x, monitor_y))
+    let monitor = monitor_geometry(window.app_handle(), &resolve_monitor(&window, monitor_id.as_deref())?);
+    let rect = layout::shown_rect(edge, &monitor, &window_metrics(&window, dock_height));
+    cancel_animation(&window);
+    window.set_position(PhysicalPosition::new(rect.x, rect.y))
         .map_err(|e| e.to_string())?;
-
     Ok(())
 }
 
-#[derive(Debug, Serialize)]
-pub struct MonitorInfo {
-    pub index: usize,
-    pub name: String,
-    pub width: u32,
-    pub height: u32,
-    pub is_primary: bool,
+/// Panel width of this window in logical px
+#[tauri::command]
+async fn get_hud_width(window: WebviewWindow) -> Result<f64, String> {
+    Ok(window.state::<HudSizesState>().0.lock().unwrap().width(window.label()))
 }
 
-/// Get list of available monitors
+/// Resize this window's panel, clamped to the allowed range. While dragging
+/// the frontend passes `persist: false` and saves once on release.
 #[tauri::command]
-async fn get_available_monitors(app: tauri::AppHandle) -> Result<Vec<MonitorInfo>, String> {
-    let monitors = app.available_monitors()
-        .map_err(|e| e.to_string())?;
-
-    let monitor_list: Vec<MonitorInfo> = monitors
-        .into_iter()
-        .enumerate()
-        .map(|(index, monitor)| {
-            let size = monitor.size();
-            let _raw_name = monitor.name()
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| format!("Display {}", index + 1));
+async fn set_hud_width(window: WebviewWindow, width: f64, persist: Option<bool>) -> Result<f64, String> {
+    let width = {
+        let state = window.state::<HudSizesState>();
+        let mut sizes = state.0.lock().unwrap();
+        let width = sizes.set_width(window.label(), width);
+        if persist.unwrap_or(true) {
+            sizes.save().map_err(|e| e.to_string())?;
+        }
+        width
+    };
+    let placement = window.state::<WindowLayoutState>().0.lock().unwrap().get(window.label()).cloned();
+    if let Some(placement) = placement.filter(|p| !p.tucked) {
+        apply_placement(&window, &placement)?;
+    }
+    Ok(width)
+}
 
-            // Use a simple sequential "Display N" format with resolution
-            let display_name = format!("Display {} ({}×{})", index + 1, size.width, size.height);
+/// Get list of available monitors
+#[tauri::command]
+async fn get_available_monitors(app: tauri::AppHandle) -> Result<Vec<monitors::MonitorInfo>, String> {
+    Ok(list_monitors(&app)?.into_iter().map(|(_, info)| info).collect())
+}
 
-            MonitorInfo {
-                index,
-                name: display_name,
-                width: size.width,
-                height: size.height,
-                is_primary: index == 0, // First monitor is typically primary
+/// Poll the display configuration. When monitors are plugged, unplugged,
+/// moved or rescaled, re-apply every HUD window's placement (falling back to
+/// the primary monitor when its own is gone) and emit `monitors-changed`.
+fn spawn_monitor_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_signature: Option<String> = None;
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(2));
+            let Ok(list) = list_monitors(&app) else { continue };
+            let infos: Vec<_> = list.into_iter().map(|(_, info)| info).collect();
+            let signature = monitors::signature(&infos);
+            if last_signature.as_ref() == Some(&signature) {
+                continue;
+            }
+            let changed = last_signature.is_some();
+            last_signature = Some(signature);
+            if !changed || infos.is_empty() {
+                continue;
             }
-        })
-        .collect();
 
-    Ok(monitor_list)
+            let placements: Vec<_> = app.state::<WindowLayoutState>().0.lock().unwrap()
+                .iter()
+                .map(|(label, placement)| (label.clone(), placement.clone()))
+                .collect();
+            for (label, placement) in placements {
+                if let Some(window) = app.get_webview_window(&label) {
+                    if let Err(e) = apply_placement(&window, &placement) {
+                        eprintln!("Failed to re-apply placement for {}: {}", label, e);
+                    }
+                }
+            }
+            let _ = app.emit("monitors-changed", &infos);
+        }
+    });
 }
 
 #[derive(Debug, Serialize)]
@@ -301,39 +388,46 @@ pub struct CursorPosition {
     pub y: i32,
 }
 
-#[tauri::command]
-async fn get_cursor_position() -> Result<CursorPosition, String> {
-    // Use Windows API to get cursor position
-    #[cfg(target_os = "windows")]
-    {
-        use std::mem::MaybeUninit;
-
-        #[repr(C)]
-        struct POINT {
-            x: i32,
-            y: i32,
-        }
-
-        extern "system" {
-            fn GetCursorPos(lpPoint: *mut POINT) -> i32;
-        }
-
-        let mut point = MaybeUninit::<POINT>::uninit();
-        let result = unsafe { GetCursorPos(point.as_mut_ptr()) };
+// One pointer reader (and X11 connection) shared by the cursor command and
+// the edge-hover service
+struct PointerReaderState(Mutex<edge_hover::PointerReader>);
 
-        if result != 0 {
-            let point = unsafe { point.assume_init() };
-            return Ok(CursorPosition { x: point.x, y: point.y });
-        }
+/// Global cursor position in physical pixels
+#[tauri::command]
+async fn get_cursor_position(app: tauri::AppHandle, reader: State<'_, PointerReaderState>) -> Result<CursorPosition, String> {
+    let pointer = reader.0.lock().unwrap().read();
+    if let Some(pointer) = pointer {
+        return Ok(CursorPosition { x: pointer.x, y: pointer.y });
     }
+    let position = app.cursor_position().map_err(|e| format!("Failed to get cursor position: {}", e))?;
+    Ok(CursorPosition { x: position.x as i32, y: position.y as i32 })
+}
 
-    Err("Failed to get cursor position".to_string())
+/// Sample the averaged NxN area around a physical desktop point
+fn sample_color_at(x: f64, y: f64, sample_size: u32) -> Result<color::ColorSample, String> {
+    let capture = capture::capture_screen_at(x, y).map_err(|e| e.to_string())?;
+    let (px, py) = capture
+        .pixel_at(x, y)
+        .ok_or_else(|| format!("Point ({}, {}) is outside the captured screen", x, y))?;
+    Ok(color::ColorSample::from_rgb(color::average_area(&capture.image, px, py, sample_size)))
 }
 
-/// Pick the screen color at the current cursor position (Windows only).
-/// Hides the overlay window first so GetPixel reads the real desktop.
+/// Pick the screen color at the current cursor position as hex.
+/// Uses the overlay session's capture when there is one; otherwise hides the
+/// overlay window first so a fresh capture sees the real desktop.
 #[tauri::command]
-async fn pick_screen_color(app: tauri::AppHandle) -> Result<String, String> {
+async fn pick_screen_color(
+    app: tauri::AppHandle,
+    capture_state: State<'_, ColorPickerCaptureState>,
+    sample_size: Option<u32>,
+) -> Result<String, String> {
+    let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+    if let Some(screens) = capture_state.0.lock().unwrap().as_ref() {
+        if let Some((screen, (px, py))) = capture::locate(screens, cursor.x, cursor.y) {
+            return Ok(color::to_hex(color::average_area(&screen.image, px, py, sample_size.unwrap_or(1))));
+        }
+    }
+
     // Hide the overlay from Rust so timing is deterministic
     if let Some(overlay) = app.get_webview_window("color-picker-overlay") {
         let _ = overlay.hide();
@@ -341,50 +435,79 @@ async fn pick_screen_color(app: tauri::AppHandle) -> Result<String, String> {
     // Wait for the compositor to fully remove the overlay surface
     std::thread::sleep(std::time::Duration::from_millis(150));
 
-    #[cfg(target_os = "windows")]
-    {
-        use std::mem::MaybeUninit;
-
-        #[repr(C)]
-        struct POINT { x: i32, y: i32 }
-
-        type HDC = *mut std::ffi::c_void;
-        type HWND = *mut std::ffi::c_void;
-        type COLORREF = u32;
-
-        extern "system" {
-            fn GetCursorPos(lpPoint: *mut POINT) -> i32;
-            fn GetDC(hWnd: HWND) -> HDC;
-            fn ReleaseDC(hWnd: HWND, hDC: HDC) -> i32;
-            fn GetPixel(hdc: HDC, x: i32, y: i32) -> COLORREF;
-        }
+    Ok(sample_color_at(cursor.x, cursor.y, sample_size.unwrap_or(1))?.hex)
+}
 
-        let mut pt = MaybeUninit::<POINT>::uninit();
-        if unsafe { GetCursorPos(pt.as_mut_ptr()) } == 0 {
-            return Err("Failed to get cursor position".to_string());
+/// Sample the screen color at a physical desktop point (defaults to the cursor)
+/// in hex, RGB, HSL and OKLCH
+#[tauri::command]
+async fn sample_screen_color(
+    app: tauri::AppHandle,
+    x: Option<f64>,
+    y: Option<f64>,
+    sample_size: Option<u32>,
+) -> Result<color::ColorSample, String> {
+    let (x, y) = match (x, y) {
+        (Some(x), Some(y)) => (x, y),
+        _ => {
+            let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+            (cursor.x, cursor.y)
         }
-        let pt = unsafe { pt.assume_init() };
+    };
+    sample_color_at(x, y, sample_size.unwrap_or(1))
+}
 
-        let hdc = unsafe { GetDC(std::ptr::null_mut()) };
-        if hdc.is_null() {
-            return Err("Failed to get screen DC".to_string());
-        }
+/// Dominant colors of a screenshot file, or of a live capture region when no path is given
+#[tauri::command]
+async fn extract_color_palette(
+    path: Option<String>,
+    region: Option<[i32; 4]>,
+    count: Option<usize>,
+) -> Result<Vec<color::PaletteColor>, String> {
+    let count = count.unwrap_or(6);
+    match path {
+        Some(path) => color::palette_from_path(&path, region, count),
+        None => color::palette_from_screen(region, count),
+    }
+    .map_err(|e| e.to_string())
+}
 
-        let color = unsafe { GetPixel(hdc, pt.x, pt.y) };
-        unsafe { ReleaseDC(std::ptr::null_mut(), hdc) };
+/// WCAG contrast ratio of two hex colors plus colorblind simulations of the pair
+#[tauri::command]
+async fn check_color_contrast(foreground: String, background: String) -> Result<color::ContrastReport, String> {
+    let fg = color::parse_hex(&foreground).map_err(|e| e.to_string())?;
+    let bg = color::parse_hex(&background).map_err(|e| e.to_string())?;
+    Ok(color::contrast_report(fg, bg))
+}
 
-        if color == 0xFFFFFFFF {
-            return Err(format!("GetPixel failed at ({}, {})", pt.x, pt.y));
+/// Zoomed pixel grid around a physical desktop point (defaults to the cursor),
+/// read from the color picker overlay's cached capture. Outside an overlay
+/// session it captures the screen under the point without caching it.
+#[tauri::command]
+async fn get_color_loupe(
+    app: tauri::AppHandle,
+    capture_state: State<'_, ColorPickerCaptureState>,
+    x: Option<f64>,
+    y: Option<f64>,
+    size: Option<u32>,
+) -> Result<color::Loupe, String> {
+    let (x, y) = match (x, y) {
+        (Some(x), Some(y)) => (x, y),
+        _ => {
+            let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+            (cursor.x, cursor.y)
         }
+    };
 
-        let r = color & 0xFF;
-        let g = (color >> 8) & 0xFF;
-        let b = (color >> 16) & 0xFF;
-        return Ok(format!("#{:02x}{:02x}{:02x}", r, g, b));
+    let outside = || format!("Point ({}, {}) is outside the captured screens", x, y);
+    if let Some(screens) = capture_state.0.lock().unwrap().as_ref() {
+        let (screen, (px, py)) = capture::locate(screens, x, y).ok_or_else(outside)?;
+        return Ok(color::loupe(&screen.image, px, py, size.unwrap_or(15)));
     }
 
-    #[cfg(not(target_os = "windows"))]
-    Err("pick_screen_color is only supported on Windows".to_string())
+    let screen = capture::capture_screen_at(x, y).map_err(|e| e.to_string())?;
+    let (px, py) = screen.pixel_at(x, y).ok_or_else(outside)?;
+    Ok(color::loupe(&screen.image, px, py, size.unwrap_or(15)))
 }
 
 #[derive(Debug, Serialize)]
@@ -392,6 +515,9 @@ pub struct OsScreenshot {
     pub path: String,
     pub filename: String,
     pub modified: u64,
+    /// Tags, symbol, timeframe, notes and OCR text
+    #[serde(flatten)]
+    pub meta: library::ScreenshotMeta,
 }
 
 /// Open a native folder picker dialog and return the selected path
@@ -564,51 +690,30 @@ async fn get_app_icon(path: String) -> Result<Option<String>, String> {
 /// Get the default screenshots folder path
 #[tauri::command]
 async fn get_default_screenshots_folder() -> Result<String, String> {
-    let pictures = dirs::picture_dir().ok_or("Cannot find Pictures directory")?;
-    let ss_dir = pictures.join("Screenshots");
+    let ss_dir = screenshot::default_folder().map_err(|e| e.to_string())?;
     Ok(ss_dir.to_string_lossy().to_string())
 }
 
-/// List screenshots from the OS screenshots folder (or custom folder)
+/// List screenshots from the OS screenshots folder (or custom folder) and any
+/// extra library folders, recursively and newest first. Served from the
+/// watched library index rather than re-reading the disk.
 #[tauri::command]
-async fn list_os_screenshots(custom_folder: Option<String>) -> Result<Vec<OsScreenshot>, String> {
-    let ss_dir = if let Some(ref folder) = custom_folder {
-        if !folder.is_empty() {
-            std::path::PathBuf::from(folder)
-        } else {
-            let pictures = dirs::picture_dir().ok_or("Cannot find Pictures directory")?;
-            pictures.join("Screenshots")
-        }
-    } else {
-        let pictures = dirs::picture_dir().ok_or("Cannot find Pictures directory")?;
-        pictures.join("Screenshots")
-    };
-    if !ss_dir.exists() {
-        return Ok(vec![]);
-    }
-    let mut results = Vec::new();
-    let entries = std::fs::read_dir(&ss_dir).map_err(|e| e.to_string())?;
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let ext_lower = ext.to_lowercase();
-            if ext_lower == "png" || ext_lower == "jpg" || ext_lower == "jpeg" || ext_lower == "bmp" {
-                let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                let modified = entry.metadata()
-                    .and_then(|m| m.modified())
-                    .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs())
-                    .unwrap_or(0);
-                results.push(OsScreenshot {
-                    path: path.to_string_lossy().to_string(),
-                    filename,
-                    modified,
-                });
-            }
-        }
-    }
-    results.sort_by(|a, b| b.modified.cmp(&a.modified));
-    if results.len() > 50 { results.truncate(50); }
-    Ok(results)
+async fn list_os_screenshots(
+    state: State<'_, ScreenshotLibraryState>,
+    meta_state: State<'_, ScreenshotMetaState>,
+    custom_folder: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ScreenshotPage, String> {
+    let mut library = state.0.lock().unwrap();
+    sync_library_roots(&mut library, custom_folder.as_deref())?;
+    let page = library.page(offset.unwrap_or(0), limit, |_| true);
+    let index = meta_state.0.lock().unwrap();
+    Ok(ScreenshotPage {
+        total: page.total,
+        offset: page.offset,
+        entries: page.entries.into_iter().map(|e| to_os_screenshot(e, &index)).collect(),
+    })
 }
 
 /// Read a screenshot file and return as base64
@@ -618,31 +723,17 @@ async fn read_screenshot_file(path: String) -> Result<String, String> {
     Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data))
 }
 
-/// Read a screenshot and return a small thumbnail as base64 PNG
+/// Read a screenshot and return a small thumbnail as base64 PNG (cached on disk)
 #[tauri::command]
 async fn read_screenshot_thumbnail(path: String, max_width: u32) -> Result<String, String> {
-    let img = image::open(&path).map_err(|e| format!("Failed to open image: {}", e))?;
-    let thumb = img.thumbnail(max_width, max_width);
-    let mut buf = std::io::Cursor::new(Vec::new());
-    thumb.write_to(&mut buf, image::ImageFormat::Png)
-        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
-    Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, buf.into_inner()))
+    let png = library::thumbnail_png(std::path::Path::new(&path), max_width).map_err(|e| e.to_string())?;
+    Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, png))
 }
 
 /// Open the OS screenshots folder in file explorer
 #[tauri::command]
 async fn open_screenshots_folder(custom_folder: Option<String>) -> Result<(), String> {
-    let ss_dir = if let Some(ref folder) = custom_folder {
-        if !folder.is_empty() {
-            std::path::PathBuf::from(folder)
-        } else {
-            let pictures = dirs::picture_dir().ok_or("Cannot find Pictures directory")?;
-            pictures.join("Screenshots")
-        }
-    } else {
-        let pictures = dirs::picture_dir().ok_or("Cannot find Pictures directory")?;
-        pictures.join("Screenshots")
-    };
+    let ss_dir = screenshot::resolve_folder(custom_folder.as_deref()).map_err(|e| e.to_string())?;
     if !ss_dir.exists() {
         std::fs::create_dir_all(&ss_dir).map_err(|e| e.to_string())?;
     }
@@ -656,131 +747,34 @@ async fn open_screenshots_folder(custom_folder: Option<String>) -> Result<(), St
     Ok(())
 }
 
-/// Copy a screenshot image to the system clipboard (actual image, not text)
-#[tauri::command]
-#[allow(unused_variables)]
-async fn copy_screenshot_to_clipboard(path: String) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let img = image::open(&path).map_err(|e| format!("Failed to open image: {}", e))?;
-        let rgba = img.to_rgba8();
-        let (w, h) = (rgba.width(), rgba.height());
-
-        // Convert RGBA to BGRA for Windows DIB
-        let mut bgra_pixels: Vec<u8> = Vec::with_capacity((w * h * 4) as usize);
-        for pixel in rgba.pixels() {
-            bgra_pixels.push(pixel[2]); // B
-            bgra_pixels.push(pixel[1]); // G
-            bgra_pixels.push(pixel[0]); // R
-            bgra_pixels.push(pixel[3]); // A
-        }
-
-        // Windows DIB is bottom-up, flip rows
-        let row_bytes = (w * 4) as usize;
-        let mut flipped = vec![0u8; bgra_pixels.len()];
-        for y in 0..h as usize {
-            let src_start = y * row_bytes;
-            let dst_start = ((h as usize) - 1 - y) * row_bytes;
-            flipped[dst_start..dst_start + row_bytes]
-                .copy_from_slice(&bgra_pixels[src_start..src_start + row_bytes]);
-        }
-
-        type HWND = *mut std::ffi::c_void;
-        type HANDLE = *mut std::ffi::c_void;
-        type UINT = u32;
-        type BOOL = i32;
-
-        #[repr(C)]
-        #[allow(non_snake_case)]
-        struct BITMAPINFOHEADER {
-            biSize: u32,
-            biWidth: i32,
-            biHeight: i32,
-            biPlanes: u16,
-            biBitCount: u16,
-            biCompression: u32,
-            biSizeImage: u32,
-            biXPelsPerMeter: i32,
-            biYPelsPerMeter: i32,
-            biClrUsed: u32,
-            biClrImportant: u32,
-        }
-
-        const CF_DIB: UINT = 8;
-        const GMEM_MOVEABLE: UINT = 0x0002;
-
-        extern "system" {
-            fn OpenClipboard(hWnd: HWND) -> BOOL;
-            fn CloseClipboard() -> BOOL;
-            fn EmptyClipboard() -> BOOL;
-            fn SetClipboardData(uFormat: UINT, hMem: HANDLE) -> HANDLE;
-            fn GlobalAlloc(uFlags: UINT, dwBytes: usize) -> HANDLE;
-            fn GlobalLock(hMem: HANDLE) -> *mut u8;
-            fn GlobalUnlock(hMem: HANDLE) -> BOOL;
-        }
-
-        let header_size = std::mem::size_of::<BITMAPINFOHEADER>();
-        let data_size = flipped.len();
-        let total_size = header_size + data_size;
-
-        unsafe {
-            if OpenClipboard(std::ptr::null_mut()) == 0 {
-                return Err("Failed to open clipboard".into());
-            }
-            EmptyClipboard();
-
-            let hmem = GlobalAlloc(GMEM_MOVEABLE, total_size);
-            if hmem.is_null() {
-                CloseClipboard();
-                return Err("Failed to allocate global memory".into());
-            }
-
-            let ptr = GlobalLock(hmem);
-            if ptr.is_null() {
-                CloseClipboard();
-                return Err("Failed to lock global memory".into());
-            }
-
-            let header = BITMAPINFOHEADER {
-                biSize: header_size as u32,
-                biWidth: w as i32,
-                biHeight: h as i32, // positive = bottom-up
-                biPlanes: 1,
-                biBitCount: 32,
-                biCompression: 0, // BI_RGB
-                biSizeImage: data_size as u32,
-                biXPelsPerMeter: 0,
-                biYPelsPerMeter: 0,
-                biClrUsed: 0,
-                biClrImportant: 0,
-            };
-
-            std::ptr::copy_nonoverlapping(
-                &header as *const BITMAPINFOHEADER as *const u8,
-                ptr,
-                header_size,
-            );
-            std::ptr::copy_nonoverlapping(
-                flipped.as_ptr(),
-                ptr.add(header_size),
-                data_size,
-            );
-
-            GlobalUnlock(hmem);
+/// Put an RGBA image on the system clipboard
+fn write_clipboard_image(app: &tauri::AppHandle, img: image::RgbaImage) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
-            if SetClipboardData(CF_DIB, hmem).is_null() {
-                CloseClipboard();
-                return Err("Failed to set clipboard data".into());
-            }
+    let (width, height) = img.dimensions();
+    let clip = tauri::image::Image::new_owned(img.into_raw(), width, height);
+    app.clipboard().write_image(&clip).map_err(|e| e.to_string())
+}
 
-            CloseClipboard();
-        }
+/// Copy a screenshot image to the system clipboard (actual image, not text)
+#[tauri::command]
+async fn copy_screenshot_to_clipboard(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let img = clipboard_image::load_for_clipboard(&path).map_err(|e| e.to_string())?;
+    write_clipboard_image(&app, img)
+}
 
-        Ok(())
-    }
+/// Read an image from the system clipboard as base64 PNG, e.g. a chart
+/// pasted into the analyzer. Returns None if the clipboard holds no image.
+#[tauri::command]
+async fn paste_clipboard_image(app: tauri::AppHandle) -> Result<Option<CaptureResult>, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
-    #[cfg(not(target_os = "windows"))]
-    Err("copy_screenshot_to_clipboard is only supported on Windows".to_string())
+    let Ok(clip) = app.clipboard().read_image() else {
+        return Ok(None);
+    };
+    let (image_base64, width, height) =
+        clipboard_image::encode_pasted(clip.rgba().to_vec(), clip.width(), clip.height()).map_err(|e| e.to_string())?;
+    Ok(Some(CaptureResult { image_base64, width, height }))
 }
 
 #[tauri::command]
@@ -803,6 +797,10 @@ struct RegionState(Mutex<Option<[i32; 4]>>);
 // Global state for picked color
 struct PickedColorState(Mutex<Option<Option<String>>>);
 
+// Screens captured once when the color picker overlay opens, so the loupe
+// and the final pick read the same frame without re-capturing per mouse move
+struct ColorPickerCaptureState(Mutex<Option<Vec<capture::ScreenCapture>>>);
+
 // Global state for screenshot preview path
 struct ScreenshotPreviewState(Mutex<Option<String>>);
 
@@ -857,12 +855,19 @@ async fn get_selected_region(state: State<'_, RegionState>) -> Result<Option<[i3
 
 /// Open transparent color picker overlay window spanning ALL monitors
 #[tauri::command]
-async fn open_color_picker_overlay(app: tauri::AppHandle, state: State<'_, PickedColorState>) -> Result<(), String> {
+async fn open_color_picker_overlay(
+    app: tauri::AppHandle,
+    state: State<'_, PickedColorState>,
+    capture_state: State<'_, ColorPickerCaptureState>,
+) -> Result<(), String> {
     use tauri::{WebviewUrl, WebviewWindowBuilder};
 
     // Clear any stale result from a previous pick
     *state.0.lock().unwrap() = None;
 
+    // Capture before the overlay exists so it never appears in the loupe
+    *capture_state.0.lock().unwrap() = capture::capture_all_screens().ok();
+
     // Compute bounding box of the entire virtual desktop (all monitors)
     let monitors = app.available_monitors().map_err(|e| e.to_string())?;
     let (mut min_x, mut min_y, mut max_x, mut max_y) = (i32::MAX, i32::MAX, i32::MIN, i32::MIN);
@@ -901,7 +906,19 @@ async fn open_color_picker_overlay(app: tauri::AppHandle, state: State<'_, Picke
         .transparent(true)
         .visible(false)
         .build()
-        .map_err(|e| format!("Failed to build window: {}", e))?;
+        .map_err(|e| {
+            *capture_state.0.lock().unwrap() = None;
+            format!("Failed to build window: {}", e)
+        })?;
+
+    // The captures hold every monitor at full resolution; drop them however
+    // the overlay goes away, not only through `set_picked_color`
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if matches!(event, tauri::WindowEvent::Destroyed) {
+            *handle.state::<ColorPickerCaptureState>().0.lock().unwrap() = None;
+        }
+    });
 
     // Strip WS_THICKFRAME and WS_CAPTION so the DWM invisible border is
     // removed, then use SetWindowPos to place the window at exact physical
@@ -971,9 +988,11 @@ async fn open_color_picker_overlay(app: tauri::AppHandle, state: State<'_, Picke
 async fn set_picked_color(
     app: tauri::AppHandle,
     state: State<'_, PickedColorState>,
+    capture_state: State<'_, ColorPickerCaptureState>,
     color: Option<String>,
 ) -> Result<(), String> {
     *state.0.lock().unwrap() = Some(color);
+    *capture_state.0.lock().unwrap() = None;
 
     if let Some(window) = app.get_webview_window("color-picker-overlay") {
         window.close().map_err(|e| e.to_string())?;
@@ -1044,78 +1063,45 @@ async fn close_screenshot_preview(app: tauri::AppHandle) -> Result<(), String> {
 
 /// Create a second window on the right edge for dual mode
 #[tauri::command]
-async fn create_dual_window(app: tauri::AppHandle, monitor_index: Option<usize>) -> Result<(), String> {
-    use tauri::{WebviewUrl, WebviewWindowBuilder, PhysicalSize};
-
+async fn create_dual_window(app: tauri::AppHandle, monitor_id: Option<String>) -> Result<(), String> {
     // Close existing dual window if any
-    if let Some(existing) = app.get_webview_window("dual-right") {
+    if let Some(existing) = app.get_webview_window(hud_windows::DUAL_LABEL) {
         let _ = existing.close();
         std::thread::sleep(std::time::Duration::from_millis(100));
     }
-
-    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
-    let monitor = if let Some(idx) = monitor_index {
-        monitors.into_iter().nth(idx).ok_or("Monitor index out of range")?
-    } else {
-        app.primary_monitor()
-            .map_err(|e| e.to_string())?
-            .ok_or("No primary monitor found")?
-    };
-
-    let scale_factor = monitor.scale_factor();
-    let screen_width = monitor.size().width as i32;
-    let window_height = get_work_area_height(&monitor);
-    let window_width = (TOTAL_WIDTH as f64 * scale_factor) as u32;
-
-    let window = WebviewWindowBuilder::new(&app, "dual-right", WebviewUrl::App("/".into()))
-        .title("QuantHUD Right")
-        .inner_size(TOTAL_WIDTH as f64, 900.0)
-        .resizable(false)
-        .decorations(false)
-        .always_on_top(true)
-        .skip_taskbar(true)
-        .visible(false)
-        .transparent(true)
-        .shadow(false)
-        .disable_drag_drop_handler()
-        .build()
-        .map_err(|e| format!("Failed to create dual window: {}", e))?;
-
-    // Size to full height
-    window.set_size(PhysicalSize::new(window_width, window_height as u32))
-        .map_err(|e| e.to_string())?;
-
-    // Position at right edge
-    let monitor_x = monitor.position().x;
-    let monitor_y = monitor.position().y;
-    let x = screen_width - (TRIGGER_WIDTH as f64 * scale_factor) as i32;
-    window.set_position(PhysicalPosition::new(monitor_x + x, monitor_y))
-        .map_err(|e| e.to_string())?;
-
-    // Show after positioning
-    let window_clone = window.clone();
-    std::thread::spawn(move || {
-        std::thread::sleep(std::time::Duration::from_millis(50));
-        let _ = window_clone.show();
-    });
-
+    build_hud_window(&app, hud_windows::DUAL_LABEL, "QuantHUD Right", Some(layout::Edge::Right), monitor_id.as_deref())?;
     Ok(())
 }
 
 /// Close the dual-right window if it exists
 #[tauri::command]
 async fn close_dual_window(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("dual-right") {
+    app.state::<WindowLayoutState>().0.lock().unwrap().remove(hud_windows::DUAL_LABEL);
+    if let Some(window) = app.get_webview_window(hud_windows::DUAL_LABEL) {
         window.close().map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
-/// Show a native Win32 notification popup with OK button and chime
+/// Show a native Win32 notification popup with OK button and chime.
+/// Windows only: elsewhere this does nothing, so callers that need the
+/// message seen everywhere should also surface it in the HUD.
 #[tauri::command]
-async fn show_notification_popup(message: String) -> Result<(), String> {
+async fn show_notification_popup(app: tauri::AppHandle, message: String) -> Result<(), String> {
+    if !fullscreen_suppressed(&app) {
+        spawn_notification_popup(message, true);
+    }
+    Ok(())
+}
+
+/// Show the notification popup on its own thread (Windows only; a no-op
+/// elsewhere, where price alerts still reach the HUD as `price-alert`).
+/// `chime` plays the system notification sound when the popup appears.
+fn spawn_notification_popup(message: String, chime: bool) {
+    #[cfg(not(target_os = "windows"))]
+    let _ = (message, chime);
+    #[cfg(target_os = "windows")]
     std::thread::spawn(move || {
-        #[cfg(target_os = "windows")]
         unsafe {
             type HANDLE = *mut std::ffi::c_void;
             type HWND = *mut std::ffi::c_void;
@@ -1361,7 +1347,9 @@ async fn show_notification_popup(message: String) -> Result<(), String> {
             );
             ShowWindow(hwnd, 5);
             UpdateWindow(hwnd);
-            MessageBeep(0x40); // subtle chime
+            if chime {
+                MessageBeep(0x40); // subtle chime
+            }
             let mut msg: MSG = std::mem::zeroed();
             while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
                 TranslateMessage(&msg);
@@ -1369,9 +1357,920 @@ async fn show_notification_popup(message: String) -> Result<(), String> {
             }
         }
     });
+}
+
+// ── Window Animation ──
+
+const ANIMATION_FRAME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+
+struct WindowAnimation {
+    animation: animation::Animation,
+    move_first: bool,
+}
+
+struct AnimationState {
+    config: Mutex<animation::AnimationConfig>,
+    /// Running slides by window label
+    active: Mutex<std::collections::HashMap<String, WindowAnimation>>,
+    /// Wakes the driver when a slide starts
+    wake: std::sync::Condvar,
+}
+
+/// Current bounds in the same terms `apply_window_rect` sets them
+fn window_rect(window: &WebviewWindow) -> Result<layout::Rect, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    Ok(layout::Rect { x: position.x, y: position.y, width: size.width as i32, height: size.height as i32 })
+}
+
+/// Start sliding a window to `target`. A slide already running for the
+/// window is redirected from wherever it currently is; with reduced motion
+/// the window jumps straight there.
+fn animate_window(window: &WebviewWindow, target: layout::Rect, move_first: bool) -> Result<(), String> {
+    let state = window.state::<AnimationState>();
+    let config = state.config.lock().unwrap().clone();
+    let now = std::time::Instant::now();
+    let mut active = state.active.lock().unwrap();
+    let animation = match active.get(window.label()) {
+        Some(running) if running.animation.to == target => return Ok(()),
+        Some(running) => running.animation.retarget(target, &config, now),
+        None => animation::Animation::new(window_rect(window)?, target, &config, now),
+    };
+    if animation.is_finished(now) {
+        active.remove(window.label());
+        drop(active);
+        return apply_window_rect(window, target, move_first);
+    }
+    active.insert(window.label().to_string(), WindowAnimation { animation, move_first });
+    state.wake.notify_one();
+    Ok(())
+}
+
+/// Stop a window's slide where it is, e.g. before jumping to a new placement.
+/// Frames are applied under the same lock, so once this returns no frame of
+/// the old slide can land after the caller's own move.
+fn cancel_animation(window: &WebviewWindow) {
+    window.state::<AnimationState>().active.lock().unwrap().remove(window.label());
+}
+
+/// Step every running slide once per frame; sleeps while nothing is moving
+fn spawn_animation_driver(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<AnimationState>();
+        {
+            let mut active = state.wake.wait_while(state.active.lock().unwrap(), |a| a.is_empty()).unwrap();
+            let now = std::time::Instant::now();
+            // Window setters only queue the change, so holding the lock here is
+            // cheap and keeps `cancel_animation` from racing a stale frame
+            for (label, a) in active.iter() {
+                if let Some(window) = app.get_webview_window(label) {
+                    let _ = apply_window_rect(&window, a.animation.frame(now), a.move_first);
+                }
+            }
+            active.retain(|_, a| !a.animation.is_finished(now));
+        }
+        std::thread::sleep(ANIMATION_FRAME_INTERVAL);
+    });
+}
+
+#[tauri::command]
+async fn get_animation_config(state: State<'_, AnimationState>) -> Result<animation::AnimationConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+async fn set_animation_config(
+    state: State<'_, AnimationState>,
+    config: animation::AnimationConfig,
+) -> Result<(), String> {
+    animation::save_config(&config).map_err(|e| e.to_string())?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// ── HUD Windows ──
+
+struct HudWindowsState(Mutex<hud_windows::HudWindows>);
+
+/// Build a HUD window on the `/` route, tucked against `edge` until its page
+/// loads and applies its own placement
+fn build_hud_window(
+    app: &tauri::AppHandle,
+    label: &str,
+    title: &str,
+    edge: Option<layout::Edge>,
+    monitor_id: Option<&str>,
+) -> Result<WebviewWindow, String> {
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+    let monitor = find_monitor(app, monitor_id)?;
+    let width = app.state::<HudSizesState>().0.lock().unwrap().width(label);
+    let metrics = layout::HudMetrics::default().with_width(Some(width));
+
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App("/".into()))
+        .title(title)
+        .inner_size(metrics.thickness(layout::Edge::Left), 900.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .transparent(true)
+        .shadow(false)
+        .disable_drag_drop_handler()
+        .build()
+        .map_err(|e| format!("Failed to create HUD window: {}", e))?;
+
+    if let Some(edge) = edge {
+        let monitor = monitor_geometry(app, &monitor);
+        let rect = layout::tucked_rect(edge, &monitor, &metrics, layout::TriggerStyle::Column);
+        // A slide left over from a closed window with the same label
+        cancel_animation(&window);
+        apply_window_rect(&window, rect, false)?;
+    }
+
+    // Show after positioning
+    let window_clone = window.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let _ = window_clone.show();
+    });
+
+    Ok(window)
+}
+
+fn build_configured_hud_window(app: &tauri::AppHandle, config: &hud_windows::HudWindowConfig) -> Result<WebviewWindow, String> {
+    build_hud_window(app, &config.label, "QuantHUD", config.placement.edge(false), config.monitor_id.as_deref())
+}
+
+/// Recreate the additional HUD windows from the previous session
+fn restore_hud_windows(app: &tauri::AppHandle) {
+    let configs = app.state::<HudWindowsState>().0.lock().unwrap().windows.clone();
+    for config in configs {
+        if let Err(e) = build_configured_hud_window(app, &config) {
+            eprintln!("Failed to restore HUD window {}: {}", config.label, e);
+        }
+    }
+}
+
+/// Every open HUD window: main, the dual-mode window and any additional ones
+fn open_hud_windows(app: &tauri::AppHandle) -> Vec<WebviewWindow> {
+    let state = app.state::<HudWindowsState>();
+    let windows = state.0.lock().unwrap();
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| windows.is_hud(label))
+        .map(|(_, window)| window)
+        .collect()
+}
+
+fn emit_hud_windows(app: &tauri::AppHandle) {
+    let list = app.state::<HudWindowsState>().0.lock().unwrap().windows.clone();
+    let _ = app.emit("hud-windows-changed", &list);
+}
+
+/// Additional HUD windows (the main and dual-mode windows aren't listed)
+#[tauri::command]
+async fn list_hud_windows(state: tauri::State<'_, HudWindowsState>) -> Result<Vec<hud_windows::HudWindowConfig>, String> {
+    Ok(state.0.lock().unwrap().windows.clone())
+}
+
+/// Settings of the calling window; None for the main and dual-mode windows
+#[tauri::command]
+async fn get_hud_window(window: WebviewWindow) -> Result<Option<hud_windows::HudWindowConfig>, String> {
+    let state = window.state::<HudWindowsState>();
+    let windows = state.0.lock().unwrap();
+    Ok(windows.get(window.label()).cloned())
+}
+
+/// Open a new HUD window and remember it for the next start
+#[tauri::command]
+async fn create_hud_window(
+    app: tauri::AppHandle,
+    placement: layout::HudPlacement,
+    monitor_id: Option<String>,
+    modules: Option<Vec<String>>,
+    width: Option<f64>,
+) -> Result<hud_windows::HudWindowConfig, String> {
+    // Listed before the build so the new page finds its config, but only saved
+    // once the window exists, so a failed build isn't restored on every start
+    let config = app.state::<HudWindowsState>().0.lock().unwrap().add(placement, monitor_id, modules.unwrap_or_default());
+    if let Some(width) = width {
+        app.state::<HudSizesState>().0.lock().unwrap().set_width(&config.label, width);
+    }
+    if let Err(e) = build_configured_hud_window(&app, &config) {
+        app.state::<HudWindowsState>().0.lock().unwrap().remove(&config.label);
+        app.state::<HudSizesState>().0.lock().unwrap().remove(&config.label);
+        return Err(e);
+    }
+    app.state::<HudWindowsState>().0.lock().unwrap().save().map_err(|e| e.to_string())?;
+    if width.is_some() {
+        app.state::<HudSizesState>().0.lock().unwrap().save().map_err(|e| e.to_string())?;
+    }
+    emit_hud_windows(&app);
+    Ok(config)
+}
+
+/// Save a window's placement, monitor and modules. The window's page picks
+/// the change up from `hud-windows-changed` and re-applies its placement.
+#[tauri::command]
+async fn update_hud_window(app: tauri::AppHandle, config: hud_windows::HudWindowConfig) -> Result<(), String> {
+    {
+        let state = app.state::<HudWindowsState>();
+        let mut windows = state.0.lock().unwrap();
+        windows.update(config).map_err(|e| e.to_string())?;
+        windows.save().map_err(|e| e.to_string())?;
+    }
+    emit_hud_windows(&app);
+    Ok(())
+}
+
+/// Close a HUD window and forget it
+#[tauri::command]
+async fn close_hud_window(app: tauri::AppHandle, label: String) -> Result<(), String> {
+    {
+        let state = app.state::<HudWindowsState>();
+        let mut windows = state.0.lock().unwrap();
+        windows.remove(&label).ok_or_else(|| hud_windows::HudWindowError::NotFound(label.clone()).to_string())?;
+        windows.save().map_err(|e| e.to_string())?;
+    }
+    {
+        let state = app.state::<HudSizesState>();
+        let mut sizes = state.0.lock().unwrap();
+        sizes.remove(&label);
+        let _ = sizes.save();
+    }
+    app.state::<WindowLayoutState>().0.lock().unwrap().remove(&label);
+    if let Some(window) = app.get_webview_window(&label) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    emit_hud_windows(&app);
+    Ok(())
+}
+
+// ── Floating Modules ──
+
+/// Delay after the last move/resize before floating window bounds are saved
+const FLOATING_SAVE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+struct FloatingModulesState {
+    modules: Mutex<floating::FloatingModules>,
+    /// Wakes the saver thread on every move/resize
+    saves: Mutex<std::sync::mpsc::Sender<()>>,
+}
+
+fn emit_floating_modules(app: &tauri::AppHandle) {
+    let modules = app.state::<FloatingModulesState>().modules.lock().unwrap().modules.clone();
+    let _ = app.emit("floating-modules-changed", &modules);
+}
+
+/// Record a floating window's bounds after it was moved or resized and save
+/// them once dragging has settled
+fn record_floating_bounds(window: &WebviewWindow, module_id: &str) {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    let bounds = layout::Rect { x: position.x, y: position.y, width: size.width as i32, height: size.height as i32 };
+    // Minimizing reports a zero size; keep the last real bounds
+    if bounds.is_empty() {
+        return;
+    }
+
+    let state = window.state::<FloatingModulesState>();
+    state.modules.lock().unwrap().entry(module_id).bounds = Some(bounds);
+    let _ = state.saves.lock().unwrap().send(());
+}
+
+/// Save floating module bounds once no move/resize has arrived for
+/// `FLOATING_SAVE_DELAY`
+fn spawn_floating_saver(app: tauri::AppHandle, saves: std::sync::mpsc::Receiver<()>) {
+    std::thread::spawn(move || {
+        while saves.recv().is_ok() {
+            while saves.recv_timeout(FLOATING_SAVE_DELAY).is_ok() {}
+            let _ = app.state::<FloatingModulesState>().modules.lock().unwrap().save();
+        }
+    });
+}
+
+/// Build an always-on-top window showing a single module on `/module/<id>`,
+/// at its saved bounds or centered on the primary monitor
+fn build_floating_window(app: &tauri::AppHandle, module_id: &str, module: &floating::FloatingModule) -> Result<WebviewWindow, String> {
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+    let monitors: Vec<layout::Rect> = app
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(monitor_bounds)
+        .collect();
+    let bounds = floating::visible_bounds(module.bounds, &monitors);
+
+    let window = WebviewWindowBuilder::new(app, floating::label(module_id), WebviewUrl::App(format!("/module/{}", module_id).into()))
+        .title("QuantHUD")
+        .inner_size(floating::DEFAULT_WIDTH, floating::DEFAULT_HEIGHT)
+        .min_inner_size(floating::MIN_WIDTH, floating::MIN_HEIGHT)
+        .center()
+        .resizable(true)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .transparent(true)
+        .shadow(false)
+        .build()
+        .map_err(|e| format!("Failed to create floating window: {}", e))?;
+
+    if let Some(bounds) = bounds {
+        apply_window_rect(&window, bounds, true)?;
+    }
+    if module.click_through {
+        window.set_ignore_cursor_events(true).map_err(|e| e.to_string())?;
+    }
+
+    let window_clone = window.clone();
+    let id = module_id.to_string();
+    window.on_window_event(move |event| {
+        if matches!(event, tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)) {
+            record_floating_bounds(&window_clone, &id);
+        }
+    });
+
+    // Show after positioning
+    let window_clone = window.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let _ = window_clone.show();
+    });
+
+    Ok(window)
+}
+
+/// Reopen the floating modules that were open when the app last quit
+fn restore_floating_modules(app: &tauri::AppHandle) {
+    let modules = app.state::<FloatingModulesState>().modules.lock().unwrap().clone();
+    for id in modules.open_ids() {
+        // The ids come from a file on disk and end up in labels and URLs
+        if let Err(e) = floating::validate_id(&id) {
+            eprintln!("Not restoring floating module: {}", e);
+            continue;
+        }
+        if let Err(e) = build_floating_window(app, &id, &modules.get(&id)) {
+            eprintln!("Failed to restore floating module {}: {}", id, e);
+        }
+    }
+}
+
+/// Floating window state of every module that was ever popped out
+#[tauri::command]
+async fn list_floating_modules(state: State<'_, FloatingModulesState>) -> Result<std::collections::HashMap<String, floating::FloatingModule>, String> {
+    Ok(state.modules.lock().unwrap().modules.clone())
+}
+
+/// Floating window state of a module
+#[tauri::command]
+async fn get_floating_module(state: State<'_, FloatingModulesState>, module_id: String) -> Result<floating::FloatingModule, String> {
+    Ok(state.modules.lock().unwrap().get(&module_id))
+}
+
+/// Pop a module out into its own floating window, or focus it if it's already open
+#[tauri::command]
+async fn open_floating_module(app: tauri::AppHandle, module_id: String) -> Result<floating::FloatingModule, String> {
+    floating::validate_id(&module_id).map_err(|e| e.to_string())?;
+    if let Some(window) = app.get_webview_window(&floating::label(&module_id)) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(app.state::<FloatingModulesState>().modules.lock().unwrap().get(&module_id));
+    }
+
+    let module = {
+        let state = app.state::<FloatingModulesState>();
+        let mut modules = state.modules.lock().unwrap();
+        modules.entry(&module_id).open = true;
+        modules.save().map_err(|e| e.to_string())?;
+        modules.get(&module_id)
+    };
+    build_floating_window(&app, &module_id, &module)?;
+    emit_floating_modules(&app);
+    Ok(module)
+}
+
+/// Close a module's floating window; its bounds and options are kept
+#[tauri::command]
+async fn close_floating_module(app: tauri::AppHandle, module_id: String) -> Result<(), String> {
+    floating::validate_id(&module_id).map_err(|e| e.to_string())?;
+    {
+        let state = app.state::<FloatingModulesState>();
+        let mut modules = state.modules.lock().unwrap();
+        modules.entry(&module_id).open = false;
+        modules.save().map_err(|e| e.to_string())?;
+    }
+    if let Some(window) = app.get_webview_window(&floating::label(&module_id)) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    emit_floating_modules(&app);
+    Ok(())
+}
+
+/// Let mouse input pass through a floating window. While enabled the window
+/// can't be clicked, so this is turned off again from the HUD settings.
+#[tauri::command]
+async fn set_floating_click_through(app: tauri::AppHandle, module_id: String, enabled: bool) -> Result<(), String> {
+    floating::validate_id(&module_id).map_err(|e| e.to_string())?;
+    {
+        let state = app.state::<FloatingModulesState>();
+        let mut modules = state.modules.lock().unwrap();
+        modules.entry(&module_id).click_through = enabled;
+        modules.save().map_err(|e| e.to_string())?;
+    }
+    if let Some(window) = app.get_webview_window(&floating::label(&module_id)) {
+        window.set_ignore_cursor_events(enabled).map_err(|e| e.to_string())?;
+    }
+    emit_floating_modules(&app);
+    Ok(())
+}
+
+/// Set a floating window's opacity, clamped to 0.2–1.0. Returns the stored value.
+#[tauri::command]
+async fn set_floating_opacity(app: tauri::AppHandle, module_id: String, opacity: f64) -> Result<f64, String> {
+    floating::validate_id(&module_id).map_err(|e| e.to_string())?;
+    let opacity = floating::clamp_opacity(opacity);
+    {
+        let state = app.state::<FloatingModulesState>();
+        let mut modules = state.modules.lock().unwrap();
+        modules.entry(&module_id).opacity = opacity;
+        modules.save().map_err(|e| e.to_string())?;
+    }
+    emit_floating_modules(&app);
+    Ok(opacity)
+}
+
+// ── Edge Activation ──
+
+const EDGE_HOVER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(30);
+const EDGE_HOVER_IDLE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+struct EdgeHoverWindow {
+    tracker: edge_hover::HoverTracker,
+    pinned: bool,
+}
+
+struct EdgeHoverState {
+    config: Mutex<edge_hover::EdgeHoverConfig>,
+    windows: Mutex<std::collections::HashMap<String, EdgeHoverWindow>>,
+}
+
+fn edge_hover_snapshot(window: &WebviewWindow, pinned: bool) -> Option<edge_hover::WindowSnapshot> {
+    if !window.is_visible().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    Some(edge_hover::WindowSnapshot {
+        bounds: layout::Rect { x: position.x, y: position.y, width: size.width as i32, height: size.height as i32 },
+        tucked: window_is_tucked(window).ok()?,
+        pinned,
+        scale_factor: window.scale_factor().ok()?,
+    })
+}
+
+/// Track the cursor for every HUD window in hover mode and emit `hud-reveal`
+/// / `hud-hide` to that window once the dwell or hide delay has elapsed
+fn spawn_edge_hover_service(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        loop {
+            let state = app.state::<EdgeHoverState>();
+            if state.windows.lock().unwrap().is_empty() {
+                std::thread::sleep(EDGE_HOVER_IDLE_INTERVAL);
+                continue;
+            }
+            std::thread::sleep(EDGE_HOVER_POLL_INTERVAL);
+
+            let pointer = app.state::<PointerReaderState>().0.lock().unwrap().read();
+            let pointer = pointer.or_else(|| {
+                let position = app.cursor_position().ok()?;
+                Some(edge_hover::PointerState { x: position.x as i32, y: position.y as i32, buttons_down: false })
+            });
+            let Some(pointer) = pointer else { continue };
+            let config = state.config.lock().unwrap().clone();
+            let now = std::time::Instant::now();
+
+            let mut windows = state.windows.lock().unwrap();
+            windows.retain(|label, _| app.get_webview_window(label).is_some());
+            for (label, hover) in windows.iter_mut() {
+                let Some(window) = app.get_webview_window(label) else { continue };
+                let Some(snapshot) = edge_hover_snapshot(&window, hover.pinned) else { continue };
+                if let Some(event) = hover.tracker.update(&config, pointer, &snapshot, now) {
+                    let _ = app.emit_to(label.as_str(), event.event_name(), ());
+                }
+            }
+        }
+    });
+}
+
+/// Register or unregister the calling window with the edge-hover service.
+/// Windows in click mode unregister; pinned windows are never hidden.
+#[tauri::command]
+async fn set_edge_hover_enabled(
+    window: WebviewWindow,
+    state: State<'_, EdgeHoverState>,
+    enabled: bool,
+    pinned: Option<bool>,
+) -> Result<(), String> {
+    let mut windows = state.windows.lock().unwrap();
+    let label = window.label().to_string();
+    if !enabled {
+        windows.remove(&label);
+        return Ok(());
+    }
+    let entry = windows
+        .entry(label)
+        .or_insert_with(|| EdgeHoverWindow { tracker: edge_hover::HoverTracker::default(), pinned: false });
+    entry.pinned = pinned.unwrap_or(false);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_edge_hover_config(state: State<'_, EdgeHoverState>) -> Result<edge_hover::EdgeHoverConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+async fn set_edge_hover_config(
+    state: State<'_, EdgeHoverState>,
+    config: edge_hover::EdgeHoverConfig,
+) -> Result<(), String> {
+    edge_hover::save_config(&config).map_err(|e| e.to_string())?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+// ── Fullscreen Awareness ──
+
+const FULLSCREEN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+struct FullscreenState {
+    config: Mutex<fullscreen::FullscreenConfig>,
+    active: Mutex<Option<fullscreen::Suppression>>,
+    /// Tucked HUD windows hidden during the current suppression
+    hidden: Mutex<Vec<String>>,
+}
+
+/// Whether edge triggers and popups are held back for a fullscreen app
+fn fullscreen_suppressed(app: &tauri::AppHandle) -> bool {
+    app.state::<FullscreenState>().active.lock().unwrap().is_some()
+}
+
+/// Bounds of the monitor a HUD window is placed on
+fn hud_monitor_bounds(window: &WebviewWindow) -> Option<layout::Rect> {
+    let monitor_id = window.state::<WindowLayoutState>().0.lock().unwrap().get(window.label()).and_then(|p| p.monitor_id.clone());
+    resolve_monitor(window, monitor_id.as_deref()).ok().map(|m| monitor_bounds(&m))
+}
+
+/// Watch the foreground window. While a fullscreen or listed app is in front,
+/// hide tucked HUD windows on the affected monitor (so their edge triggers
+/// can't fire) and hold back notification popups; emit `fullscreen-changed`
+/// on every transition. Windows the user brings back during suppression
+/// aren't hidden again.
+fn spawn_fullscreen_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut detector = fullscreen::FullscreenDetector::new(fullscreen::SystemProvider::new());
+        loop {
+            std::thread::sleep(FULLSCREEN_POLL_INTERVAL);
+            let state = app.state::<FullscreenState>();
+            let config = state.config.lock().unwrap().clone();
+            let monitors: Vec<layout::Rect> = app
+                .available_monitors()
+                .map(|monitors| monitors.iter().map(monitor_bounds).collect())
+                .unwrap_or_default();
+            let suppression = detector.poll(&monitors, &config);
+
+            let changed = {
+                let mut active = state.active.lock().unwrap();
+                let changed = *active != suppression;
+                *active = suppression.clone();
+                changed
+            };
+
+            let affected = |window: &WebviewWindow| {
+                suppression.as_ref().is_some_and(|s| hud_monitor_bounds(window).is_none_or(|m| s.affects(&m)))
+            };
+            let mut hidden = state.hidden.lock().unwrap();
+            // Bring back windows whose monitor is no longer affected, e.g. the
+            // fullscreen app moved to another monitor or closed
+            hidden.retain(|label| {
+                let Some(window) = app.get_webview_window(label) else { return false };
+                if affected(&window) {
+                    return true;
+                }
+                let _ = window.show();
+                false
+            });
+            if suppression.is_some() {
+                for window in open_hud_windows(&app) {
+                    let label = window.label().to_string();
+                    if hidden.contains(&label) || !window.is_visible().unwrap_or(false) || !affected(&window) {
+                        continue;
+                    }
+                    if window_is_tucked(&window).unwrap_or(false) {
+                        let _ = window.hide();
+                        hidden.push(label);
+                    }
+                }
+            }
+            drop(hidden);
+
+            if changed {
+                let _ = app.emit("fullscreen-changed", &suppression);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+async fn get_fullscreen_config(state: State<'_, FullscreenState>) -> Result<fullscreen::FullscreenConfig, String> {
+    Ok(state.config.lock().unwrap().clone())
+}
+
+#[tauri::command]
+async fn set_fullscreen_config(
+    state: State<'_, FullscreenState>,
+    config: fullscreen::FullscreenConfig,
+) -> Result<(), String> {
+    fullscreen::save_config(&config).map_err(|e| e.to_string())?;
+    *state.config.lock().unwrap() = config;
+    Ok(())
+}
+
+/// Current suppression, None while no fullscreen or listed app is in front
+#[tauri::command]
+async fn get_fullscreen_state(state: State<'_, FullscreenState>) -> Result<Option<fullscreen::Suppression>, String> {
+    Ok(state.active.lock().unwrap().clone())
+}
+
+// ── Price Alerts ──
+
+// Running OCR price watches, keyed by watch name
+struct PriceWatchState(Mutex<std::collections::HashMap<String, alerts::WatchHandle>>);
+
+/// Spawn a screen-OCR watch that emits `price-alert` / `price-watch-status`
+/// and, on Windows, shows the notification popup for every alert.
+fn spawn_price_watch(app: &tauri::AppHandle, config: alerts::PriceWatchConfig) -> alerts::WatchHandle {
+    let reader = alerts::TesseractReader::new(config.decimal_mark);
+    let alert_app = app.clone();
+    let status_app = app.clone();
+    alerts::spawn_watch(
+        config,
+        alerts::ScreenSource,
+        reader,
+        move |event, sound| {
+            let _ = alert_app.emit("price-alert", event);
+            if !fullscreen_suppressed(&alert_app) {
+                spawn_notification_popup(event.message(), sound);
+            }
+        },
+        move |status| {
+            let _ = status_app.emit("price-watch-status", status);
+        },
+    )
+}
+
+fn persist_price_watches(watches: &std::collections::HashMap<String, alerts::WatchHandle>) -> Result<(), String> {
+    let configs: Vec<alerts::PriceWatchConfig> = watches.values().map(|w| w.config()).collect();
+    alerts::save_watches(&configs).map_err(|e| e.to_string())
+}
+
+/// Start (or restart) watching a named capture region for level crossings
+#[tauri::command]
+async fn start_price_watch(
+    app: tauri::AppHandle,
+    state: State<'_, PriceWatchState>,
+    config: alerts::PriceWatchConfig,
+) -> Result<(), String> {
+    if config.name.trim().is_empty() {
+        return Err("Watch name is required".into());
+    }
+    alerts::check_tesseract().map_err(|e| e.to_string())?;
+    let mut watches = state.0.lock().unwrap();
+    if let Some(old) = watches.remove(&config.name) {
+        old.stop();
+    }
+    let name = config.name.clone();
+    watches.insert(name, spawn_price_watch(&app, config));
+    persist_price_watches(&watches)
+}
+
+#[tauri::command]
+async fn stop_price_watch(state: State<'_, PriceWatchState>, name: String) -> Result<(), String> {
+    let mut watches = state.0.lock().unwrap();
+    if let Some(watch) = watches.remove(&name) {
+        watch.stop();
+    }
+    persist_price_watches(&watches)
+}
+
+/// Replace the alert levels of a running watch (e.g. after a new fib capture)
+#[tauri::command]
+async fn update_price_watch_levels(
+    state: State<'_, PriceWatchState>,
+    name: String,
+    levels: Vec<alerts::AlertLevel>,
+) -> Result<(), String> {
+    let watches = state.0.lock().unwrap();
+    let watch = watches.get(&name).ok_or_else(|| format!("No price watch named '{}'", name))?;
+    watch.set_levels(levels);
+    persist_price_watches(&watches)
+}
+
+#[tauri::command]
+async fn list_price_watches(state: State<'_, PriceWatchState>) -> Result<Vec<alerts::WatchInfo>, String> {
+    let watches = state.0.lock().unwrap();
+    let mut list: Vec<_> = watches.values().map(|w| w.info()).collect();
+    list.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+    Ok(list)
+}
+
+// ── Market Data Feeds ──
+
+// Running market data feeds, keyed by feed name
+struct MarketFeedState(Mutex<std::collections::HashMap<String, market_data::FeedHandle>>);
+
+/// Start (or restart) a market data feed. Normalized events are emitted as
+/// `market-tick` / `market-candle`; errors as `market-feed-error`.
+#[tauri::command]
+async fn start_market_feed(
+    app: tauri::AppHandle,
+    state: State<'_, MarketFeedState>,
+    config: market_data::FeedConfig,
+) -> Result<(), String> {
+    if config.name.trim().is_empty() {
+        return Err("Feed name is required".into());
+    }
+    let source = market_data::create_source(&config).map_err(|e| e.to_string())?;
+
+    let mut feeds = state.0.lock().unwrap();
+    if let Some(old) = feeds.remove(&config.name) {
+        old.stop();
+    }
+
+    let event_app = app.clone();
+    let error_app = app.clone();
+    let feed_name = config.name.clone();
+    let handle = market_data::spawn_feed(
+        config.clone(),
+        source,
+        move |event| {
+            let name = match event {
+                market_data::MarketEvent::Tick(_) => "market-tick",
+                market_data::MarketEvent::Candle(_) => "market-candle",
+            };
+            let _ = event_app.emit(name, event);
+        },
+        move |error| {
+            let _ = error_app.emit("market-feed-error", serde_json::json!({ "feed": feed_name, "error": error }));
+        },
+    );
+    feeds.insert(config.name, handle);
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_market_feed(state: State<'_, MarketFeedState>, name: String) -> Result<(), String> {
+    if let Some(feed) = state.0.lock().unwrap().remove(&name) {
+        feed.stop();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_market_feeds(state: State<'_, MarketFeedState>) -> Result<Vec<market_data::FeedInfo>, String> {
+    let feeds = state.0.lock().unwrap();
+    let mut list: Vec<_> = feeds.values().map(|f| f.info()).collect();
+    list.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+    Ok(list)
+}
+
+// ── Live Positions ──
+
+const POSITION_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+// Open positions tracked for live PnL
+struct PositionState(Mutex<positions::PositionBook>);
+
+/// Current price for a position from its feed or price watch
+fn lookup_position_price(app: &tauri::AppHandle, source: &positions::PriceSource, symbol: &str) -> Option<f64> {
+    match source {
+        positions::PriceSource::Feed { feed, symbol: feed_symbol } => {
+            let state = app.state::<MarketFeedState>();
+            let feeds = state.0.lock().unwrap();
+            feeds.get(feed)?.last_price(feed_symbol.as_deref().unwrap_or(symbol))
+        }
+        positions::PriceSource::Watch { name } => {
+            let state = app.state::<PriceWatchState>();
+            let watches = state.0.lock().unwrap();
+            watches.get(name)?.status().last_price
+        }
+        positions::PriceSource::Manual => None,
+    }
+}
+
+fn emit_position_update(app: &tauri::AppHandle, book: &positions::PositionBook) {
+    let _ = app.emit("position-update", book.snapshots(config::unix_millis()));
+}
+
+/// Refresh prices and emit `position-update` to the HUD windows once a second
+/// while any position is open.
+fn spawn_position_tracker(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POSITION_UPDATE_INTERVAL);
+        let state = app.state::<PositionState>();
+        let mut book = state.0.lock().unwrap();
+        if book.is_empty() {
+            continue;
+        }
+        book.refresh_prices(|source, symbol| lookup_position_price(&app, source, symbol));
+        emit_position_update(&app, &book);
+    });
+}
+
+/// Run the calculator's sizing math (same results as useCalculator)
+#[tauri::command]
+async fn calculate_position_size(
+    inputs: sizing::SizingInputs,
+    is_long: bool,
+    entry: f64,
+    take_profit: f64,
+    stop_loss: f64,
+) -> Result<sizing::SizingResult, String> {
+    sizing::calculate(&inputs, is_long, entry, take_profit, stop_loss).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn open_position(
+    app: tauri::AppHandle,
+    state: State<'_, PositionState>,
+    request: positions::OpenPositionRequest,
+) -> Result<positions::PositionSnapshot, String> {
+    let mut book = state.0.lock().unwrap();
+    let now = config::unix_millis();
+    let id = book.open(request, now).map_err(|e| e.to_string())?.id.clone();
+    book.refresh_prices(|source, symbol| lookup_position_price(&app, source, symbol));
+    book.save().map_err(|e| e.to_string())?;
+    emit_position_update(&app, &book);
+    let snapshot = book.snapshots(now).into_iter().find(|s| s.id == id);
+    snapshot.ok_or_else(|| "Position was not opened".to_string())
+}
+
+/// Stop tracking a position; returns its final snapshot
+#[tauri::command]
+async fn close_position(
+    app: tauri::AppHandle,
+    state: State<'_, PositionState>,
+    id: String,
+) -> Result<positions::PositionSnapshot, String> {
+    let mut book = state.0.lock().unwrap();
+    let position = book.close(&id).map_err(|e| e.to_string())?;
+    book.save().map_err(|e| e.to_string())?;
+    emit_position_update(&app, &book);
+    Ok(position.snapshot(config::unix_millis()))
+}
+
+#[tauri::command]
+async fn update_position_levels(
+    app: tauri::AppHandle,
+    state: State<'_, PositionState>,
+    id: String,
+    stop_loss: Option<f64>,
+    targets: Option<Vec<f64>>,
+) -> Result<positions::PositionSnapshot, String> {
+    let mut book = state.0.lock().unwrap();
+    let snapshot = book
+        .update_levels(&id, stop_loss, targets)
+        .map_err(|e| e.to_string())?
+        .snapshot(config::unix_millis());
+    book.save().map_err(|e| e.to_string())?;
+    emit_position_update(&app, &book);
+    Ok(snapshot)
+}
+
+/// Push a price for a position with a manual price source
+#[tauri::command]
+async fn set_position_price(
+    app: tauri::AppHandle,
+    state: State<'_, PositionState>,
+    id: String,
+    price: f64,
+) -> Result<(), String> {
+    let mut book = state.0.lock().unwrap();
+    book.set_price(&id, price).map_err(|e| e.to_string())?;
+    emit_position_update(&app, &book);
     Ok(())
 }
 
+#[tauri::command]
+async fn list_positions(state: State<'_, PositionState>) -> Result<Vec<positions::PositionSnapshot>, String> {
+    Ok(state.0.lock().unwrap().snapshots(config::unix_millis()))
+}
+
 // ── Speech Recognition ──
 static SPEECH_ACTIVE: AtomicBool = AtomicBool::new(false);
 static SPEECH_THREAD_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
@@ -1940,6 +2839,630 @@ async fn analyze_chart(
     Ok(text)
 }
 
+// ── Screenshot Capture ──
+
+/// Capture a monitor, region or named region and save it into the screenshots
+/// folder. Emits `screenshot-taken` so the history list can refresh.
+#[tauri::command]
+async fn take_screenshot(
+    app: tauri::AppHandle,
+    options: screenshot::ScreenshotOptions,
+) -> Result<screenshot::SavedScreenshot, String> {
+    let (img, label) = screenshot::capture_target(&options.target).map_err(|e| e.to_string())?;
+    let folder = screenshot::resolve_folder(options.folder.as_deref()).map_err(|e| e.to_string())?;
+    let saved = screenshot::save_image(&img, &folder, &options, &label).map_err(|e| e.to_string())?;
+
+    if options.copy_to_clipboard {
+        let img = clipboard_image::prepare_for_clipboard(image::DynamicImage::ImageRgba8(img), clipboard_image::MAX_CLIPBOARD_DIMENSION);
+        write_clipboard_image(&app, img)?;
+    }
+
+    let _ = app.emit("screenshot-taken", &saved);
+    Ok(saved)
+}
+
+#[tauri::command]
+async fn list_capture_regions() -> Result<std::collections::BTreeMap<String, [i32; 4]>, String> {
+    screenshot::load_regions().map_err(|e| e.to_string())
+}
+
+/// Save a region (e.g. from the region selector) under a name for `take_screenshot`
+#[tauri::command]
+async fn save_capture_region(name: String, region: [i32; 4]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Region name is required".into());
+    }
+    screenshot::save_region(name.trim(), region).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_capture_region(name: String) -> Result<(), String> {
+    screenshot::delete_region(&name).map_err(|e| e.to_string())
+}
+
+// ── Screenshot Library ──
+
+const LIBRARY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+// Index of every image under the screenshots folder and extra library folders
+struct ScreenshotLibraryState(Mutex<library::ScreenshotLibrary>);
+
+/// Point the library at the screenshots folder plus the saved extra folders,
+/// rescanning right away if they changed
+fn sync_library_roots(library: &mut library::ScreenshotLibrary, custom_folder: Option<&str>) -> Result<(), String> {
+    let mut roots = vec![screenshot::resolve_folder(custom_folder).map_err(|e| e.to_string())?];
+    let settings = library::load_settings().map_err(|e| e.to_string())?;
+    roots.extend(settings.extra_folders.into_iter().filter(|f| !f.is_empty()).map(std::path::PathBuf::from));
+    if library.roots() != roots.as_slice() {
+        library.set_roots(roots);
+        library.scan();
+    }
+    Ok(())
+}
+
+/// Drop metadata of screenshots that were deleted. Files that only vanished
+/// because their drive or folder went away keep their tags for when it's back.
+fn forget_screenshot_meta(app: &tauri::AppHandle, removed: &[String], roots: &[std::path::PathBuf]) {
+    let state = app.state::<ScreenshotMetaState>();
+    let mut index = state.0.lock().unwrap();
+    let before = index.len();
+    for path in removed {
+        if library::is_deleted(std::path::Path::new(path), roots) {
+            index.remove(path);
+        }
+    }
+    if index.len() != before {
+        let _ = library::save_meta_index(&index);
+    }
+}
+
+/// Poll the library folders and emit `screenshot-added` / `screenshot-removed`
+/// with the changed entries
+fn spawn_library_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        {
+            let state = app.state::<ScreenshotLibraryState>();
+            let mut library = state.0.lock().unwrap();
+            let _ = sync_library_roots(&mut library, None);
+        }
+        library::prune_stale_thumbnails();
+        loop {
+            std::thread::sleep(LIBRARY_POLL_INTERVAL);
+            let (changes, roots) = {
+                let state = app.state::<ScreenshotLibraryState>();
+                let mut library = state.0.lock().unwrap();
+                (library.scan(), library.roots().to_vec())
+            };
+            library::prune_thumbnails(&changes.removed);
+            if !changes.added.is_empty() {
+                let _ = app.emit("screenshot-added", &changes.added);
+            }
+            if !changes.removed.is_empty() {
+                forget_screenshot_meta(&app, &changes.removed, &roots);
+                let _ = app.emit("screenshot-removed", &changes.removed);
+            }
+        }
+    });
+}
+
+// User metadata for screenshots, keyed by path
+struct ScreenshotMetaState(Mutex<library::MetaIndex>);
+
+fn to_os_screenshot(entry: library::LibraryEntry, index: &library::MetaIndex) -> OsScreenshot {
+    let meta = index.get(&entry.path).cloned().unwrap_or_default();
+    OsScreenshot { path: entry.path, filename: entry.filename, modified: entry.modified, meta }
+}
+
+/// One page of screenshots with their metadata
+#[derive(Debug, Serialize)]
+struct ScreenshotPage {
+    total: usize,
+    offset: usize,
+    entries: Vec<OsScreenshot>,
+}
+
+/// Page through the library, newest first, optionally within one folder
+#[tauri::command]
+async fn list_screenshot_library(
+    state: State<'_, ScreenshotLibraryState>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    folder: Option<String>,
+) -> Result<library::LibraryPage, String> {
+    let library = state.0.lock().unwrap();
+    let folder = folder.filter(|f| !f.is_empty()).map(std::path::PathBuf::from);
+    Ok(library.page(offset.unwrap_or(0), limit, |e| {
+        folder.as_ref().is_none_or(|f| std::path::Path::new(&e.path).starts_with(f))
+    }))
+}
+
+#[tauri::command]
+async fn get_screenshot_library_folders() -> Result<Vec<String>, String> {
+    Ok(library::load_settings().map_err(|e| e.to_string())?.extra_folders)
+}
+
+/// Set the folders indexed in addition to the screenshots folder
+#[tauri::command]
+async fn set_screenshot_library_folders(
+    state: State<'_, ScreenshotLibraryState>,
+    folders: Vec<String>,
+) -> Result<(), String> {
+    library::save_settings(&library::LibrarySettings { extra_folders: folders }).map_err(|e| e.to_string())?;
+    sync_library_roots(&mut state.0.lock().unwrap(), None)
+}
+
+#[tauri::command]
+async fn get_screenshot_meta(state: State<'_, ScreenshotMetaState>, path: String) -> Result<library::ScreenshotMeta, String> {
+    Ok(state.0.lock().unwrap().get(&path).cloned().unwrap_or_default())
+}
+
+/// Save tags, symbol, timeframe and notes for a screenshot. Existing OCR text
+/// is kept unless `meta` carries its own.
+#[tauri::command]
+async fn set_screenshot_meta(
+    state: State<'_, ScreenshotMetaState>,
+    path: String,
+    meta: library::ScreenshotMeta,
+) -> Result<library::ScreenshotMeta, String> {
+    let mut index = state.0.lock().unwrap();
+    let mut meta = meta;
+    if meta.ocr_text.is_none() {
+        meta.ocr_text = index.get(&path).and_then(|m| m.ocr_text.clone());
+    }
+    let meta = meta.normalized();
+    if meta.is_empty() {
+        index.remove(&path);
+    } else {
+        index.insert(path, meta.clone());
+    }
+    library::save_meta_index(&index).map_err(|e| e.to_string())?;
+    Ok(meta)
+}
+
+/// OCR a screenshot with Tesseract and store the text for search
+#[tauri::command]
+async fn ocr_screenshot(state: State<'_, ScreenshotMetaState>, path: String) -> Result<String, String> {
+    let text = alerts::tesseract_text(std::path::Path::new(&path), &["--psm", "3"]).map_err(|e| e.to_string())?;
+    let mut index = state.0.lock().unwrap();
+    let meta = index.get(&path).cloned().unwrap_or_default();
+    let meta = library::ScreenshotMeta { ocr_text: Some(text), ..meta }.normalized();
+    let text = meta.ocr_text.clone().unwrap_or_default();
+    index.insert(path, meta);
+    library::save_meta_index(&index).map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+/// Search the library by text, tags, symbol and timeframe, newest first
+#[tauri::command]
+async fn search_screenshots(
+    state: State<'_, ScreenshotLibraryState>,
+    meta_state: State<'_, ScreenshotMetaState>,
+    query: library::ScreenshotQuery,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ScreenshotPage, String> {
+    let library = state.0.lock().unwrap();
+    let index = meta_state.0.lock().unwrap();
+    let page = library.page(offset.unwrap_or(0), limit, |e| query.matches(e, index.get(&e.path)));
+    Ok(ScreenshotPage {
+        total: page.total,
+        offset: page.offset,
+        entries: page.entries.into_iter().map(|e| to_os_screenshot(e, &index)).collect(),
+    })
+}
+
+/// Every tag in use with how many screenshots carry it
+#[tauri::command]
+async fn list_screenshot_tags(state: State<'_, ScreenshotMetaState>) -> Result<std::collections::BTreeMap<String, usize>, String> {
+    let mut counts = std::collections::BTreeMap::new();
+    for meta in state.0.lock().unwrap().values() {
+        for tag in &meta.tags {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+// ── Screenshot Annotation ──
+
+/// Ops saved for a screenshot by a previous `annotate_screenshot`
+#[tauri::command]
+async fn load_screenshot_annotations(path: String) -> Result<Vec<annotate::AnnotationOp>, String> {
+    annotate::load_annotations(std::path::Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// Render markup onto a screenshot and save it as `<name>_annotated.png`
+/// (or `output`). The original stays untouched and the ops are kept in a
+/// sidecar file so the markup can be edited later.
+#[tauri::command]
+async fn annotate_screenshot(
+    app: tauri::AppHandle,
+    path: String,
+    ops: Vec<annotate::AnnotationOp>,
+    output: Option<String>,
+) -> Result<screenshot::SavedScreenshot, String> {
+    let (out_path, img) = annotate::annotate_file(std::path::Path::new(&path), ops, output.map(std::path::PathBuf::from))
+        .map_err(|e| e.to_string())?;
+    let saved = screenshot::SavedScreenshot {
+        path: out_path.to_string_lossy().to_string(),
+        filename: out_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        modified: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        width: img.width(),
+        height: img.height(),
+    };
+    let _ = app.emit("screenshot-taken", &saved);
+    Ok(saved)
+}
+
+// ── Clipboard History ──
+
+const CLIPBOARD_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+// Without an OS change counter, images are re-read (and decoded) at most this often
+const CLIPBOARD_IMAGE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+struct ClipboardHistoryState(Mutex<clipboard_history::ClipboardHistory>);
+
+fn emit_clipboard_history(app: &tauri::AppHandle, history: &clipboard_history::ClipboardHistory) {
+    let _ = app.emit("clipboard-history", history.entries());
+}
+
+/// Watch the clipboard and record changes. Emits `clipboard-added` with the
+/// new or re-copied entry and `clipboard-history` with the full list. New text
+/// that is a plain number gets the auto-apply transform preset, if one is set.
+fn spawn_clipboard_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut clipboard = match arboard::Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                eprintln!("Clipboard history disabled: {}", e);
+                return;
+            }
+        };
+        // Whatever is on the clipboard at startup is not a new copy
+        let mut last_seq = clipboard_history::change_count();
+        let mut last_hash = clipboard_history::read_clipboard(&mut clipboard, true).map(|c| c.hash());
+        let mut last_image_read = std::time::Instant::now();
+        let mut last_auto_output: Option<String> = None;
+
+        loop {
+            std::thread::sleep(CLIPBOARD_POLL_INTERVAL);
+            let seq = clipboard_history::change_count();
+            if seq.is_some() && seq == last_seq {
+                continue;
+            }
+            last_seq = seq;
+
+            let (enabled, capture_images) = {
+                let state = app.state::<ClipboardHistoryState>();
+                let history = state.0.lock().unwrap();
+                (history.settings().enabled, history.settings().capture_images)
+            };
+            if !enabled {
+                continue;
+            }
+            let read_images =
+                capture_images && (seq.is_some() || last_image_read.elapsed() >= CLIPBOARD_IMAGE_POLL_INTERVAL);
+            let Some(captured) = clipboard_history::read_clipboard(&mut clipboard, read_images) else {
+                continue;
+            };
+            if matches!(captured, clipboard_history::Captured::Image(_)) {
+                last_image_read = std::time::Instant::now();
+            }
+            let hash = captured.hash();
+            if last_hash.as_ref() == Some(&hash) {
+                continue;
+            }
+            last_hash = Some(hash);
+            let text = match &captured {
+                clipboard_history::Captured::Text(text) => Some(text.clone()),
+                _ => None,
+            };
+
+            {
+                let state = app.state::<ClipboardHistoryState>();
+                let mut history = state.0.lock().unwrap();
+                match history.record(captured, config::unix_millis()) {
+                    Ok(Some(entry)) => {
+                        let _ = app.emit("clipboard-added", entry);
+                    }
+                    Ok(None) => continue,
+                    Err(e) => {
+                        eprintln!("Failed to record clipboard: {}", e);
+                        continue;
+                    }
+                }
+                if let Err(e) = history.save() {
+                    eprintln!("Failed to save clipboard history: {}", e);
+                }
+                emit_clipboard_history(&app, &history);
+            }
+
+            // Our own auto-applied output must not be transformed again
+            let Some(text) = text.filter(|t| last_auto_output.as_ref() != Some(t)) else {
+                continue;
+            };
+            let transformed = app.state::<ClipboardTransformState>().0.lock().unwrap().auto_transform(&text);
+            if let Some(output) = transformed {
+                if clipboard.set_text(output.clone()).is_ok() {
+                    let _ = app.emit(
+                        "clipboard-transformed",
+                        serde_json::json!({ "input": text, "output": output }),
+                    );
+                    last_auto_output = Some(output);
+                }
+            }
+        }
+    });
+}
+
+/// Clipboard history, newest first
+#[tauri::command]
+async fn list_clipboard_history(
+    state: State<'_, ClipboardHistoryState>,
+) -> Result<Vec<clipboard_history::ClipboardEntry>, String> {
+    Ok(state.0.lock().unwrap().entries().to_vec())
+}
+
+/// Put a history entry back on the clipboard; the watcher then moves it to the top
+#[tauri::command]
+async fn copy_clipboard_entry(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistoryState>,
+    id: String,
+) -> Result<(), String> {
+    use clipboard_history::ClipboardContent;
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let (content, image_path) = {
+        let history = state.0.lock().unwrap();
+        let entry = history.get(&id).map_err(|e| e.to_string())?;
+        (entry.content.clone(), history.image_path(&id).map_err(|e| e.to_string())?)
+    };
+    match content {
+        ClipboardContent::Text { text } => app.clipboard().write_text(text).map_err(|e| e.to_string()),
+        ClipboardContent::Image { .. } => {
+            let path = image_path.ok_or("Image file missing")?;
+            let img = clipboard_image::load_for_clipboard(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+            write_clipboard_image(&app, img)
+        }
+        ClipboardContent::Files { paths } => arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set().file_list(&paths))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Base64 PNG of an image entry
+#[tauri::command]
+async fn read_clipboard_entry_image(state: State<'_, ClipboardHistoryState>, id: String) -> Result<String, String> {
+    let path = state.0.lock().unwrap().image_path(&id).map_err(|e| e.to_string())?;
+    let path = path.ok_or_else(|| format!("Clipboard entry '{}' is not an image", id))?;
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    Ok(STANDARD.encode(bytes))
+}
+
+#[tauri::command]
+async fn set_clipboard_entry_pinned(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistoryState>,
+    id: String,
+    pinned: bool,
+) -> Result<(), String> {
+    let mut history = state.0.lock().unwrap();
+    history.set_pinned(&id, pinned).map_err(|e| e.to_string())?;
+    history.save().map_err(|e| e.to_string())?;
+    emit_clipboard_history(&app, &history);
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_clipboard_entry(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistoryState>,
+    id: String,
+) -> Result<(), String> {
+    let mut history = state.0.lock().unwrap();
+    history.remove(&id).map_err(|e| e.to_string())?;
+    history.save().map_err(|e| e.to_string())?;
+    emit_clipboard_history(&app, &history);
+    Ok(())
+}
+
+/// Clear the history; pinned entries are kept unless `keep_pinned` is false
+#[tauri::command]
+async fn clear_clipboard_history(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistoryState>,
+    keep_pinned: Option<bool>,
+) -> Result<(), String> {
+    let mut history = state.0.lock().unwrap();
+    history.clear(keep_pinned.unwrap_or(true));
+    history.save().map_err(|e| e.to_string())?;
+    emit_clipboard_history(&app, &history);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_clipboard_settings(
+    state: State<'_, ClipboardHistoryState>,
+) -> Result<clipboard_history::ClipboardSettings, String> {
+    Ok(state.0.lock().unwrap().settings().clone())
+}
+
+#[tauri::command]
+async fn set_clipboard_settings(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistoryState>,
+    settings: clipboard_history::ClipboardSettings,
+) -> Result<(), String> {
+    let mut history = state.0.lock().unwrap();
+    history.set_settings(settings).map_err(|e| e.to_string())?;
+    emit_clipboard_history(&app, &history);
+    Ok(())
+}
+
+// ── Clipboard Transforms ──
+
+struct ClipboardTransformState(Mutex<clipboard_transform::TransformSettings>);
+
+/// Explicit steps win over a named preset
+fn resolve_transform_steps(
+    settings: &clipboard_transform::TransformSettings,
+    steps: Option<Vec<clipboard_transform::TransformStep>>,
+    preset: Option<String>,
+) -> Result<Vec<clipboard_transform::TransformStep>, String> {
+    match (steps, preset) {
+        (Some(steps), _) => Ok(steps),
+        (None, Some(preset)) => settings.preset(&preset).map(<[_]>::to_vec).map_err(|e| e.to_string()),
+        (None, None) => Ok(vec![clipboard_transform::TransformStep::Normalize]),
+    }
+}
+
+/// Normalize, round, convert and reformat a copied number
+#[tauri::command]
+async fn transform_clipboard_text(
+    state: State<'_, ClipboardTransformState>,
+    text: String,
+    steps: Option<Vec<clipboard_transform::TransformStep>>,
+    preset: Option<String>,
+) -> Result<clipboard_transform::TransformResult, String> {
+    let settings = state.0.lock().unwrap();
+    let steps = resolve_transform_steps(&settings, steps, preset)?;
+    settings.apply(&text, &steps).map_err(|e| e.to_string())
+}
+
+/// Transform a text entry from the clipboard history and, unless `copy` is
+/// false, put the result on the clipboard
+#[tauri::command]
+async fn transform_clipboard_entry(
+    app: tauri::AppHandle,
+    history_state: State<'_, ClipboardHistoryState>,
+    state: State<'_, ClipboardTransformState>,
+    id: String,
+    steps: Option<Vec<clipboard_transform::TransformStep>>,
+    preset: Option<String>,
+    copy: Option<bool>,
+) -> Result<clipboard_transform::TransformResult, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let text = match &history_state.0.lock().unwrap().get(&id).map_err(|e| e.to_string())?.content {
+        clipboard_history::ClipboardContent::Text { text } => text.clone(),
+        _ => return Err(format!("Clipboard entry '{}' is not text", id)),
+    };
+    let result = {
+        let settings = state.0.lock().unwrap();
+        let steps = resolve_transform_steps(&settings, steps, preset)?;
+        settings.apply(&text, &steps).map_err(|e| e.to_string())?
+    };
+    if copy.unwrap_or(true) {
+        app.clipboard().write_text(result.output.clone()).map_err(|e| e.to_string())?;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get_clipboard_transform_settings(
+    state: State<'_, ClipboardTransformState>,
+) -> Result<clipboard_transform::TransformSettings, String> {
+    Ok(state.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+async fn set_clipboard_transform_settings(
+    state: State<'_, ClipboardTransformState>,
+    settings: clipboard_transform::TransformSettings,
+) -> Result<(), String> {
+    if let Some(preset) = &settings.auto_apply {
+        settings.preset(preset).map_err(|e| e.to_string())?;
+    }
+    clipboard_transform::save_settings(&settings).map_err(|e| e.to_string())?;
+    *state.0.lock().unwrap() = settings;
+    Ok(())
+}
+
+// ── Trade Import ──
+
+/// Default window for matching an imported trade to a journal plan
+const DEFAULT_MATCH_WINDOW_SECS: i64 = 4 * 60 * 60;
+
+/// List built-in and user-saved CSV column mapping profiles
+#[tauri::command]
+async fn list_import_profiles() -> Result<Vec<trade_import::ImportProfile>, String> {
+    trade_import::list_profiles().map_err(|e| e.to_string())
+}
+
+/// Save (or replace by name) a user CSV column mapping profile
+#[tauri::command]
+async fn save_import_profile(profile: trade_import::ImportProfile) -> Result<(), String> {
+    trade_import::save_profile(profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_import_profile(name: String) -> Result<(), String> {
+    trade_import::delete_profile(&name).map_err(|e| e.to_string())
+}
+
+/// Import an exchange/broker trade-history CSV: parse fills with the given
+/// profile, group them into round trips with realized PnL, and match them
+/// to the planned journal trades passed in by the frontend.
+#[tauri::command]
+async fn import_trade_history(
+    path: String,
+    profile: String,
+    planned: Option<Vec<trade_import::PlannedTrade>>,
+    match_window_secs: Option<i64>,
+) -> Result<trade_import::ImportResult, String> {
+    let profile = trade_import::find_profile(&profile).map_err(|e| e.to_string())?;
+    let file = std::fs::File::open(&path).map_err(|e| format!("Failed to open file: {}", e))?;
+    trade_import::import_trades(
+        file,
+        &profile,
+        &planned.unwrap_or_default(),
+        match_window_secs.unwrap_or(DEFAULT_MATCH_WINDOW_SECS),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// ── Trade Plan Export ──
+
+/// Render a trade plan in the given format (text, markdown, json, pine)
+/// and copy it to the clipboard unless `copy_to_clipboard` is false.
+/// Returns the rendered text so the frontend can preview it.
+#[tauri::command]
+async fn export_trade_plan(
+    app: tauri::AppHandle,
+    plan: trade_plan::TradePlan,
+    format: trade_plan::PlanFormat,
+    copy_to_clipboard: Option<bool>,
+) -> Result<String, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let templates = trade_plan::load_templates().map_err(|e| e.to_string())?;
+    let rendered = trade_plan::render_plan(&plan, format, &templates).map_err(|e| e.to_string())?;
+    if copy_to_clipboard.unwrap_or(true) {
+        app.clipboard()
+            .write_text(rendered.clone())
+            .map_err(|e| format!("Failed to copy trade plan: {}", e))?;
+    }
+    Ok(rendered)
+}
+
+/// Get the user's text/markdown trade plan templates (defaults if never saved)
+#[tauri::command]
+async fn get_plan_templates() -> Result<trade_plan::PlanTemplates, String> {
+    trade_plan::load_templates().map_err(|e| e.to_string())
+}
+
+/// Save trade plan templates. Pass `null` to restore the defaults.
+#[tauri::command]
+async fn save_plan_templates(templates: Option<trade_plan::PlanTemplates>) -> Result<trade_plan::PlanTemplates, String> {
+    let templates = templates.unwrap_or_default();
+    trade_plan::save_templates(&templates).map_err(|e| e.to_string())?;
+    Ok(templates)
+}
+
 // ── App Updates ──
 
 #[derive(Serialize)]
@@ -2073,14 +3596,50 @@ pub fn run() {
         "--use-fake-ui-for-media-stream",
     );
 
+    let (floating_saves, floating_saves_rx) = std::sync::mpsc::channel();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(RegionState(Mutex::new(None)))
         .manage(PickedColorState(Mutex::new(None)))
+        .manage(ColorPickerCaptureState(Mutex::new(None)))
         .manage(ScreenshotPreviewState(Mutex::new(None)))
-        .setup(|app| {
+        .manage(PriceWatchState(Mutex::new(std::collections::HashMap::new())))
+        .manage(MarketFeedState(Mutex::new(std::collections::HashMap::new())))
+        .manage(ScreenshotLibraryState(Mutex::new(library::ScreenshotLibrary::default())))
+        .manage(ScreenshotMetaState(Mutex::new(library::load_meta_index().unwrap_or_default())))
+        .manage(PositionState(Mutex::new(positions::PositionBook::load().unwrap_or_default())))
+        .manage(ClipboardHistoryState(Mutex::new(
+            clipboard_history::ClipboardHistory::load().unwrap_or_default(),
+        )))
+        .manage(WindowLayoutState(Mutex::new(std::collections::HashMap::new())))
+        .manage(PointerReaderState(Mutex::new(edge_hover::PointerReader::new())))
+        .manage(HudSizesState(Mutex::new(layout::HudSizes::load().unwrap_or_default())))
+        .manage(AnimationState {
+            config: Mutex::new(animation::load_config().unwrap_or_default()),
+            active: Mutex::new(std::collections::HashMap::new()),
+            wake: std::sync::Condvar::new(),
+        })
+        .manage(HudWindowsState(Mutex::new(hud_windows::HudWindows::load().unwrap_or_default())))
+        .manage(FloatingModulesState {
+            modules: Mutex::new(floating::FloatingModules::load().unwrap_or_default()),
+            saves: Mutex::new(floating_saves),
+        })
+        .manage(FullscreenState {
+            config: Mutex::new(fullscreen::load_config().unwrap_or_default()),
+            active: Mutex::new(None),
+            hidden: Mutex::new(Vec::new()),
+        })
+        .manage(EdgeHoverState {
+            config: Mutex::new(edge_hover::load_config().unwrap_or_default()),
+            windows: Mutex::new(std::collections::HashMap::new()),
+        })
+        .manage(ClipboardTransformState(Mutex::new(
+            clipboard_transform::load_settings().unwrap_or_default(),
+        )))
+        .setup(move |app| {
             let show_item = MenuItem::with_id(app, "show", "Show / Hide", true, None::<&str>)?;
             let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
@@ -2092,18 +3651,18 @@ pub fn run() {
                 .on_menu_event(|app, event| {
                     match event.id.as_ref() {
                         "show" => {
-                            if let Some(window) = app.get_webview_window("main") {
-                                if window.is_visible().unwrap_or(false) {
+                            let windows = open_hud_windows(app);
+                            let any_visible = windows.iter().any(|w| w.is_visible().unwrap_or(false));
+                            for window in &windows {
+                                if any_visible {
                                     let _ = window.hide();
-                                    if let Some(dual) = app.get_webview_window("dual-right") {
-                                        let _ = dual.hide();
-                                    }
                                 } else {
                                     let _ = window.show();
-                                    let _ = window.set_focus();
-                                    if let Some(dual) = app.get_webview_window("dual-right") {
-                                        let _ = dual.show();
-                                    }
+                                }
+                            }
+                            if !any_visible {
+                                if let Some(main) = app.get_webview_window(hud_windows::MAIN_LABEL) {
+                                    let _ = main.set_focus();
                                 }
                             }
                         }
@@ -2115,23 +3674,69 @@ pub fn run() {
                 })
                 .build(app)?;
 
+            // Resume price watches from the previous session
+            if let Ok(configs) = alerts::load_watches() {
+                let state = app.state::<PriceWatchState>();
+                let mut watches = state.0.lock().unwrap();
+                for config in configs {
+                    let name = config.name.clone();
+                    watches.insert(name, spawn_price_watch(app.handle(), config));
+                }
+            }
+
+            spawn_position_tracker(app.handle().clone());
+            spawn_library_watcher(app.handle().clone());
+            spawn_clipboard_watcher(app.handle().clone());
+            spawn_edge_hover_service(app.handle().clone());
+            spawn_animation_driver(app.handle().clone());
+            spawn_fullscreen_watcher(app.handle().clone());
+            spawn_monitor_watcher(app.handle().clone());
+            spawn_floating_saver(app.handle().clone(), floating_saves_rx);
+            restore_hud_windows(app.handle());
+            restore_floating_modules(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             capture_screen,
             get_cursor_position,
             get_available_monitors,
+            get_hud_width,
+            set_hud_width,
+            list_hud_windows,
+            get_hud_window,
+            create_hud_window,
+            update_hud_window,
+            close_hud_window,
+            list_floating_modules,
+            get_floating_module,
+            open_floating_module,
+            close_floating_module,
+            set_floating_click_through,
+            set_floating_opacity,
             load_config,
             save_config,
             tuck_window,
             show_window,
             is_window_tucked,
+            set_edge_hover_enabled,
+            get_edge_hover_config,
+            set_edge_hover_config,
+            get_animation_config,
+            set_animation_config,
+            get_fullscreen_config,
+            set_fullscreen_config,
+            get_fullscreen_state,
             setup_window_size,
             set_window_position,
             open_region_selector,
             set_selected_region,
             get_selected_region,
             pick_screen_color,
+            sample_screen_color,
+            get_color_loupe,
+            extract_color_palette,
+            check_color_contrast,
             pick_folder,
             pick_file,
             launch_app,
@@ -2142,6 +3747,19 @@ pub fn run() {
             read_screenshot_thumbnail,
             open_screenshots_folder,
             copy_screenshot_to_clipboard,
+            paste_clipboard_image,
+            list_clipboard_history,
+            copy_clipboard_entry,
+            read_clipboard_entry_image,
+            set_clipboard_entry_pinned,
+            remove_clipboard_entry,
+            clear_clipboard_history,
+            get_clipboard_settings,
+            set_clipboard_settings,
+            transform_clipboard_text,
+            transform_clipboard_entry,
+            get_clipboard_transform_settings,
+            set_clipboard_transform_settings,
             open_color_picker_overlay,
             set_picked_color,
             get_picked_color,
@@ -2156,7 +3774,41 @@ pub fn run() {
             check_for_update,
             download_and_install_update,
             analyze_chart,
-            save_temp_image
+            save_temp_image,
+            take_screenshot,
+            list_capture_regions,
+            save_capture_region,
+            delete_capture_region,
+            load_screenshot_annotations,
+            annotate_screenshot,
+            list_screenshot_library,
+            get_screenshot_library_folders,
+            set_screenshot_library_folders,
+            get_screenshot_meta,
+            set_screenshot_meta,
+            ocr_screenshot,
+            search_screenshots,
+            list_screenshot_tags,
+            list_import_profiles,
+            save_import_profile,
+            delete_import_profile,
+            import_trade_history,
+            export_trade_plan,
+            get_plan_templates,
+            save_plan_templates,
+            start_price_watch,
+            stop_price_watch,
+            update_price_watch_levels,
+            list_price_watches,
+            start_market_feed,
+            stop_market_feed,
+            list_market_feeds,
+            calculate_position_size,
+            open_position,
+            close_position,
+            update_position_levels,
+            set_position_price,
+            list_positions
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,649 @@
+use crate::config::{self, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LibraryError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to open image: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+pub const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "bmp", "webp", "gif"];
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryEntry {
+    pub path: String,
+    pub filename: String,
+    /// The watched root folder this file was found under
+    pub folder: String,
+    pub modified: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryChanges {
+    pub added: Vec<LibraryEntry>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryPage {
+    pub total: usize,
+    pub offset: usize,
+    pub entries: Vec<LibraryEntry>,
+}
+
+/// What a directory contained at its last read
+struct DirState {
+    modified: SystemTime,
+    subdirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+/// Recursive index of image files under one or more folders.
+///
+/// Rescans are incremental: a directory is only re-read when its own mtime
+/// changes (which happens when entries are added, removed or renamed), so
+/// polling large folders costs one stat per directory.
+#[derive(Default)]
+pub struct ScreenshotLibrary {
+    roots: Vec<PathBuf>,
+    dirs: HashMap<PathBuf, DirState>,
+    entries: HashMap<PathBuf, LibraryEntry>,
+}
+
+impl ScreenshotLibrary {
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Replace the watched folders and drop the index; the next scan rebuilds it
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        let mut unique = Vec::new();
+        for root in roots {
+            if !unique.contains(&root) {
+                unique.push(root);
+            }
+        }
+        self.roots = unique;
+        self.dirs.clear();
+        self.entries.clear();
+    }
+
+    /// Bring the index up to date and report what changed
+    pub fn scan(&mut self) -> LibraryChanges {
+        let mut changes = LibraryChanges::default();
+        let mut seen_dirs = HashSet::new();
+        for root in self.roots.clone() {
+            self.scan_dir(&root, &root, &mut seen_dirs, &mut changes);
+        }
+
+        // Directories that disappeared take their files with them
+        let gone: Vec<PathBuf> = self.dirs.keys().filter(|d| !seen_dirs.contains(*d)).cloned().collect();
+        for dir in gone {
+            if let Some(state) = self.dirs.remove(&dir) {
+                for file in state.files {
+                    if self.entries.remove(&file).is_some() {
+                        changes.removed.push(file.to_string_lossy().to_string());
+                    }
+                }
+            }
+        }
+        changes
+    }
+
+    fn scan_dir(&mut self, root: &Path, dir: &Path, seen: &mut HashSet<PathBuf>, changes: &mut LibraryChanges) {
+        let Ok(modified) = fs::metadata(dir).and_then(|m| m.modified()) else { return };
+        if !seen.insert(dir.to_path_buf()) {
+            return;
+        }
+
+        let unchanged = self.dirs.get(dir).is_some_and(|s| s.modified == modified);
+        let subdirs = if unchanged {
+            self.dirs[dir].subdirs.clone()
+        } else {
+            let Ok(read) = fs::read_dir(dir) else { return };
+            let mut subdirs = Vec::new();
+            let mut files = Vec::new();
+            for entry in read.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else { continue };
+                if file_type.is_dir() {
+                    subdirs.push(path);
+                } else if file_type.is_file() && is_image(&path) {
+                    if !self.entries.contains_key(&path) {
+                        if let Ok(meta) = entry.metadata() {
+                            let item = LibraryEntry {
+                                path: path.to_string_lossy().to_string(),
+                                filename: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                                folder: root.to_string_lossy().to_string(),
+                                modified: meta.modified().map(unix_secs).unwrap_or(0),
+                                size: meta.len(),
+                            };
+                            changes.added.push(item.clone());
+                            self.entries.insert(path.clone(), item);
+                        }
+                    }
+                    files.push(path);
+                }
+            }
+            if let Some(old) = self.dirs.get(dir) {
+                for file in old.files.iter().filter(|f| !files.contains(f)) {
+                    if self.entries.remove(file).is_some() {
+                        changes.removed.push(file.to_string_lossy().to_string());
+                    }
+                }
+            }
+            self.dirs.insert(dir.to_path_buf(), DirState { modified, subdirs: subdirs.clone(), files });
+            subdirs
+        };
+
+        for sub in subdirs {
+            self.scan_dir(root, &sub, seen, changes);
+        }
+    }
+
+    /// Newest first, optionally limited to paths matching `filter`
+    pub fn sorted(&self, filter: impl Fn(&LibraryEntry) -> bool) -> Vec<&LibraryEntry> {
+        let mut list: Vec<&LibraryEntry> = self.entries.values().filter(|e| filter(e)).collect();
+        list.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+        list
+    }
+
+    pub fn page(&self, offset: usize, limit: Option<usize>, filter: impl Fn(&LibraryEntry) -> bool) -> LibraryPage {
+        let list = self.sorted(filter);
+        let total = list.len();
+        let entries = list.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).cloned().collect();
+        LibraryPage { total, offset, entries }
+    }
+}
+
+/// Extra folders indexed alongside the configured screenshots folder
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibrarySettings {
+    pub extra_folders: Vec<String>,
+}
+
+fn settings_path() -> Result<PathBuf, LibraryError> {
+    Ok(config::get_app_dir()?.join("screenshot_library.json"))
+}
+
+pub fn load_settings() -> Result<LibrarySettings, LibraryError> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(LibrarySettings::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_settings(settings: &LibrarySettings) -> Result<(), LibraryError> {
+    fs::write(settings_path()?, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+/// FNV-1a; stable across builds so cache file names survive upgrades
+pub fn fnv1a(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in data {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn thumbnail_dir() -> Result<PathBuf, LibraryError> {
+    let dir = config::get_app_dir()?.join("thumbnails");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn path_key(path: &Path) -> String {
+    format!("{:016x}", fnv1a(path.to_string_lossy().as_bytes()))
+}
+
+/// PNG thumbnail bytes, served from the on-disk cache when the source is
+/// unchanged. Cache files are keyed by path hash, mtime and size; renditions
+/// left behind by an older mtime are removed by [`prune_thumbnails`].
+pub fn thumbnail_png(path: &Path, max_width: u32) -> Result<Vec<u8>, LibraryError> {
+    let modified = fs::metadata(path)?.modified().map(unix_secs).unwrap_or(0);
+    let cached = thumbnail_dir()?.join(format!("{}_{}_{}.png", path_key(path), modified, max_width));
+    if let Ok(bytes) = fs::read(&cached) {
+        return Ok(bytes);
+    }
+
+    let thumb = image::open(path)?.thumbnail(max_width, max_width);
+    let mut buf = Cursor::new(Vec::new());
+    thumb.write_to(&mut buf, image::ImageFormat::Png)?;
+    let bytes = buf.into_inner();
+    fs::write(&cached, &bytes)?;
+    Ok(bytes)
+}
+
+/// Whether a file that left the library was really deleted: it's gone while
+/// the watched folder holding it is still there. An unmounted drive or a
+/// removed folder doesn't count.
+pub fn is_deleted(path: &Path, roots: &[PathBuf]) -> bool {
+    !path.exists() && roots.iter().any(|root| path.starts_with(root) && root.is_dir())
+}
+
+/// Cache files to delete: every rendition of a removed key, and renditions
+/// older than the newest mtime cached for their key
+fn stale_thumbnails(names: &[String], removed: &HashSet<String>) -> Vec<String> {
+    let parse = |name: &str| -> Option<(String, u64)> {
+        let mut parts = name.splitn(3, '_');
+        Some((parts.next()?.to_string(), parts.next()?.parse().ok()?))
+    };
+    let mut newest: HashMap<String, u64> = HashMap::new();
+    for (key, modified) in names.iter().filter_map(|n| parse(n)) {
+        let entry = newest.entry(key).or_insert(modified);
+        *entry = (*entry).max(modified);
+    }
+    names
+        .iter()
+        .filter(|name| {
+            parse(name).is_some_and(|(key, modified)| removed.contains(&key) || newest.get(&key).is_some_and(|n| modified < *n))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Drop cached thumbnails of files that left the library and stale
+/// renditions of files that changed. Reads the cache folder once, and not at
+/// all when nothing was removed.
+pub fn prune_thumbnails(removed: &[String]) {
+    if !removed.is_empty() {
+        prune_thumbnail_dir(removed);
+    }
+}
+
+/// Drop renditions left behind by files that changed since they were cached
+pub fn prune_stale_thumbnails() {
+    prune_thumbnail_dir(&[]);
+}
+
+fn prune_thumbnail_dir(removed: &[String]) {
+    let Ok(dir) = thumbnail_dir() else { return };
+    let Ok(read) = fs::read_dir(&dir) else { return };
+    let names: Vec<String> = read.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect();
+    let removed: HashSet<String> = removed.iter().map(|p| path_key(Path::new(p))).collect();
+    for name in stale_thumbnails(&names, &removed) {
+        let _ = fs::remove_file(dir.join(name));
+    }
+}
+
+/// User metadata for one screenshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotMeta {
+    pub tags: Vec<String>,
+    pub symbol: Option<String>,
+    pub timeframe: Option<String>,
+    pub notes: String,
+    /// Text recognized in the image, when OCR has been run
+    pub ocr_text: Option<String>,
+}
+
+impl ScreenshotMeta {
+    /// Lowercase, de-duplicated tags; uppercase symbol; blanks become None
+    pub fn normalized(mut self) -> Self {
+        let mut tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        self.tags = tags;
+        self.symbol = self.symbol.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty());
+        self.timeframe = self.timeframe.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        self.notes = self.notes.trim().to_string();
+        self.ocr_text = self.ocr_text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ScreenshotMeta::default()
+    }
+}
+
+/// Metadata by screenshot path
+pub type MetaIndex = BTreeMap<String, ScreenshotMeta>;
+
+fn meta_path() -> Result<PathBuf, LibraryError> {
+    Ok(config::get_app_dir()?.join("screenshot_meta.json"))
+}
+
+pub fn load_meta_index() -> Result<MetaIndex, LibraryError> {
+    let path = meta_path()?;
+    if !path.exists() {
+        return Ok(MetaIndex::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_meta_index(index: &MetaIndex) -> Result<(), LibraryError> {
+    fs::write(meta_path()?, serde_json::to_string_pretty(index)?)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotQuery {
+    /// Free text; every word must appear in the filename, notes, OCR text,
+    /// tags, symbol or timeframe
+    pub text: String,
+    /// Every tag must be present
+    pub tags: Vec<String>,
+    pub symbol: Option<String>,
+    pub timeframe: Option<String>,
+}
+
+impl ScreenshotQuery {
+    pub fn matches(&self, entry: &LibraryEntry, meta: Option<&ScreenshotMeta>) -> bool {
+        let empty = ScreenshotMeta::default();
+        let meta = meta.unwrap_or(&empty);
+
+        if let Some(symbol) = self.symbol.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            if !meta.symbol.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(symbol)) {
+                return false;
+            }
+        }
+        if let Some(tf) = self.timeframe.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            if !meta.timeframe.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(tf)) {
+                return false;
+            }
+        }
+        for tag in &self.tags {
+            let tag = tag.trim().trim_start_matches('#').to_lowercase();
+            if !tag.is_empty() && !meta.tags.contains(&tag) {
+                return false;
+            }
+        }
+
+        let haystack = [
+            entry.filename.as_str(),
+            meta.notes.as_str(),
+            meta.ocr_text.as_deref().unwrap_or(""),
+            meta.symbol.as_deref().unwrap_or(""),
+            meta.timeframe.as_deref().unwrap_or(""),
+            &meta.tags.join(" "),
+        ]
+        .join("\n")
+        .to_lowercase();
+        self.text
+            .split_whitespace()
+            .all(|word| haystack.contains(&word.trim_start_matches('#').to_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn stale_thumbnails_keeps_newest_rendition_per_key() {
+        let cache = names(&["aaaa_100_120.png", "aaaa_200_120.png", "aaaa_200_240.png", "bbbb_50_120.png", "notes.txt"]);
+        let mut stale = stale_thumbnails(&cache, &HashSet::new());
+        stale.sort();
+        assert_eq!(stale, names(&["aaaa_100_120.png"]));
+    }
+
+    #[test]
+    fn stale_thumbnails_drops_every_rendition_of_removed_keys() {
+        let cache = names(&["aaaa_100_120.png", "aaaa_200_120.png", "bbbb_50_120.png"]);
+        let removed: HashSet<String> = ["aaaa".to_string()].into();
+        let mut stale = stale_thumbnails(&cache, &removed);
+        stale.sort();
+        assert_eq!(stale, names(&["aaaa_100_120.png", "aaaa_200_120.png"]));
+    }
+
+    #[test]
+    fn is_deleted_only_when_the_watched_folder_is_still_there() {
+        let root = std::env::temp_dir().join(format!("quanthud_library_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let kept = root.join("kept.png");
+        fs::write(&kept, b"png").unwrap();
+        let roots = vec![root.clone()];
+
+        assert!(is_deleted(&root.join("gone.png"), &roots));
+        assert!(!is_deleted(&kept, &roots));
+        // Outside every watched folder
+        assert!(!is_deleted(Path::new("/nonexistent/elsewhere/gone.png"), &roots));
+
+        fs::remove_dir_all(&root).unwrap();
+        // The whole folder went away, e.g. an unplugged drive
+        assert!(!is_deleted(&kept, &roots));
+    }
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("quanthud_library_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn library(root: &Path) -> ScreenshotLibrary {
+        let mut library = ScreenshotLibrary::default();
+        library.set_roots(vec![root.to_path_buf(), root.to_path_buf()]);
+        library
+    }
+
+    fn paths(list: &[String]) -> Vec<String> {
+        let mut list = list.to_vec();
+        list.sort();
+        list
+    }
+
+    fn added_paths(changes: &LibraryChanges) -> Vec<String> {
+        paths(&changes.added.iter().map(|e| e.path.clone()).collect::<Vec<_>>())
+    }
+
+    fn lossy(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    fn entry(name: &str, modified: u64) -> LibraryEntry {
+        LibraryEntry {
+            path: format!("/shots/{}", name),
+            filename: name.to_string(),
+            folder: "/shots".to_string(),
+            modified,
+            size: 1,
+        }
+    }
+
+    #[test]
+    fn scan_indexes_images_in_every_subfolder() {
+        let root = temp_root("recursive");
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        fs::write(root.join("a.png"), b"png").unwrap();
+        fs::write(root.join("notes.txt"), b"text").unwrap();
+        fs::write(root.join("sub/b.JPG"), b"jpg").unwrap();
+        fs::write(root.join("sub/deeper/c.webp"), b"webp").unwrap();
+
+        // The duplicate root is indexed once
+        let mut library = library(&root);
+        assert_eq!(library.roots().len(), 1);
+        let changes = library.scan();
+        assert_eq!(
+            added_paths(&changes),
+            paths(&[lossy(&root.join("a.png")), lossy(&root.join("sub/b.JPG")), lossy(&root.join("sub/deeper/c.webp"))])
+        );
+        assert!(changes.added.iter().all(|e| e.folder == lossy(&root)));
+        assert!(changes.removed.is_empty());
+
+        let again = library.scan();
+        assert!(again.added.is_empty() && again.removed.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scan_reports_added_and_removed_files() {
+        let root = temp_root("changes");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("old.png"), b"png").unwrap();
+        fs::write(root.join("sub/inner.png"), b"png").unwrap();
+        let mut library = library(&root);
+        library.scan();
+
+        fs::remove_file(root.join("old.png")).unwrap();
+        fs::write(root.join("new.png"), b"png").unwrap();
+        let changes = library.scan();
+        assert_eq!(added_paths(&changes), vec![lossy(&root.join("new.png"))]);
+        assert_eq!(changes.removed, vec![lossy(&root.join("old.png"))]);
+
+        // A removed folder takes its files with it
+        fs::remove_dir_all(root.join("sub")).unwrap();
+        let changes = library.scan();
+        assert!(changes.added.is_empty());
+        assert_eq!(changes.removed, vec![lossy(&root.join("sub/inner.png"))]);
+        assert_eq!(library.page(0, None, |_| true).total, 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scan_skips_directories_whose_mtime_is_unchanged() {
+        let root = temp_root("unchanged");
+        fs::write(root.join("real.png"), b"png").unwrap();
+        let mut library = library(&root);
+        library.scan();
+
+        // A file the index thinks is there: re-reading the folder would report
+        // it removed, so silence means the folder was not read again
+        let ghost = root.join("ghost.png");
+        library.dirs.get_mut(&root).unwrap().files.push(ghost.clone());
+        library.entries.insert(ghost.clone(), entry("ghost.png", 0));
+        let changes = library.scan();
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+
+        // Touching the folder makes the next scan read it
+        fs::write(root.join("other.png"), b"png").unwrap();
+        let changes = library.scan();
+        assert_eq!(added_paths(&changes), vec![lossy(&root.join("other.png"))]);
+        assert_eq!(changes.removed, vec![lossy(&ghost)]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn page_is_newest_first_and_counts_before_paging() {
+        let mut library = ScreenshotLibrary::default();
+        for (name, modified) in [("a.png", 30), ("b.png", 10), ("c.png", 20), ("d.png", 30), ("e.png", 40)] {
+            let item = entry(name, modified);
+            library.entries.insert(PathBuf::from(&item.path), item);
+        }
+        let names = |page: &LibraryPage| page.entries.iter().map(|e| e.filename.clone()).collect::<Vec<_>>();
+
+        let all = library.page(0, None, |_| true);
+        assert_eq!(all.total, 5);
+        // Equal times fall back to the path
+        assert_eq!(names(&all), ["e.png", "a.png", "d.png", "c.png", "b.png"]);
+
+        let second = library.page(2, Some(2), |_| true);
+        assert_eq!((second.total, second.offset), (5, 2));
+        assert_eq!(names(&second), ["d.png", "c.png"]);
+        assert!(library.page(10, Some(2), |_| true).entries.is_empty());
+
+        let filtered = library.page(1, Some(10), |e| e.modified >= 30);
+        assert_eq!(filtered.total, 3);
+        assert_eq!(names(&filtered), ["a.png", "d.png"]);
+    }
+
+    fn meta(tags: &[&str], symbol: Option<&str>, timeframe: Option<&str>, notes: &str, ocr: Option<&str>) -> ScreenshotMeta {
+        ScreenshotMeta {
+            tags: names(tags),
+            symbol: symbol.map(str::to_string),
+            timeframe: timeframe.map(str::to_string),
+            notes: notes.to_string(),
+            ocr_text: ocr.map(str::to_string),
+        }
+    }
+
+    fn query(text: &str, tags: &[&str], symbol: Option<&str>, timeframe: Option<&str>) -> ScreenshotQuery {
+        ScreenshotQuery {
+            text: text.to_string(),
+            tags: names(tags),
+            symbol: symbol.map(str::to_string),
+            timeframe: timeframe.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn normalized_cleans_tags_and_blanks() {
+        let raw = meta(&["#Breakout", " breakout ", "BTC", "#", "  ", "Retest"], Some(" btcusdt "), Some(" 4h "), "  note \n", Some("   "));
+        let clean = raw.normalized();
+        assert_eq!(clean.tags, names(&["breakout", "btc", "retest"]));
+        assert_eq!(clean.symbol.as_deref(), Some("BTCUSDT"));
+        assert_eq!(clean.timeframe.as_deref(), Some("4h"));
+        assert_eq!(clean.notes, "note");
+        assert_eq!(clean.ocr_text, None);
+
+        let blank = meta(&["#"], Some(" "), Some(""), " ", Some("")).normalized();
+        assert_eq!(blank.symbol, None);
+        assert_eq!(blank.timeframe, None);
+        assert!(blank.is_empty());
+    }
+
+    #[test]
+    fn query_filters_on_symbol_timeframe_and_tags() {
+        let shot = entry("chart.png", 0);
+        let tagged = meta(&["breakout", "btc"], Some("BTCUSDT"), Some("4h"), "", None);
+
+        assert!(query("", &[], None, None).matches(&shot, Some(&tagged)));
+        assert!(query("", &[], None, None).matches(&shot, None));
+        assert!(query("", &[], Some(" btcusdt "), Some("4H")).matches(&shot, Some(&tagged)));
+        assert!(!query("", &[], Some("ETHUSDT"), None).matches(&shot, Some(&tagged)));
+        assert!(!query("", &[], None, Some("1h")).matches(&shot, Some(&tagged)));
+        // A filter never matches a screenshot without metadata
+        assert!(!query("", &[], Some("BTCUSDT"), None).matches(&shot, None));
+        // Blank filters are ignored
+        assert!(query("", &[" ", "#"], Some(" "), Some("")).matches(&shot, None));
+
+        assert!(query("", &["#Breakout", "BTC"], None, None).matches(&shot, Some(&tagged)));
+        assert!(!query("", &["breakout", "eth"], None, None).matches(&shot, Some(&tagged)));
+    }
+
+    #[test]
+    fn query_text_needs_every_word_somewhere() {
+        let shot = entry("BTC_2024-03-09.png", 0);
+        let tagged = meta(&["breakout"], Some("BTCUSDT"), Some("4h"), "Retest of the range high", Some("Volume spike 12.5K"));
+
+        // Each word may come from a different field
+        assert!(query("2024 retest volume", &[], None, None).matches(&shot, Some(&tagged)));
+        assert!(query("RANGE spike", &[], None, None).matches(&shot, Some(&tagged)));
+        assert!(query("#breakout 4h btcusdt", &[], None, None).matches(&shot, Some(&tagged)));
+        assert!(!query("retest support", &[], None, None).matches(&shot, Some(&tagged)));
+        assert!(query("btc_2024", &[], None, None).matches(&shot, None));
+        assert!(!query("retest", &[], None, None).matches(&shot, None));
+    }
+}
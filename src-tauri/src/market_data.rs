@@ -0,0 +1,1107 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tungstenite::handshake::HandshakeError;
+use tungstenite::http::Uri;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+#[derive(Error, Debug)]
+pub enum FeedError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("WebSocket error: {0}")]
+    WebSocket(Box<tungstenite::Error>),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Invalid JSON on line {line}: {source}")]
+    Json { line: usize, source: serde_json::Error },
+    #[error("Unsupported replay file (expected .csv, .jsonl or .ndjson): {0}")]
+    UnsupportedReplay(String),
+    #[error("Invalid replay speed {0} (expected a finite number, 0 for no delay)")]
+    InvalidSpeed(f64),
+    #[error("No record in the replay file matches the field mapping: {0}")]
+    EmptyReplay(String),
+    #[error("Invalid WebSocket URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl From<tungstenite::Error> for FeedError {
+    fn from(e: tungstenite::Error) -> Self {
+        FeedError::WebSocket(Box::new(e))
+    }
+}
+
+/// A normalized trade/price update
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tick {
+    pub feed: String,
+    pub symbol: String,
+    pub price: f64,
+    pub size: Option<f64>,
+    /// Unix milliseconds (source time when available, otherwise receive time)
+    pub time: i64,
+    /// Exchange trade id, when mapped
+    pub id: Option<String>,
+}
+
+/// A normalized OHLCV bar. `closed` is false for in-progress updates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candle {
+    pub feed: String,
+    pub symbol: String,
+    pub interval: Option<String>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<f64>,
+    /// Unix milliseconds of the bar open
+    pub time: i64,
+    pub closed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MarketEvent {
+    Tick(Tick),
+    Candle(Candle),
+}
+
+impl MarketEvent {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketEvent::Tick(t) => &t.symbol,
+            MarketEvent::Candle(c) => &c.symbol,
+        }
+    }
+
+    /// Last traded price (close for candles)
+    pub fn price(&self) -> f64 {
+        match self {
+            MarketEvent::Tick(t) => t.price,
+            MarketEvent::Candle(c) => c.close,
+        }
+    }
+
+    pub fn time(&self) -> i64 {
+        match self {
+            MarketEvent::Tick(t) => t.time,
+            MarketEvent::Candle(c) => c.time,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeUnit {
+    #[default]
+    Millis,
+    Seconds,
+}
+
+/// Where to find each field in a message, as JSON pointers ("/data/p", "/k/c", "/4").
+/// A leading "/" may be omitted for top-level keys. When `open`, `high` and
+/// `low` are all set the record is a candle (with `price` as the close),
+/// otherwise a tick. CSV replay rows are objects keyed by header name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    /// Pointer to an array of records ("" when the message is the array); when
+    /// unset the message itself is one record
+    pub items: Option<String>,
+    pub price: String,
+    pub size: Option<String>,
+    pub time: Option<String>,
+    pub time_unit: TimeUnit,
+    pub symbol: Option<String>,
+    /// Trade id; tells apart polled trades that share a millisecond
+    pub id: Option<String>,
+    pub open: Option<String>,
+    pub high: Option<String>,
+    pub low: Option<String>,
+    pub volume: Option<String>,
+    pub closed: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FeedSource {
+    /// Streaming endpoint; `subscribe` is sent verbatim after connecting
+    WebSocket { url: String, subscribe: Option<Value> },
+    /// Polled JSON endpoint
+    Rest { url: String, poll_ms: u64 },
+    /// Offline CSV/JSONL file. `speed` scales the recorded gaps between
+    /// records (2.0 = twice as fast, 0 = no delay); gaps are capped at a minute
+    /// and a looping replay pauses briefly between passes.
+    Replay {
+        path: String,
+        #[serde(default = "default_replay_speed")]
+        speed: f64,
+        #[serde(default, rename = "loop")]
+        repeat: bool,
+    },
+}
+
+fn default_replay_speed() -> f64 {
+    1.0
+}
+
+/// Pause between passes of a looping replay, so `speed: 0` doesn't spin
+const REPLAY_LOOP_PAUSE: Duration = Duration::from_millis(250);
+
+/// Longest wait for the TCP connection of a WebSocket feed
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest pause a replay sits through, so a hole in the recording (a
+/// weekend, a restart of the recorder) doesn't stall it
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedConfig {
+    pub name: String,
+    /// Symbol used when the mapping has no symbol field
+    pub symbol: String,
+    /// Candle interval label, e.g. "1m"
+    #[serde(default)]
+    pub interval: Option<String>,
+    pub source: FeedSource,
+    pub mapping: FieldMapping,
+}
+
+impl FeedConfig {
+    pub fn validate(&self) -> Result<(), FeedError> {
+        if let FeedSource::Replay { speed, .. } = &self.source {
+            if !speed.is_finite() || *speed < 0.0 {
+                return Err(FeedError::InvalidSpeed(*speed));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn pointer<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() || path.starts_with('/') {
+        value.pointer(path)
+    } else {
+        value.pointer(&format!("/{}", path))
+    }
+}
+
+/// Numbers arrive as JSON numbers or as strings ("3151.25") depending on the exchange
+fn number_at(value: &Value, path: &str) -> Option<f64> {
+    match pointer(value, path)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|v: &f64| v.is_finite())
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Map a decoded message to normalized events using the feed's field mapping.
+/// Records missing required fields are skipped.
+pub fn map_message(config: &FeedConfig, message: &Value) -> Vec<MarketEvent> {
+    let m = &config.mapping;
+    let records: Vec<&Value> = match m.items.as_deref() {
+        Some(items) => match pointer(message, items) {
+            Some(Value::Array(arr)) => arr.iter().collect(),
+            _ => return vec![],
+        },
+        None => vec![message],
+    };
+
+    records
+        .into_iter()
+        .filter_map(|rec| {
+            let price = number_at(rec, &m.price)?;
+            let time = m
+                .time
+                .as_deref()
+                .and_then(|p| number_at(rec, p))
+                .map(|t| match m.time_unit {
+                    TimeUnit::Millis => t as i64,
+                    TimeUnit::Seconds => (t * 1000.0) as i64,
+                })
+                .unwrap_or_else(now_millis);
+            let symbol = m
+                .symbol
+                .as_deref()
+                .and_then(|p| pointer(rec, p))
+                .and_then(|v| v.as_str())
+                .unwrap_or(&config.symbol)
+                .to_string();
+            let volume = |p: &Option<String>| p.as_deref().and_then(|p| number_at(rec, p));
+
+            match (m.open.as_deref(), m.high.as_deref(), m.low.as_deref()) {
+                (Some(o), Some(h), Some(l)) => Some(MarketEvent::Candle(Candle {
+                    feed: config.name.clone(),
+                    symbol,
+                    interval: config.interval.clone(),
+                    open: number_at(rec, o)?,
+                    high: number_at(rec, h)?,
+                    low: number_at(rec, l)?,
+                    close: price,
+                    volume: volume(&m.volume),
+                    time,
+                    closed: m
+                        .closed
+                        .as_deref()
+                        .and_then(|p| pointer(rec, p))
+                        .map(|v| v.as_bool().unwrap_or(v.as_str() == Some("true")))
+                        .unwrap_or(true),
+                })),
+                _ => Some(MarketEvent::Tick(Tick {
+                    feed: config.name.clone(),
+                    symbol,
+                    price,
+                    size: volume(&m.size),
+                    time,
+                    id: m.id.as_deref().and_then(|p| pointer(rec, p)).map(|v| match v {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    }),
+                })),
+            }
+        })
+        .collect()
+}
+
+/// A source of normalized market data. `run` blocks until `stop` is set or
+/// the source is exhausted, passing every event to `emit`. Transient errors
+/// (dropped connection, failed poll) go to `report` and the source retries;
+/// an `Err` return means it cannot continue.
+pub trait MarketDataSource: Send {
+    fn run(
+        &mut self,
+        stop: &AtomicBool,
+        emit: &mut dyn FnMut(MarketEvent),
+        report: &mut dyn FnMut(&FeedError),
+    ) -> Result<(), FeedError>;
+}
+
+/// Sleep up to `total`, waking early when `stop` is set
+fn sleep_unless_stopped(stop: &AtomicBool, total: Duration) {
+    let deadline = Instant::now() + total;
+    while !stop.load(Ordering::SeqCst) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50).min(deadline.saturating_duration_since(Instant::now())));
+    }
+}
+
+pub struct WebSocketSource {
+    config: FeedConfig,
+    url: String,
+    subscribe: Option<Value>,
+}
+
+impl WebSocketSource {
+    fn set_read_timeout(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>, timeout: Duration) -> std::io::Result<()> {
+        match socket.get_mut() {
+            MaybeTlsStream::Plain(s) => s.set_read_timeout(Some(timeout)),
+            MaybeTlsStream::Rustls(s) => s.get_mut().set_read_timeout(Some(timeout)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Open the TCP connection with a timeout (`tungstenite::connect` has none,
+    /// so a stop request would wait on the OS default), then run the handshake
+    fn connect(&self) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, FeedError> {
+        let uri: Uri = self.url.parse().map_err(|_| FeedError::InvalidUrl(self.url.clone()))?;
+        let host = uri.host().ok_or_else(|| FeedError::InvalidUrl(self.url.clone()))?;
+        let port = uri.port_u16().unwrap_or(if uri.scheme_str() == Some("wss") { 443 } else { 80 });
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+
+        let mut last_err = std::io::Error::new(std::io::ErrorKind::NotFound, format!("No address for {}", host));
+        let mut stream = None;
+        for addr in (host, port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                Ok(s) => {
+                    stream = Some(s);
+                    break;
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        let stream = stream.ok_or(last_err)?;
+        // Bound the handshake as well; `session` shortens this afterwards
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+
+        match tungstenite::client_tls(self.url.as_str(), stream) {
+            Ok((socket, _)) => Ok(socket),
+            Err(HandshakeError::Failure(e)) => Err(e.into()),
+            Err(HandshakeError::Interrupted(_)) => {
+                Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "WebSocket handshake timed out").into())
+            }
+        }
+    }
+
+    /// One connection lifetime; returns when the socket closes or `stop` is set
+    fn session(&mut self, stop: &AtomicBool, emit: &mut dyn FnMut(MarketEvent)) -> Result<(), FeedError> {
+        let mut socket = self.connect()?;
+        // Short read timeout so the loop notices stop requests
+        Self::set_read_timeout(&mut socket, Duration::from_millis(500))?;
+
+        if let Some(sub) = &self.subscribe {
+            let text = match sub {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            socket.send(Message::text(text))?;
+        }
+
+        while !stop.load(Ordering::SeqCst) {
+            let text = match socket.read() {
+                Ok(Message::Text(t)) => t.to_string(),
+                Ok(Message::Binary(b)) => String::from_utf8_lossy(&b).to_string(),
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            };
+            // Non-JSON frames (e.g. "pong") are ignored
+            if let Ok(value) = serde_json::from_str::<Value>(&text) {
+                for event in map_message(&self.config, &value) {
+                    emit(event);
+                }
+            }
+        }
+        let _ = socket.close(None);
+        Ok(())
+    }
+}
+
+impl MarketDataSource for WebSocketSource {
+    /// Reconnects with exponential backoff (1s..30s) until stopped
+    fn run(
+        &mut self,
+        stop: &AtomicBool,
+        emit: &mut dyn FnMut(MarketEvent),
+        report: &mut dyn FnMut(&FeedError),
+    ) -> Result<(), FeedError> {
+        let mut backoff = Duration::from_secs(1);
+        while !stop.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            let result = self.session(stop, emit);
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            if started.elapsed() > Duration::from_secs(30) {
+                backoff = Duration::from_secs(1);
+            }
+            if let Err(e) = result {
+                report(&e);
+            }
+            sleep_unless_stopped(stop, backoff);
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+        Ok(())
+    }
+}
+
+pub struct RestSource {
+    config: FeedConfig,
+    url: String,
+    poll: Duration,
+}
+
+/// Polled endpoints usually return a window of recent records; this keeps
+/// the ones not forwarded by an earlier poll.
+#[derive(Debug, Default)]
+struct SeenTrades {
+    newest: Option<i64>,
+    /// Ticks already forwarded at `newest`, so trades in the same millisecond
+    /// as the last poll's newest one aren't dropped
+    at_newest: HashSet<String>,
+}
+
+impl SeenTrades {
+    /// Trade id when mapped, otherwise the trade itself
+    fn key(tick: &Tick) -> String {
+        tick.id.clone().unwrap_or_else(|| format!("{}|{}|{:?}", tick.symbol, tick.price, tick.size))
+    }
+
+    fn fresh(&mut self, events: Vec<MarketEvent>) -> Vec<MarketEvent> {
+        let previous = self.newest;
+        let fresh: Vec<MarketEvent> = events
+            .into_iter()
+            .filter(|event| match (previous, event) {
+                (None, _) => true,
+                // In-progress candles are re-sent with the same open time
+                (Some(t), MarketEvent::Candle(c)) => c.time >= t,
+                (Some(t), MarketEvent::Tick(tick)) => {
+                    tick.time > t || (tick.time == t && !self.at_newest.contains(&Self::key(tick)))
+                }
+            })
+            .collect();
+
+        for event in &fresh {
+            if self.newest.is_none_or(|t| event.time() > t) {
+                self.newest = Some(event.time());
+                self.at_newest.clear();
+            }
+            if let MarketEvent::Tick(tick) = event {
+                if Some(tick.time) == self.newest {
+                    self.at_newest.insert(Self::key(tick));
+                }
+            }
+        }
+        fresh
+    }
+}
+
+impl MarketDataSource for RestSource {
+    fn run(
+        &mut self,
+        stop: &AtomicBool,
+        emit: &mut dyn FnMut(MarketEvent),
+        report: &mut dyn FnMut(&FeedError),
+    ) -> Result<(), FeedError> {
+        // Built on the feed thread: the blocking client runs its own runtime
+        // and panics when created from inside an async command
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let mut seen = SeenTrades::default();
+        while !stop.load(Ordering::SeqCst) {
+            let started = Instant::now();
+            let fetched = client
+                .get(&self.url)
+                .header("User-Agent", "QuantHUD")
+                .send()
+                .and_then(|r| r.error_for_status())
+                .and_then(|r| r.json::<Value>());
+            match fetched {
+                Ok(value) => {
+                    for event in seen.fresh(map_message(&self.config, &value)) {
+                        emit(event);
+                    }
+                }
+                Err(e) => report(&e.into()),
+            }
+            sleep_unless_stopped(stop, self.poll.saturating_sub(started.elapsed()));
+        }
+        Ok(())
+    }
+}
+
+/// Replays a recorded CSV or JSONL file through the same field mapping as live feeds
+pub struct ReplaySource {
+    config: FeedConfig,
+    path: PathBuf,
+    speed: f64,
+    repeat: bool,
+}
+
+impl ReplaySource {
+    fn load_records(path: &Path) -> Result<Vec<Value>, FeedError> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "csv" => {
+                let mut rdr = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path)?;
+                let headers = rdr.headers()?.clone();
+                let mut records = Vec::new();
+                for row in rdr.records() {
+                    let row = row?;
+                    let obj: serde_json::Map<String, Value> = headers
+                        .iter()
+                        .zip(row.iter())
+                        .map(|(h, v)| (h.to_string(), Value::String(v.to_string())))
+                        .collect();
+                    records.push(Value::Object(obj));
+                }
+                Ok(records)
+            }
+            "jsonl" | "ndjson" => {
+                let reader = BufReader::new(fs::File::open(path)?);
+                let mut records = Vec::new();
+                for (i, line) in reader.lines().enumerate() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let value = serde_json::from_str(&line).map_err(|source| FeedError::Json { line: i + 1, source })?;
+                    records.push(value);
+                }
+                Ok(records)
+            }
+            _ => Err(FeedError::UnsupportedReplay(path.to_string_lossy().to_string())),
+        }
+    }
+}
+
+impl MarketDataSource for ReplaySource {
+    fn run(
+        &mut self,
+        stop: &AtomicBool,
+        emit: &mut dyn FnMut(MarketEvent),
+        _report: &mut dyn FnMut(&FeedError),
+    ) -> Result<(), FeedError> {
+        let records = Self::load_records(&self.path)?;
+        loop {
+            let mut prev_time: Option<i64> = None;
+            for record in &records {
+                for event in map_message(&self.config, record) {
+                    if stop.load(Ordering::SeqCst) {
+                        return Ok(());
+                    }
+                    if let Some(delay) = prev_time.and_then(|prev| replay_delay(event.time() - prev, self.speed)) {
+                        sleep_unless_stopped(stop, delay);
+                    }
+                    prev_time = Some(event.time());
+                    emit(event);
+                }
+            }
+            // Nothing mapped: looping would spin without ever emitting
+            if prev_time.is_none() {
+                return Err(FeedError::EmptyReplay(self.path.to_string_lossy().to_string()));
+            }
+            if !self.repeat || stop.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            sleep_unless_stopped(stop, REPLAY_LOOP_PAUSE);
+        }
+    }
+}
+
+/// Pause before a record `gap_ms` after the previous one, None for no pause
+fn replay_delay(gap_ms: i64, speed: f64) -> Option<Duration> {
+    if gap_ms <= 0 || speed.is_nan() || speed <= 0.0 {
+        return None;
+    }
+    let delay = Duration::try_from_secs_f64(gap_ms as f64 / 1000.0 / speed).unwrap_or(MAX_REPLAY_GAP);
+    Some(delay.min(MAX_REPLAY_GAP))
+}
+
+/// Build the source described by a feed config
+pub fn create_source(config: &FeedConfig) -> Result<Box<dyn MarketDataSource>, FeedError> {
+    config.validate()?;
+    Ok(match &config.source {
+        FeedSource::WebSocket { url, subscribe } => Box::new(WebSocketSource {
+            config: config.clone(),
+            url: url.clone(),
+            subscribe: subscribe.clone(),
+        }),
+        FeedSource::Rest { url, poll_ms } => Box::new(RestSource {
+            config: config.clone(),
+            url: url.clone(),
+            poll: Duration::from_millis((*poll_ms).max(250)),
+        }),
+        FeedSource::Replay { path, speed, repeat } => Box::new(ReplaySource {
+            config: config.clone(),
+            path: PathBuf::from(path),
+            speed: *speed,
+            repeat: *repeat,
+        }),
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedStatus {
+    pub running: bool,
+    pub events: u64,
+    pub last: Option<MarketEvent>,
+    /// Latest price per symbol (uppercased)
+    pub prices: BTreeMap<String, f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedInfo {
+    pub config: FeedConfig,
+    pub status: FeedStatus,
+}
+
+/// Handle to a feed running on its own thread
+pub struct FeedHandle {
+    config: FeedConfig,
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<FeedStatus>>,
+}
+
+impl FeedHandle {
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    pub fn info(&self) -> FeedInfo {
+        FeedInfo {
+            config: self.config.clone(),
+            status: self.status.lock().unwrap().clone(),
+        }
+    }
+
+    /// Latest price seen for `symbol` (compared case-insensitively)
+    pub fn last_price(&self, symbol: &str) -> Option<f64> {
+        self.status.lock().unwrap().prices.get(&symbol.to_uppercase()).copied()
+    }
+}
+
+/// Run a source on a background thread, calling `on_event` for each event
+/// and `on_error` for transient and fatal errors
+pub fn spawn_feed(
+    config: FeedConfig,
+    mut source: Box<dyn MarketDataSource>,
+    on_event: impl Fn(&MarketEvent) + Send + 'static,
+    on_error: impl Fn(&str) + Send + 'static,
+) -> FeedHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let status = Arc::new(Mutex::new(FeedStatus {
+        running: true,
+        ..Default::default()
+    }));
+
+    let thread_stop = stop.clone();
+    let thread_status = status.clone();
+    std::thread::spawn(move || {
+        let mut emit = |event: MarketEvent| {
+            on_event(&event);
+            let mut st = thread_status.lock().unwrap();
+            st.events += 1;
+            st.error = None;
+            st.prices.insert(event.symbol().to_uppercase(), event.price());
+            st.last = Some(event);
+        };
+        let mut report = |e: &FeedError| {
+            let msg = e.to_string();
+            on_error(&msg);
+            thread_status.lock().unwrap().error = Some(msg);
+        };
+        let result = source.run(&thread_stop, &mut emit, &mut report);
+        if let Err(e) = &result {
+            report(e);
+        }
+        thread_status.lock().unwrap().running = false;
+    });
+
+    FeedHandle { config, stop, status }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TRADES_CSV: &str = include_str!("../tests/fixtures/market_data/trades.csv");
+    const KLINES_JSONL: &str = include_str!("../tests/fixtures/market_data/klines.jsonl");
+
+    fn feed(source: FeedSource, mapping: FieldMapping) -> FeedConfig {
+        FeedConfig { name: "test".to_string(), symbol: "DEFAULT".to_string(), interval: Some("1m".to_string()), source, mapping }
+    }
+
+    fn replay(path: &Path, speed: f64, repeat: bool) -> FeedSource {
+        FeedSource::Replay { path: path.to_string_lossy().to_string(), speed, repeat }
+    }
+
+    fn trade_mapping() -> FieldMapping {
+        FieldMapping {
+            price: "price".to_string(),
+            size: Some("qty".to_string()),
+            time: Some("time".to_string()),
+            symbol: Some("symbol".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn kline_mapping() -> FieldMapping {
+        FieldMapping {
+            price: "/k/c".to_string(),
+            time: Some("/k/t".to_string()),
+            time_unit: TimeUnit::Seconds,
+            symbol: Some("s".to_string()),
+            open: Some("k/o".to_string()),
+            high: Some("k/h".to_string()),
+            low: Some("k/l".to_string()),
+            volume: Some("k/v".to_string()),
+            closed: Some("k/x".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Write a fixture to a temp file with the extension the replay source expects
+    fn fixture_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("quanthud_test_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn run_source(config: &FeedConfig) -> Result<Vec<MarketEvent>, FeedError> {
+        let mut source = create_source(config)?;
+        let stop = AtomicBool::new(false);
+        let mut events = Vec::new();
+        source.run(&stop, &mut |e| events.push(e), &mut |_| {})?;
+        Ok(events)
+    }
+
+    #[test]
+    fn maps_ticks_with_string_numbers_and_symbol_fallback() {
+        let config = feed(replay(Path::new("x.csv"), 1.0, false), FieldMapping {
+            items: Some("data".to_string()),
+            price: "p".to_string(),
+            size: Some("q".to_string()),
+            time: Some("/T".to_string()),
+            ..Default::default()
+        });
+        let message = json!({ "data": [
+            { "p": "3151.25", "q": 0.5, "T": 1709301900000_i64 },
+            { "p": "NaN", "q": 1, "T": 1709301900001_i64 },
+            { "q": 1, "T": 1709301900002_i64 },
+        ]});
+
+        let events = map_message(&config, &message);
+        assert_eq!(events, vec![MarketEvent::Tick(Tick {
+            feed: "test".to_string(),
+            symbol: "DEFAULT".to_string(),
+            price: 3151.25,
+            size: Some(0.5),
+            time: 1709301900000,
+            id: None,
+        })]);
+    }
+
+    #[test]
+    fn maps_array_rows_by_index() {
+        // Kraken-style [price, volume, time] arrays
+        let config = feed(replay(Path::new("x.csv"), 1.0, false), FieldMapping {
+            items: Some("/result/XXBTZUSD".to_string()),
+            price: "/0".to_string(),
+            size: Some("/1".to_string()),
+            time: Some("/2".to_string()),
+            time_unit: TimeUnit::Seconds,
+            ..Default::default()
+        });
+        let message = json!({ "result": { "XXBTZUSD": [["60000.1", "0.01", 1709301900.5]] } });
+
+        let events = map_message(&config, &message);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].price(), 60000.1);
+        assert_eq!(events[0].time(), 1709301900500);
+        assert!(map_message(&config, &json!({ "result": {} })).is_empty());
+    }
+
+    #[test]
+    fn maps_candles_when_ohl_are_set() {
+        let config = feed(replay(Path::new("x.jsonl"), 1.0, false), kline_mapping());
+        let message: Value = serde_json::from_str(KLINES_JSONL.lines().next().unwrap()).unwrap();
+
+        let events = map_message(&config, &message);
+        assert_eq!(events, vec![MarketEvent::Candle(Candle {
+            feed: "test".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            interval: Some("1m".to_string()),
+            open: 59990.0,
+            high: 60010.0,
+            low: 59980.5,
+            close: 60000.0,
+            volume: Some(12.5),
+            time: 1709301840000,
+            closed: false,
+        })]);
+    }
+
+    #[test]
+    fn replays_csv_and_skips_unmappable_rows() {
+        let path = fixture_file("trades.csv", TRADES_CSV);
+        let events = run_source(&feed(replay(&path, 0.0, false), trade_mapping())).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let summary: Vec<(&str, f64, Option<f64>)> = events
+            .iter()
+            .map(|e| match e {
+                MarketEvent::Tick(t) => (t.symbol.as_str(), t.price, t.size),
+                MarketEvent::Candle(_) => panic!("expected ticks"),
+            })
+            .collect();
+        assert_eq!(summary, vec![("BTCUSDT", 60000.5, Some(0.25)), ("BTCUSDT", 60001.0, Some(0.1)), ("ETHUSDT", 3000.25, None)]);
+    }
+
+    #[test]
+    fn replays_jsonl_at_speed_and_skips_blank_lines() {
+        let path = fixture_file("klines.jsonl", KLINES_JSONL);
+        // The 60 s recorded gap at 6000x speed is a 10 ms pause
+        let started = Instant::now();
+        let events = run_source(&feed(replay(&path, 6000.0, false), kline_mapping())).unwrap();
+        let elapsed = started.elapsed();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[1], MarketEvent::Candle(c) if c.closed && c.close == 60015.0));
+        assert!(elapsed >= Duration::from_millis(10) && elapsed < Duration::from_secs(5), "{elapsed:?}");
+    }
+
+    #[test]
+    fn replay_reports_bad_json_line_and_extension() {
+        let path = fixture_file("broken.jsonl", "{\"p\": 1}\nnot json\n");
+        let err = run_source(&feed(replay(&path, 0.0, false), trade_mapping())).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(err, FeedError::Json { line: 2, .. }));
+
+        let err = run_source(&feed(replay(Path::new("ticks.parquet"), 0.0, false), trade_mapping())).unwrap_err();
+        assert!(matches!(err, FeedError::UnsupportedReplay(_)));
+    }
+
+    #[test]
+    fn looping_replay_runs_until_stopped() {
+        let path = fixture_file("loop.csv", TRADES_CSV);
+        let mut source = create_source(&feed(replay(&path, 0.0, true), trade_mapping())).unwrap();
+        let stop = AtomicBool::new(false);
+        let mut count = 0;
+        source
+            .run(
+                &stop,
+                &mut |_| {
+                    count += 1;
+                    if count == 7 {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                },
+                &mut |_| {},
+            )
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(count, 7);
+    }
+
+    #[test]
+    fn rejects_invalid_replay_speed() {
+        for speed in [-1.0, f64::NAN, f64::INFINITY] {
+            let config = feed(replay(Path::new("x.csv"), speed, false), trade_mapping());
+            assert!(matches!(create_source(&config), Err(FeedError::InvalidSpeed(_))), "{speed}");
+        }
+        assert!(feed(replay(Path::new("x.csv"), 0.0, false), trade_mapping()).validate().is_ok());
+    }
+
+    #[test]
+    fn replay_delay_scales_and_caps_gaps() {
+        assert_eq!(replay_delay(1000, 2.0), Some(Duration::from_millis(500)));
+        assert_eq!(replay_delay(0, 1.0), None);
+        assert_eq!(replay_delay(-500, 1.0), None);
+        assert_eq!(replay_delay(1000, 0.0), None);
+        assert_eq!(replay_delay(1000, f64::NAN), None);
+        assert_eq!(replay_delay(i64::MAX, 1e-300), Some(MAX_REPLAY_GAP));
+        assert_eq!(replay_delay(86_400_000, 1.0), Some(MAX_REPLAY_GAP));
+    }
+
+    #[test]
+    fn looping_replay_pauses_between_passes() {
+        let path = fixture_file("loop_pause.csv", TRADES_CSV);
+        let mut source = create_source(&feed(replay(&path, 0.0, true), trade_mapping())).unwrap();
+        let stop = AtomicBool::new(false);
+        let mut count = 0;
+        let started = Instant::now();
+        // Three ticks per pass: the seventh is on the third pass, after two pauses
+        source
+            .run(
+                &stop,
+                &mut |_| {
+                    count += 1;
+                    if count == 7 {
+                        stop.store(true, Ordering::SeqCst);
+                    }
+                },
+                &mut |_| {},
+            )
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(started.elapsed() >= REPLAY_LOOP_PAUSE * 2, "{:?}", started.elapsed());
+    }
+
+    #[test]
+    fn replay_with_no_mappable_record_is_an_error() {
+        let path = fixture_file("unmapped.csv", TRADES_CSV);
+        let mapping = FieldMapping { price: "last".to_string(), ..Default::default() };
+        for repeat in [false, true] {
+            let err = run_source(&feed(replay(&path, 0.0, repeat), mapping.clone())).unwrap_err();
+            assert!(matches!(err, FeedError::EmptyReplay(_)), "{err}");
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    fn tick(time: i64, price: f64, id: Option<&str>) -> MarketEvent {
+        MarketEvent::Tick(Tick {
+            feed: "test".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price,
+            size: Some(1.0),
+            time,
+            id: id.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn seen_trades_keeps_new_trades_in_the_same_millisecond() {
+        let mut seen = SeenTrades::default();
+        // Newest first, as some endpoints return them
+        assert_eq!(seen.fresh(vec![tick(1001, 2.0, Some("2")), tick(1000, 1.0, Some("1"))]).len(), 2);
+        let next = seen.fresh(vec![tick(1000, 1.0, Some("1")), tick(1001, 2.0, Some("2")), tick(1001, 2.0, Some("3"))]);
+        assert_eq!(next, vec![tick(1001, 2.0, Some("3"))]);
+        assert!(seen.fresh(vec![tick(1001, 2.0, Some("2")), tick(1001, 2.0, Some("3"))]).is_empty());
+
+        // Without ids the trade itself is the key
+        let mut seen = SeenTrades::default();
+        seen.fresh(vec![tick(1000, 1.0, None)]);
+        assert_eq!(seen.fresh(vec![tick(1000, 1.0, None), tick(1000, 1.5, None)]), vec![tick(1000, 1.5, None)]);
+    }
+
+    /// Wait for `count` events or `timeout`
+    fn collect(rx: &std::sync::mpsc::Receiver<MarketEvent>, count: usize, timeout: Duration) -> Vec<MarketEvent> {
+        let deadline = Instant::now() + timeout;
+        let mut events = Vec::new();
+        while events.len() < count {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(event) => events.push(event),
+                Err(_) => break,
+            }
+        }
+        events
+    }
+
+    fn wait_until_stopped(handle: &FeedHandle) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while handle.info().status.running {
+            assert!(Instant::now() < deadline, "feed did not stop");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn websocket_feed_subscribes_maps_and_reconnects() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (sub_tx, sub_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().take(2).enumerate() {
+                let mut ws = tungstenite::accept(stream.unwrap()).unwrap();
+                sub_tx.send(ws.read().unwrap().into_text().unwrap().to_string()).unwrap();
+                ws.send(Message::text("pong")).unwrap();
+                let trade = json!({ "data": [{ "s": "BTCUSDT", "p": format!("{}.5", 60000 + i), "q": "0.1", "T": 1709301900000_i64 + i as i64 }] });
+                ws.send(Message::text(trade.to_string())).unwrap();
+                if i == 0 {
+                    // Drop without a close frame, like a lost connection
+                    continue;
+                }
+                while ws.read().is_ok() {}
+            }
+        });
+
+        let config = feed(
+            FeedSource::WebSocket { url, subscribe: Some(json!({ "method": "SUBSCRIBE", "params": ["btcusdt@trade"] })) },
+            FieldMapping {
+                items: Some("data".to_string()),
+                price: "p".to_string(),
+                size: Some("q".to_string()),
+                time: Some("T".to_string()),
+                symbol: Some("s".to_string()),
+                ..Default::default()
+            },
+        );
+        let (tx, rx) = std::sync::mpsc::channel();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let sink = errors.clone();
+        let handle = spawn_feed(
+            config.clone(),
+            create_source(&config).unwrap(),
+            move |e| tx.send(e.clone()).unwrap(),
+            move |e| sink.lock().unwrap().push(e.to_string()),
+        );
+
+        let events = collect(&rx, 2, Duration::from_secs(10));
+        handle.stop();
+        wait_until_stopped(&handle);
+
+        let subscribe = r#"{"method":"SUBSCRIBE","params":["btcusdt@trade"]}"#;
+        assert_eq!(sub_rx.try_iter().collect::<Vec<_>>(), vec![subscribe, subscribe]);
+        let prices: Vec<(String, f64, i64)> = events.iter().map(|e| (e.symbol().to_string(), e.price(), e.time())).collect();
+        assert_eq!(prices, vec![
+            ("BTCUSDT".to_string(), 60000.5, 1709301900000),
+            ("BTCUSDT".to_string(), 60001.5, 1709301900001),
+        ]);
+        assert_eq!(errors.lock().unwrap().len(), 1, "the dropped connection is reported once");
+        assert_eq!(handle.last_price("btcusdt"), Some(60001.5));
+        assert_eq!(handle.info().status.events, 2);
+    }
+
+    #[test]
+    fn rest_feed_forwards_each_trade_once() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/trades", listener.local_addr().unwrap());
+        let polls = [
+            r#"[{"id":1,"p":"100","T":1000},{"id":2,"p":"101","T":1000}]"#,
+            r#"[{"id":2,"p":"101","T":1000},{"id":3,"p":"102","T":1000},{"id":4,"p":"103","T":1001}]"#,
+        ];
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let body = polls[i.min(polls.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let config = feed(FeedSource::Rest { url, poll_ms: 250 }, FieldMapping {
+            // The response is a top-level array
+            items: Some(String::new()),
+            price: "p".to_string(),
+            time: Some("T".to_string()),
+            id: Some("id".to_string()),
+            ..Default::default()
+        });
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = spawn_feed(config.clone(), create_source(&config).unwrap(), move |e| tx.send(e.clone()).unwrap(), |_| {});
+
+        let mut events = collect(&rx, 4, Duration::from_secs(10));
+        // A third poll repeats the second response and must add nothing
+        events.extend(collect(&rx, 1, Duration::from_millis(600)));
+        handle.stop();
+        wait_until_stopped(&handle);
+
+        let ids: Vec<Option<String>> = events
+            .iter()
+            .map(|e| match e {
+                MarketEvent::Tick(t) => t.id.clone(),
+                MarketEvent::Candle(_) => panic!("expected ticks"),
+            })
+            .collect();
+        assert_eq!(ids, ["1", "2", "3", "4"].map(|id| Some(id.to_string())));
+        assert_eq!(handle.last_price("DEFAULT"), Some(103.0));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::layout::Rect;
+use serde::Serialize;
+
+/// A connected monitor as shown to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    /// Stable fingerprint, see `fingerprint`
+    pub id: String,
+    pub index: usize,
+    /// "Display N (W×H)" label for the settings dropdown
+    pub name: String,
+    /// Name reported by the OS, e.g. `\\.\DISPLAY2` or `DP-1`
+    pub device_name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    pub fn new(index: usize, device_name: Option<String>, bounds: Rect, scale_factor: f64, is_primary: bool) -> Self {
+        Self {
+            id: fingerprint(device_name.as_deref(), &bounds),
+            index,
+            name: format!("Display {} ({}×{})", index + 1, bounds.width, bounds.height),
+            device_name,
+            x: bounds.x,
+            y: bounds.y,
+            width: bounds.width as u32,
+            height: bounds.height as u32,
+            scale_factor,
+            is_primary,
+        }
+    }
+}
+
+/// `name|WxH|x,y`. Survives re-enumeration as long as the OS keeps the
+/// monitor's name; `find` relaxes the position and then the resolution
+/// when a dock or a resolution change moves things around.
+pub fn fingerprint(device_name: Option<&str>, bounds: &Rect) -> String {
+    format!("{}|{}", identity_prefix(device_name, bounds), position_key(bounds))
+}
+
+/// Name key of monitors the OS doesn't name
+const UNNAMED: &str = "display";
+
+fn name_key(device_name: Option<&str>) -> String {
+    device_name.unwrap_or(UNNAMED).replace('|', "/")
+}
+
+fn identity_prefix(device_name: Option<&str>, bounds: &Rect) -> String {
+    format!("{}|{}x{}", name_key(device_name), bounds.width, bounds.height)
+}
+
+fn position_key(bounds: &Rect) -> String {
+    format!("{},{}", bounds.x, bounds.y)
+}
+
+/// Index of the monitor best matching a saved id: exact fingerprint, then
+/// same name and resolution, then same name, then same position. Unnamed
+/// monitors all share one name, so for them position comes before resolution
+/// and the name alone decides nothing.
+pub fn find(monitors: &[MonitorInfo], id: &str) -> Option<usize> {
+    let mut parts = id.rsplitn(3, '|');
+    let (position, resolution, name) = (parts.next()?, parts.next()?, parts.next()?);
+    let bounds = |m: &MonitorInfo| Rect { x: m.x, y: m.y, width: m.width as i32, height: m.height as i32 };
+
+    let exact = monitors.iter().position(|m| m.id == id);
+    let same_model = || {
+        monitors.iter().position(|m| {
+            identity_prefix(m.device_name.as_deref(), &bounds(m)) == format!("{}|{}", name, resolution)
+        })
+    };
+    let same_name = || monitors.iter().position(|m| name_key(m.device_name.as_deref()) == name);
+    let same_position = || monitors.iter().position(|m| position_key(&bounds(m)) == position);
+    if name == UNNAMED {
+        return exact.or_else(same_position).or_else(same_model);
+    }
+    exact.or_else(same_model).or_else(same_name).or_else(same_position)
+}
+
+/// Index of the primary monitor, or the first one
+pub fn primary(monitors: &[MonitorInfo]) -> Option<usize> {
+    monitors.iter().position(|m| m.is_primary).or((!monitors.is_empty()).then_some(0))
+}
+
+/// Changes whenever a monitor is added, removed, moved, resized or rescaled
+pub fn signature(monitors: &[MonitorInfo]) -> String {
+    monitors
+        .iter()
+        .map(|m| format!("{}@{}{}", m.id, m.scale_factor, if m.is_primary { "*" } else { "" }))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(index: usize, name: Option<&str>, x: i32, y: i32, width: i32, height: i32) -> MonitorInfo {
+        MonitorInfo::new(index, name.map(str::to_string), Rect { x, y, width, height }, 1.0, index == 0)
+    }
+
+    #[test]
+    fn fingerprint_combines_name_resolution_and_position() {
+        assert_eq!(fingerprint(Some(r"\\.\DISPLAY2"), &Rect { x: -2560, y: 0, width: 2560, height: 1440 }), r"\\.\DISPLAY2|2560x1440|-2560,0");
+        assert_eq!(fingerprint(Some("odd|name"), &Rect { x: 0, y: 0, width: 1920, height: 1080 }), "odd/name|1920x1080|0,0");
+        assert_eq!(fingerprint(None, &Rect { x: 0, y: 0, width: 1920, height: 1080 }), "display|1920x1080|0,0");
+    }
+
+    #[test]
+    fn find_follows_a_monitor_across_dock_and_undock() {
+        let docked = [monitor(0, Some("eDP-1"), 0, 0, 1920, 1080), monitor(1, Some("DP-1"), 1920, 0, 2560, 1440)];
+        let external = docked[1].id.clone();
+        assert_eq!(find(&docked, &external), Some(1));
+
+        // Undocked: the saved monitor is gone and nothing sits where it was
+        let undocked = [monitor(0, Some("eDP-1"), 0, 0, 1920, 1080)];
+        assert_eq!(find(&undocked, &external), None);
+        assert_eq!(primary(&undocked), Some(0));
+
+        // Re-docked on the other side and enumerated first
+        let redocked = [monitor(0, Some("DP-1"), -2560, 0, 2560, 1440), monitor(1, Some("eDP-1"), 0, 0, 1920, 1080)];
+        assert_eq!(find(&redocked, &external), Some(0));
+    }
+
+    #[test]
+    fn find_keeps_the_monitor_after_a_resolution_change() {
+        let before = [monitor(0, Some("eDP-1"), 0, 0, 1920, 1080), monitor(1, Some("DP-1"), 1920, 0, 2560, 1440)];
+        let after = [monitor(0, Some("eDP-1"), 0, 0, 1920, 1080), monitor(1, Some("DP-1"), 1920, 0, 3840, 2160)];
+        assert_eq!(find(&after, &before[1].id), Some(1));
+    }
+
+    #[test]
+    fn find_prefers_the_name_over_a_swapped_position() {
+        let before = [monitor(0, Some("DP-1"), 0, 0, 1920, 1080), monitor(1, Some("HDMI-1"), 1920, 0, 1920, 1080)];
+        let swapped = [monitor(0, Some("HDMI-1"), 0, 0, 1920, 1080), monitor(1, Some("DP-1"), 1920, 0, 1920, 1080)];
+        assert_eq!(find(&swapped, &before[0].id), Some(1));
+        assert_eq!(find(&swapped, &before[1].id), Some(0));
+    }
+
+    #[test]
+    fn find_uses_position_for_unnamed_monitors() {
+        let before = [monitor(0, None, 0, 0, 1920, 1080), monitor(1, None, 1920, 0, 1920, 1080)];
+        let saved = before[1].id.clone();
+        assert_eq!(find(&before, &saved), Some(1));
+
+        // Every unnamed monitor shares the name key; position must decide
+        let rescaled = [monitor(0, None, 0, 0, 1920, 1080), monitor(1, None, 1920, 0, 2560, 1440)];
+        assert_eq!(find(&rescaled, &saved), Some(1));
+
+        // Moved but unchanged: resolution is the last hint
+        let moved = [monitor(0, None, 0, 0, 2560, 1440), monitor(1, None, 2560, 0, 1920, 1080)];
+        assert_eq!(find(&moved, &saved), Some(1));
+        assert_eq!(find(&moved, "display|1280x720|5000,0"), None);
+        assert_eq!(find(&moved, "garbage"), None);
+    }
+
+    #[test]
+    fn primary_falls_back_to_the_first_monitor() {
+        let mut monitors = vec![monitor(0, Some("DP-1"), 0, 0, 1920, 1080), monitor(1, Some("DP-2"), 1920, 0, 1920, 1080)];
+        monitors[0].is_primary = false;
+        monitors[1].is_primary = true;
+        assert_eq!(primary(&monitors), Some(1));
+        monitors[1].is_primary = false;
+        assert_eq!(primary(&monitors), Some(0));
+        assert_eq!(primary(&[]), None);
+    }
+
+    #[test]
+    fn signature_changes_with_scale_and_primary() {
+        let monitors = vec![monitor(0, Some("DP-1"), 0, 0, 1920, 1080)];
+        let mut rescaled = monitors.clone();
+        rescaled[0].scale_factor = 1.5;
+        let mut demoted = monitors.clone();
+        demoted[0].is_primary = false;
+        assert_eq!(signature(&monitors), "DP-1|1920x1080|0,0@1*");
+        assert_ne!(signature(&monitors), signature(&rescaled));
+        assert_ne!(signature(&monitors), signature(&demoted));
+    }
+}
@@ -0,0 +1,422 @@
+use crate::config::{self, ConfigError};
+use crate::sizing::{self, SizingError, SizingInputs};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PositionError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Sizing(#[from] SizingError),
+    #[error("Position size must be positive")]
+    InvalidSize,
+    #[error("No open position with id '{0}'")]
+    NotFound(String),
+}
+
+/// Where a position's current price comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PriceSource {
+    /// Last price of a running market data feed (defaults to the position's symbol)
+    Feed {
+        feed: String,
+        #[serde(default)]
+        symbol: Option<String>,
+    },
+    /// Last OCR reading of a running price watch
+    Watch { name: String },
+    /// Prices pushed by the frontend
+    Manual,
+}
+
+/// A position to start tracking. The size comes from the calculator's
+/// sizing math unless `position_size` overrides it. The position API is
+/// camelCase like the calculator's `sizing` inputs it carries.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenPositionRequest {
+    pub symbol: String,
+    pub is_long: bool,
+    pub entry: f64,
+    pub stop_loss: f64,
+    #[serde(default)]
+    pub targets: Vec<f64>,
+    pub sizing: SizingInputs,
+    /// Actual 1x notional, when the fill differs from the calculated size
+    #[serde(default)]
+    pub position_size: Option<f64>,
+    pub source: PriceSource,
+}
+
+/// Stored in open_positions.json; the aliases read files written before the
+/// format became camelCase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub id: String,
+    pub symbol: String,
+    #[serde(alias = "is_long")]
+    pub is_long: bool,
+    pub entry: f64,
+    #[serde(alias = "stop_loss")]
+    pub stop_loss: f64,
+    pub targets: Vec<f64>,
+    pub sizing: SizingInputs,
+    pub source: PriceSource,
+    /// 1x notional in quote currency
+    #[serde(alias = "position_size")]
+    pub position_size: f64,
+    pub quantity: f64,
+    /// Net loss at the initial stop; one R
+    pub risk: f64,
+    /// Unix milliseconds
+    #[serde(alias = "opened_at")]
+    pub opened_at: u64,
+    #[serde(default, alias = "last_price")]
+    pub last_price: Option<f64>,
+}
+
+impl Position {
+    fn pnl_at(&self, price: f64) -> f64 {
+        sizing::pnl_at(&self.sizing, self.is_long, self.entry, self.position_size, price)
+    }
+
+    fn r_at(&self, price: f64) -> f64 {
+        if self.risk > 0.0 { self.pnl_at(price) / self.risk } else { 0.0 }
+    }
+
+    fn level(&self, level: f64) -> LevelDistance {
+        let (distance, percent) = match self.last_price {
+            Some(price) if price > 0.0 => (Some(level - price), Some((level - price) / price * 100.0)),
+            _ => (None, None),
+        };
+        LevelDistance { price: level, distance, percent, r: self.r_at(level) }
+    }
+
+    pub fn snapshot(&self, now_ms: u64) -> PositionSnapshot {
+        PositionSnapshot {
+            id: self.id.clone(),
+            symbol: self.symbol.clone(),
+            is_long: self.is_long,
+            entry: self.entry,
+            price: self.last_price,
+            position_size: self.position_size,
+            quantity: self.quantity,
+            risk: self.risk,
+            unrealized_pnl: self.last_price.map(|p| self.pnl_at(p)),
+            r_multiple: self.last_price.map(|p| self.r_at(p)),
+            stop: self.level(self.stop_loss),
+            targets: self.targets.iter().map(|&t| self.level(t)).collect(),
+            time_in_trade_secs: now_ms.saturating_sub(self.opened_at) / 1000,
+        }
+    }
+}
+
+/// A stop or target relative to the current price
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelDistance {
+    pub price: f64,
+    /// Signed price distance from the current price to the level
+    pub distance: Option<f64>,
+    pub percent: Option<f64>,
+    /// R-multiple if closed at this level
+    pub r: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSnapshot {
+    pub id: String,
+    pub symbol: String,
+    pub is_long: bool,
+    pub entry: f64,
+    pub price: Option<f64>,
+    pub position_size: f64,
+    pub quantity: f64,
+    pub risk: f64,
+    pub unrealized_pnl: Option<f64>,
+    pub r_multiple: Option<f64>,
+    pub stop: LevelDistance,
+    pub targets: Vec<LevelDistance>,
+    pub time_in_trade_secs: u64,
+}
+
+fn positions_path() -> Result<PathBuf, PositionError> {
+    Ok(config::get_app_dir()?.join("open_positions.json"))
+}
+
+/// Open positions in the order they were opened
+#[derive(Debug, Default)]
+pub struct PositionBook {
+    positions: Vec<Position>,
+}
+
+impl PositionBook {
+    pub fn load() -> Result<Self, PositionError> {
+        Self::load_from(&positions_path()?)
+    }
+
+    /// An unreadable file is moved aside instead of being replaced by an
+    /// empty book on the next save
+    fn load_from(path: &Path) -> Result<Self, PositionError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        match serde_json::from_str(&fs::read_to_string(path)?) {
+            Ok(positions) => Ok(Self { positions }),
+            Err(e) => {
+                let moved = config::move_aside(path)?;
+                eprintln!("Unreadable open positions moved to {}: {}", moved.display(), e);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), PositionError> {
+        fs::write(positions_path()?, serde_json::to_string_pretty(&self.positions)?)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn open(&mut self, request: OpenPositionRequest, now_ms: u64) -> Result<&Position, PositionError> {
+        let tp = request.targets.first().copied().unwrap_or(request.entry);
+        let sized = sizing::calculate(&request.sizing, request.is_long, request.entry, tp, request.stop_loss)?;
+        let position_size = request.position_size.unwrap_or(sized.pos_size_1x);
+        if position_size <= 0.0 || !position_size.is_finite() {
+            return Err(PositionError::InvalidSize);
+        }
+
+        let mut id = format!("pos-{}", now_ms);
+        let mut n = 1;
+        while self.positions.iter().any(|p| p.id == id) {
+            id = format!("pos-{}-{}", now_ms, n);
+            n += 1;
+        }
+
+        let mut position = Position {
+            id,
+            symbol: request.symbol.trim().to_uppercase(),
+            is_long: request.is_long,
+            entry: request.entry,
+            stop_loss: request.stop_loss,
+            targets: request.targets,
+            sizing: request.sizing,
+            source: request.source,
+            position_size,
+            quantity: position_size / request.entry,
+            risk: 0.0,
+            opened_at: now_ms,
+            last_price: None,
+        };
+        position.risk = -position.pnl_at(position.stop_loss);
+        self.positions.push(position);
+        Ok(self.positions.last().unwrap())
+    }
+
+    pub fn close(&mut self, id: &str) -> Result<Position, PositionError> {
+        let index = self
+            .positions
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| PositionError::NotFound(id.to_string()))?;
+        Ok(self.positions.remove(index))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut Position, PositionError> {
+        self.positions
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| PositionError::NotFound(id.to_string()))
+    }
+
+    /// Move the stop or targets. R stays measured against the initial risk.
+    pub fn update_levels(&mut self, id: &str, stop_loss: Option<f64>, targets: Option<Vec<f64>>) -> Result<&Position, PositionError> {
+        let position = self.get_mut(id)?;
+        if let Some(stop) = stop_loss {
+            position.stop_loss = stop;
+        }
+        if let Some(targets) = targets {
+            position.targets = targets;
+        }
+        Ok(position)
+    }
+
+    pub fn set_price(&mut self, id: &str, price: f64) -> Result<(), PositionError> {
+        self.get_mut(id)?.last_price = Some(price);
+        Ok(())
+    }
+
+    /// Pull the latest price for every non-manual position.
+    /// `lookup` gets the source and the position's symbol.
+    pub fn refresh_prices(&mut self, mut lookup: impl FnMut(&PriceSource, &str) -> Option<f64>) {
+        for position in &mut self.positions {
+            if position.source == PriceSource::Manual {
+                continue;
+            }
+            if let Some(price) = lookup(&position.source, &position.symbol) {
+                position.last_price = Some(price);
+            }
+        }
+    }
+
+    pub fn snapshots(&self, now_ms: u64) -> Vec<PositionSnapshot> {
+        self.positions.iter().map(|p| p.snapshot(now_ms)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sizing::FeeType;
+
+    fn request(targets: Vec<f64>, source: PriceSource) -> OpenPositionRequest {
+        OpenPositionRequest {
+            symbol: " btcusdt ".to_string(),
+            is_long: true,
+            entry: 60000.0,
+            stop_loss: 58800.0,
+            targets,
+            sizing: SizingInputs {
+                capital: 10000.0,
+                risk_percent: 1.0,
+                leverage: 5.0,
+                maker_fee: 0.02,
+                taker_fee: 0.06,
+                entry_fee_type: FeeType::Maker,
+                exit_fee_type: FeeType::Taker,
+            },
+            position_size: None,
+            source,
+        }
+    }
+
+    fn close_to(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn open_sizes_like_the_calculator_and_measures_r_from_the_stop() {
+        let mut book = PositionBook::default();
+        let position = book.open(request(vec![63000.0], PriceSource::Manual), 1_000).unwrap();
+        let sized = sizing::calculate(&position.sizing, true, 60000.0, 63000.0, 58800.0).unwrap();
+
+        assert_eq!(position.symbol, "BTCUSDT");
+        assert_eq!(position.position_size, sized.pos_size_1x);
+        assert!(close_to(position.risk, sized.net_loss));
+        assert!(close_to(position.r_at(58800.0), -1.0));
+        assert!(close_to(position.pnl_at(63000.0), sized.net_profit));
+        assert!(close_to(position.r_at(63000.0), sized.rr_ratio));
+    }
+
+    #[test]
+    fn snapshot_reports_pnl_r_and_distances_from_the_last_price() {
+        let mut book = PositionBook::default();
+        let id = book.open(request(vec![63000.0, 66000.0], PriceSource::Manual), 1_000).unwrap().id.clone();
+        assert!(book.snapshots(2_000)[0].unrealized_pnl.is_none());
+
+        book.set_price(&id, 61200.0).unwrap();
+        let snap = &book.snapshots(91_500)[0];
+        // 4807.69 * (1200 / 60000 - 0.08% fees)
+        assert!(close_to(snap.unrealized_pnl.unwrap(), 92.3076923076923));
+        assert!(close_to(snap.r_multiple.unwrap(), 0.923076923076923));
+        assert!(close_to(snap.stop.distance.unwrap(), -2400.0));
+        assert!(close_to(snap.stop.percent.unwrap(), -2400.0 / 61200.0 * 100.0));
+        assert!(close_to(snap.stop.r, -1.0));
+        assert!(close_to(snap.targets[0].distance.unwrap(), 1800.0));
+        assert!(close_to(snap.targets[1].r, 4.769230769230769));
+        assert_eq!(snap.time_in_trade_secs, 90);
+    }
+
+    #[test]
+    fn moving_the_stop_keeps_the_initial_risk() {
+        let mut book = PositionBook::default();
+        let id = book.open(request(vec![63000.0], PriceSource::Manual), 1_000).unwrap().id.clone();
+        let position = book.update_levels(&id, Some(60000.0), None).unwrap();
+        assert!(close_to(position.risk, 100.0));
+        // Stop at entry only loses the fees
+        assert!(close_to(position.snapshot(1_000).stop.r, -0.03846153846153846));
+        assert!(matches!(book.close("pos-missing"), Err(PositionError::NotFound(_))));
+        assert_eq!(book.close(&id).unwrap().id, id);
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn open_rejects_bad_sizes_and_gives_unique_ids() {
+        let mut book = PositionBook::default();
+        let mut bad = request(vec![], PriceSource::Manual);
+        bad.position_size = Some(0.0);
+        assert!(matches!(book.open(bad, 1_000), Err(PositionError::InvalidSize)));
+
+        let first = book.open(request(vec![], PriceSource::Manual), 1_000).unwrap().id.clone();
+        let second = book.open(request(vec![], PriceSource::Manual), 1_000).unwrap().id.clone();
+        assert_eq!((first.as_str(), second.as_str()), ("pos-1000", "pos-1000-1"));
+    }
+
+    #[test]
+    fn refresh_prices_skips_manual_positions() {
+        let mut book = PositionBook::default();
+        book.open(request(vec![], PriceSource::Manual), 1_000).unwrap();
+        book.open(request(vec![], PriceSource::Watch { name: "btc".to_string() }), 1_000).unwrap();
+        book.refresh_prices(|_, symbol| (symbol == "BTCUSDT").then_some(61000.0));
+        let prices: Vec<Option<f64>> = book.snapshots(1_000).iter().map(|s| s.price).collect();
+        assert_eq!(prices, vec![None, Some(61000.0)]);
+    }
+
+    #[test]
+    fn api_is_camel_case_throughout() {
+        let json = r#"{"symbol":"ETHUSDT","isLong":false,"entry":3000,"stopLoss":3060,"targets":[2850],
+            "sizing":{"capital":10000,"riskPercent":1,"leverage":5,"makerFee":0.02,"takerFee":0.06,"entryFeeType":"taker","exitFeeType":"taker"},
+            "positionSize":5000,"source":{"kind":"feed","feed":"binance"}}"#;
+        let parsed: OpenPositionRequest = serde_json::from_str(json).unwrap();
+        assert_eq!((parsed.is_long, parsed.stop_loss, parsed.position_size), (false, 3060.0, Some(5000.0)));
+
+        let mut book = PositionBook::default();
+        book.open(parsed, 1_000).unwrap();
+        let snapshot = serde_json::to_value(&book.snapshots(1_000)[0]).unwrap();
+        for key in ["isLong", "positionSize", "unrealizedPnl", "rMultiple", "timeInTradeSecs"] {
+            assert!(snapshot.get(key).is_some(), "{key}");
+        }
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("quanthud_positions_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn load_from_reads_snake_case_files_and_moves_bad_ones_aside() {
+        let old = temp_file("old.json");
+        let mut book = PositionBook::default();
+        book.open(request(vec![63000.0], PriceSource::Manual), 1_000).unwrap();
+        let legacy = serde_json::to_string(&book.positions).unwrap().replace("isLong", "is_long").replace("stopLoss", "stop_loss");
+        fs::write(&old, legacy).unwrap();
+        let loaded = PositionBook::load_from(&old).unwrap();
+        assert_eq!(loaded.positions[0].stop_loss, 58800.0);
+        let _ = fs::remove_file(&old);
+
+        let bad = temp_file("bad.json");
+        fs::write(&bad, "[{\"id\": \"pos-1\",").unwrap();
+        assert!(PositionBook::load_from(&bad).unwrap().is_empty());
+        assert!(!bad.exists());
+        let prefix = format!("{}.bad-", bad.file_name().unwrap().to_string_lossy());
+        let moved: Vec<PathBuf> = fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(&prefix)))
+            .collect();
+        assert_eq!(moved.len(), 1);
+        let _ = fs::remove_file(&moved[0]);
+    }
+}
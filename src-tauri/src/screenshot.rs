@@ -0,0 +1,269 @@
+use crate::capture::{self, CaptureError};
+use crate::config::{self, ConfigError};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScreenshotError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Capture(#[from] CaptureError),
+    #[error("Failed to save image: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("Cannot find Pictures directory")]
+    NoPicturesDir,
+    #[error("No capture region named '{0}'")]
+    UnknownRegion(String),
+}
+
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "QuantHUD_{date}_{time}";
+
+/// What to capture
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ScreenshotTarget {
+    /// A whole monitor, by capture backend index
+    Monitor {
+        #[serde(default)]
+        index: usize,
+    },
+    /// A region [x, y, width, height] of the primary screen, e.g. from the region selector
+    Region { region: [i32; 4] },
+    /// A saved capture region
+    Named { name: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+impl ScreenshotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Jpeg => "jpg",
+        }
+    }
+}
+
+fn default_jpeg_quality() -> u8 {
+    90
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScreenshotOptions {
+    pub target: ScreenshotTarget,
+    #[serde(default)]
+    pub format: ScreenshotFormat,
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    /// Placeholders: {date}, {time}, {timestamp}, {target}
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    /// Overrides the configured screenshots folder
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub copy_to_clipboard: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedScreenshot {
+    pub path: String,
+    pub filename: String,
+    pub modified: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn regions_path() -> Result<PathBuf, ScreenshotError> {
+    Ok(config::get_app_dir()?.join("capture_regions.json"))
+}
+
+/// Saved capture regions by name
+pub fn load_regions() -> Result<BTreeMap<String, [i32; 4]>, ScreenshotError> {
+    let path = regions_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_region(name: &str, region: [i32; 4]) -> Result<(), ScreenshotError> {
+    let mut regions = load_regions()?;
+    regions.insert(name.to_string(), region);
+    fs::write(regions_path()?, serde_json::to_string_pretty(&regions)?)?;
+    Ok(())
+}
+
+pub fn delete_region(name: &str) -> Result<(), ScreenshotError> {
+    let mut regions = load_regions()?;
+    regions.remove(name);
+    fs::write(regions_path()?, serde_json::to_string_pretty(&regions)?)?;
+    Ok(())
+}
+
+/// The OS screenshots folder (Pictures/Screenshots)
+pub fn default_folder() -> Result<PathBuf, ScreenshotError> {
+    Ok(dirs::picture_dir().ok_or(ScreenshotError::NoPicturesDir)?.join("Screenshots"))
+}
+
+/// `custom` if non-empty, else the `screenshotsFolder` setting, else the OS default
+pub fn resolve_folder(custom: Option<&str>) -> Result<PathBuf, ScreenshotError> {
+    if let Some(folder) = custom.filter(|f| !f.is_empty()) {
+        return Ok(PathBuf::from(folder));
+    }
+    let configured = config::load_config()
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|v| v.get("screenshotsFolder")?.as_str().map(str::to_string))
+        .filter(|f| !f.is_empty());
+    match configured {
+        Some(folder) => Ok(PathBuf::from(folder)),
+        None => default_folder(),
+    }
+}
+
+fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control() { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').to_string();
+    if cleaned.is_empty() { "screenshot".to_string() } else { cleaned }
+}
+
+/// Render a filename template (without extension)
+pub fn render_filename(template: &str, time: &chrono::DateTime<chrono::Local>, target: &str) -> String {
+    let name = template
+        .replace("{date}", &time.format("%Y-%m-%d").to_string())
+        .replace("{time}", &time.format("%H-%M-%S").to_string())
+        .replace("{timestamp}", &time.timestamp_millis().to_string())
+        .replace("{target}", target);
+    sanitize_filename(&name)
+}
+
+/// First free `stem.ext`, `stem_1.ext`, `stem_2.ext`, ... in `folder`
+fn unique_path(folder: &Path, stem: &str, ext: &str) -> PathBuf {
+    let mut path = folder.join(format!("{}.{}", stem, ext));
+    let mut n = 1;
+    while path.exists() {
+        path = folder.join(format!("{}_{}.{}", stem, n, ext));
+        n += 1;
+    }
+    path
+}
+
+/// Capture the target; returns the image and a label for `{target}`
+pub fn capture_target(target: &ScreenshotTarget) -> Result<(RgbaImage, String), ScreenshotError> {
+    Ok(match target {
+        ScreenshotTarget::Monitor { index } => {
+            (capture::capture_screen_index(*index)?.image, format!("monitor{}", index + 1))
+        }
+        ScreenshotTarget::Region { region } => (capture::capture_image(Some(*region))?.to_rgba8(), "region".to_string()),
+        ScreenshotTarget::Named { name } => {
+            let region = *load_regions()?.get(name).ok_or_else(|| ScreenshotError::UnknownRegion(name.clone()))?;
+            (capture::capture_image(Some(region))?.to_rgba8(), name.clone())
+        }
+    })
+}
+
+/// Write an image into `folder` using the filename template
+pub fn save_image(
+    img: &RgbaImage,
+    folder: &Path,
+    options: &ScreenshotOptions,
+    target_label: &str,
+) -> Result<SavedScreenshot, ScreenshotError> {
+    fs::create_dir_all(folder)?;
+    let now = chrono::Local::now();
+    let template = options.filename_template.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+    let stem = render_filename(template, &now, target_label);
+    let path = unique_path(folder, &stem, options.format.extension());
+
+    match options.format {
+        ScreenshotFormat::Png => img.save_with_format(&path, ImageFormat::Png)?,
+        ScreenshotFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgba8(img.clone()).to_rgb8();
+            let file = fs::File::create(&path)?;
+            let mut encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(std::io::BufWriter::new(file), options.jpeg_quality.clamp(1, 100));
+            encoder.encode_image(&rgb)?;
+        }
+    }
+
+    Ok(SavedScreenshot {
+        filename: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        modified: now.timestamp() as u64,
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32, s: u32) -> chrono::DateTime<chrono::Local> {
+        chrono::Local.with_ymd_and_hms(2024, 3, 9, h, m, s).unwrap()
+    }
+
+    #[test]
+    fn render_filename_fills_every_placeholder() {
+        let time = at(7, 5, 3);
+        assert_eq!(render_filename(DEFAULT_FILENAME_TEMPLATE, &time, "monitor1"), "QuantHUD_2024-03-09_07-05-03");
+        assert_eq!(render_filename("{target}-{date}", &time, "chart"), "chart-2024-03-09");
+        assert_eq!(render_filename("shot_{timestamp}", &time, "x"), format!("shot_{}", time.timestamp_millis()));
+        // Unknown placeholders stay as written
+        assert_eq!(render_filename("{symbol}_{time}", &time, "x"), "{symbol}_07-05-03");
+    }
+
+    #[test]
+    fn render_filename_replaces_illegal_characters() {
+        let time = at(12, 0, 0);
+        assert_eq!(render_filename("{target}", &time, "BTC/USDT: 1h?"), "BTC_USDT_ 1h_");
+        assert_eq!(render_filename("a<b>c\"d|e*f\\g", &time, ""), "a_b_c_d_e_f_g");
+        assert_eq!(render_filename("tab\there", &time, ""), "tab_here");
+    }
+
+    #[test]
+    fn sanitize_filename_trims_and_never_returns_empty() {
+        assert_eq!(sanitize_filename("  name.  "), "name");
+        assert_eq!(sanitize_filename("trailing..."), "trailing");
+        assert_eq!(sanitize_filename(""), "screenshot");
+        assert_eq!(sanitize_filename(" ... "), "screenshot");
+    }
+
+    #[test]
+    fn unique_path_adds_a_suffix_on_collisions() {
+        let folder = std::env::temp_dir().join(format!("quanthud_screenshot_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+
+        assert_eq!(unique_path(&folder, "shot", "png"), folder.join("shot.png"));
+        fs::write(folder.join("shot.png"), b"").unwrap();
+        assert_eq!(unique_path(&folder, "shot", "png"), folder.join("shot_1.png"));
+        fs::write(folder.join("shot_1.png"), b"").unwrap();
+        assert_eq!(unique_path(&folder, "shot", "png"), folder.join("shot_2.png"));
+        // Another extension does not collide
+        assert_eq!(unique_path(&folder, "shot", "jpg"), folder.join("shot.jpg"));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SizingError {
+    #[error("Invalid entry price")]
+    InvalidEntryPrice,
+    #[error("SL must be below entry for LONG")]
+    StopAboveLongEntry,
+    #[error("SL must be above entry for SHORT")]
+    StopBelowShortEntry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeType {
+    Maker,
+    Taker,
+}
+
+/// Calculator settings; fees and risk are percentages (0.06 = 0.06%).
+/// Same shape as `CalculatorInputs` in composables/useCalculator.ts, so the
+/// calculator's inputs can be passed to the commands as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizingInputs {
+    pub capital: f64,
+    pub risk_percent: f64,
+    pub leverage: f64,
+    pub maker_fee: f64,
+    pub taker_fee: f64,
+    pub entry_fee_type: FeeType,
+    pub exit_fee_type: FeeType,
+}
+
+impl SizingInputs {
+    /// Combined entry + exit fee as a fraction of notional
+    pub fn total_fee_rate(&self) -> f64 {
+        let fee = |t: FeeType| match t {
+            FeeType::Maker => self.maker_fee / 100.0,
+            FeeType::Taker => self.taker_fee / 100.0,
+        };
+        fee(self.entry_fee_type) + fee(self.exit_fee_type)
+    }
+}
+
+/// Same shape as `CalculatorResults` in composables/useCalculator.ts
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizingResult {
+    pub pos_size_1x: f64,
+    pub pos_size_lev: f64,
+    pub quantity: f64,
+    pub net_profit: f64,
+    pub net_loss: f64,
+    pub rr_ratio: f64,
+    pub breakeven: f64,
+}
+
+/// Mirrors `calculate()` in composables/useCalculator.ts so Rust-side
+/// features report the same numbers as the calculator
+pub fn calculate(inputs: &SizingInputs, is_long: bool, entry: f64, tp: f64, sl: f64) -> Result<SizingResult, SizingError> {
+    if entry <= 0.0 {
+        return Err(SizingError::InvalidEntryPrice);
+    }
+
+    let total_fee_rate = inputs.total_fee_rate();
+    let risk_dollar = inputs.capital * (inputs.risk_percent / 100.0);
+
+    let (risk_per_unit, profit_move, breakeven) = if is_long {
+        if sl >= entry {
+            return Err(SizingError::StopAboveLongEntry);
+        }
+        (
+            (entry - sl) / entry + total_fee_rate,
+            (tp - entry) / entry - total_fee_rate,
+            entry * (1.0 + total_fee_rate),
+        )
+    } else {
+        if sl <= entry {
+            return Err(SizingError::StopBelowShortEntry);
+        }
+        (
+            (sl - entry) / entry + total_fee_rate,
+            (entry - tp) / entry - total_fee_rate,
+            entry * (1.0 - total_fee_rate),
+        )
+    };
+
+    let pos_size_1x = risk_dollar / risk_per_unit;
+    let net_loss = pos_size_1x * risk_per_unit;
+    let net_profit = pos_size_1x * profit_move;
+
+    Ok(SizingResult {
+        pos_size_1x,
+        pos_size_lev: pos_size_1x / inputs.leverage,
+        quantity: pos_size_1x / entry,
+        net_profit,
+        net_loss,
+        rr_ratio: if net_loss > 0.0 { net_profit / net_loss } else { 0.0 },
+        breakeven,
+    })
+}
+
+/// Net PnL of a sized position if closed at `price`, using the calculator's
+/// fee model (both legs charged on entry notional). Equals `-net_loss` at the
+/// stop and `net_profit` at the target.
+pub fn pnl_at(inputs: &SizingInputs, is_long: bool, entry: f64, pos_size_1x: f64, price: f64) -> f64 {
+    let direction = if is_long { 1.0 } else { -1.0 };
+    pos_size_1x * (direction * (price - entry) / entry - inputs.total_fee_rate())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(entry_fee_type: FeeType) -> SizingInputs {
+        SizingInputs {
+            capital: 10000.0,
+            risk_percent: 1.0,
+            leverage: 5.0,
+            maker_fee: 0.02,
+            taker_fee: 0.06,
+            entry_fee_type,
+            exit_fee_type: FeeType::Taker,
+        }
+    }
+
+    // Expected values are what calculate() in composables/useCalculator.ts
+    // returns for the same inputs
+    #[test]
+    fn calculate_matches_the_calculator_for_a_long() {
+        let r = calculate(&inputs(FeeType::Maker), true, 60000.0, 63000.0, 58800.0).unwrap();
+        assert_eq!(r.pos_size_1x, 4807.692307692308);
+        assert_eq!(r.pos_size_lev, 961.5384615384615);
+        assert_eq!(r.quantity, 0.08012820512820512);
+        assert_eq!(r.net_profit, 236.53846153846155);
+        assert_eq!(r.net_loss, 100.0);
+        assert_eq!(r.rr_ratio, 2.3653846153846154);
+        assert_eq!(r.breakeven, 60047.99999999999);
+    }
+
+    #[test]
+    fn calculate_matches_the_calculator_for_a_short() {
+        let r = calculate(&inputs(FeeType::Taker), false, 3000.0, 2850.0, 3060.0).unwrap();
+        assert_eq!(r.pos_size_1x, 4716.981132075472);
+        assert_eq!(r.pos_size_lev, 943.3962264150944);
+        assert_eq!(r.quantity, 1.572327044025157);
+        assert_eq!(r.net_profit, 230.18867924528303);
+        assert_eq!(r.net_loss, 100.0);
+        assert_eq!(r.rr_ratio, 2.30188679245283);
+        assert_eq!(r.breakeven, 2996.4);
+    }
+
+    #[test]
+    fn calculate_rejects_the_calculators_invalid_levels() {
+        let i = inputs(FeeType::Maker);
+        assert_eq!(calculate(&i, true, 0.0, 1.0, 1.0).unwrap_err(), SizingError::InvalidEntryPrice);
+        assert_eq!(calculate(&i, true, 100.0, 110.0, 100.0).unwrap_err(), SizingError::StopAboveLongEntry);
+        assert_eq!(calculate(&i, false, 100.0, 90.0, 99.0).unwrap_err(), SizingError::StopBelowShortEntry);
+    }
+
+    #[test]
+    fn pnl_at_is_the_net_loss_at_the_stop_and_net_profit_at_the_target() {
+        for (fee, is_long, entry, tp, sl) in [(FeeType::Maker, true, 60000.0, 63000.0, 58800.0), (FeeType::Taker, false, 3000.0, 2850.0, 3060.0)] {
+            let i = inputs(fee);
+            let r = calculate(&i, is_long, entry, tp, sl).unwrap();
+            assert!((pnl_at(&i, is_long, entry, r.pos_size_1x, sl) + r.net_loss).abs() < 1e-9);
+            assert!((pnl_at(&i, is_long, entry, r.pos_size_1x, tp) - r.net_profit).abs() < 1e-9);
+            // Flat exit still pays both fee legs
+            assert!((pnl_at(&i, is_long, entry, r.pos_size_1x, entry) + r.pos_size_1x * i.total_fee_rate()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn inputs_use_the_calculators_field_names() {
+        let json = r#"{"capital":100,"riskPercent":1,"leverage":5,"makerFee":0.02,"takerFee":0.06,"entryFeeType":"maker","exitFeeType":"taker"}"#;
+        let parsed: SizingInputs = serde_json::from_str(json).unwrap();
+        assert_eq!((parsed.risk_percent, parsed.entry_fee_type, parsed.exit_fee_type), (1.0, FeeType::Maker, FeeType::Taker));
+    }
+}
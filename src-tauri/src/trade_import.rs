@@ -0,0 +1,795 @@
+use crate::clipboard_transform;
+use crate::config::{self, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Invalid profile file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown import profile: {0}")]
+    UnknownProfile(String),
+    #[error("Built-in profile '{0}' cannot be changed")]
+    BuiltinProfile(String),
+    #[error("Column '{0}' not found in CSV header")]
+    MissingColumn(String),
+    #[error("Delimiter must be a single ASCII character, got '{0}'")]
+    InvalidDelimiter(char),
+    #[error("Decimal mark must be '.' or ',', got '{0}'")]
+    InvalidDecimalMark(char),
+}
+
+/// How the time column of an export is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormat {
+    /// "2024-03-01 14:05:09", "2024-03-01T14:05:09.123Z", "2024/03/01 14:05:09+02:00"
+    Iso,
+    UnixSeconds,
+    UnixMillis,
+}
+
+/// Column mapping for one exchange/broker export format.
+/// Column names are matched case-insensitively against the CSV header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub name: String,
+    pub delimiter: char,
+    pub time_column: String,
+    pub time_format: TimeFormat,
+    pub symbol_column: String,
+    pub side_column: String,
+    pub price_column: String,
+    pub quantity_column: String,
+    /// Fee is assumed to be in the quote currency; omit when the export has no fee column
+    pub fee_column: Option<String>,
+    /// Decimal separator of the export. Without one "3,151" reads as 3151,
+    /// while "59300,5" and "3.151,25" are told apart by their digits.
+    #[serde(default)]
+    pub decimal_mark: Option<char>,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl ImportProfile {
+    /// Built-in profile from [time, symbol, side, price, quantity, fee] column names
+    fn builtin(name: &str, [time, symbol, side, price, qty, fee]: [&str; 6]) -> Self {
+        Self {
+            name: name.to_string(),
+            delimiter: ',',
+            time_column: time.to_string(),
+            time_format: TimeFormat::Iso,
+            symbol_column: symbol.to_string(),
+            side_column: side.to_string(),
+            price_column: price.to_string(),
+            quantity_column: qty.to_string(),
+            fee_column: Some(fee.to_string()),
+            decimal_mark: None,
+            builtin: true,
+        }
+    }
+
+    /// The CSV reader takes a single byte, so anything non-ASCII would be
+    /// silently truncated to a different delimiter
+    pub fn validate(&self) -> Result<(), ImportError> {
+        if !self.delimiter.is_ascii() {
+            return Err(ImportError::InvalidDelimiter(self.delimiter));
+        }
+        if let Some(mark) = self.decimal_mark.filter(|m| !matches!(m, '.' | ',')) {
+            return Err(ImportError::InvalidDecimalMark(mark));
+        }
+        Ok(())
+    }
+}
+
+fn builtin_profiles() -> Vec<ImportProfile> {
+    vec![
+        ImportProfile::builtin("generic", ["time", "symbol", "side", "price", "quantity", "fee"]),
+        ImportProfile::builtin("binance", ["Date(UTC)", "Pair", "Side", "Price", "Executed", "Fee"]),
+        ImportProfile::builtin("kraken", ["time", "pair", "type", "price", "vol", "fee"]),
+        ImportProfile::builtin("bybit", ["Transaction Time", "Contract", "Direction", "Filled Price", "Qty", "Fee Paid"]),
+    ]
+}
+
+fn profiles_path() -> Result<PathBuf, ImportError> {
+    Ok(config::get_app_dir()?.join("import_profiles.json"))
+}
+
+fn load_user_profiles() -> Result<Vec<ImportProfile>, ImportError> {
+    let path = profiles_path()?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+fn save_user_profiles(profiles: &[ImportProfile]) -> Result<(), ImportError> {
+    let data = serde_json::to_string_pretty(profiles)?;
+    fs::write(profiles_path()?, data)?;
+    Ok(())
+}
+
+/// Built-in profiles followed by the user's saved profiles
+pub fn list_profiles() -> Result<Vec<ImportProfile>, ImportError> {
+    let mut all = builtin_profiles();
+    all.extend(load_user_profiles()?);
+    Ok(all)
+}
+
+pub fn find_profile(name: &str) -> Result<ImportProfile, ImportError> {
+    list_profiles()?
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| ImportError::UnknownProfile(name.to_string()))
+}
+
+/// Insert or replace a user profile by name
+pub fn save_profile(mut profile: ImportProfile) -> Result<(), ImportError> {
+    if builtin_profiles().iter().any(|p| p.name.eq_ignore_ascii_case(&profile.name)) {
+        return Err(ImportError::BuiltinProfile(profile.name));
+    }
+    profile.validate()?;
+    profile.builtin = false;
+    let mut profiles = load_user_profiles()?;
+    profiles.retain(|p| !p.name.eq_ignore_ascii_case(&profile.name));
+    profiles.push(profile);
+    save_user_profiles(&profiles)
+}
+
+pub fn delete_profile(name: &str) -> Result<(), ImportError> {
+    if builtin_profiles().iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(ImportError::BuiltinProfile(name.to_string()));
+    }
+    let mut profiles = load_user_profiles()?;
+    profiles.retain(|p| !p.name.eq_ignore_ascii_case(name));
+    save_user_profiles(&profiles)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Fill {
+    /// Unix seconds (UTC)
+    pub time: i64,
+    pub symbol: String,
+    pub side: FillSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub fee: f64,
+}
+
+/// A position opened from flat and closed back to flat
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundTrip {
+    pub symbol: String,
+    pub side: TradeSide,
+    pub open_time: i64,
+    pub close_time: i64,
+    pub quantity: f64,
+    pub avg_entry: f64,
+    pub avg_exit: f64,
+    pub fees: f64,
+    pub gross_pnl: f64,
+    /// Gross PnL minus all entry and exit fees
+    pub realized_pnl: f64,
+    pub fill_count: usize,
+}
+
+/// A position still open at the end of the export
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenPosition {
+    pub symbol: String,
+    pub side: TradeSide,
+    pub open_time: i64,
+    pub quantity: f64,
+    pub avg_entry: f64,
+    pub fees: f64,
+}
+
+/// A journal entry to match imported trades against
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlannedTrade {
+    pub id: String,
+    pub symbol: String,
+    pub side: Option<TradeSide>,
+    /// Unix seconds (UTC) when the trade was planned
+    pub planned_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeMatch {
+    /// Index into `ImportResult::trades`
+    pub trade_index: usize,
+    pub planned_id: String,
+    /// open_time - planned_at, in seconds
+    pub time_delta: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedRow {
+    /// 1-based line number in the file, header included
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportResult {
+    pub trades: Vec<RoundTrip>,
+    pub open_positions: Vec<OpenPosition>,
+    pub matches: Vec<TradeMatch>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+/// Parse a number as exported by exchanges: "3,151.25", "3.151,25", "$3151.250", "0.015BTC", "-0.12 USDT".
+/// Separators are read like clipboard values; `decimal_mark` settles "3,151" vs "3.151".
+pub fn parse_number(raw: &str, decimal_mark: Option<char>) -> Option<f64> {
+    let cleaned: String = raw
+        .trim()
+        .trim_start_matches(['$', '€', '£'])
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let (sign, body) = match cleaned.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, cleaned.strip_prefix('+').unwrap_or(&cleaned)),
+    };
+    let end = body.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',')).unwrap_or(body.len());
+    let (value, _) = clipboard_transform::parse_digits(&body[..end], decimal_mark)?;
+    Some(sign * value)
+}
+
+fn parse_side(raw: &str) -> Option<FillSide> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "buy" | "b" | "long" | "open long" | "close short" => Some(FillSide::Buy),
+        "sell" | "s" | "short" | "open short" | "close long" => Some(FillSide::Sell),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn parse_iso_time(raw: &str) -> Option<i64> {
+    let s = raw.trim();
+    let (date, rest) = s.split_at(s.find([' ', 'T']).unwrap_or(s.len()));
+    let mut parts = date.split(['-', '/']).map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    let mut secs = days_from_civil(y, m, d) * 86_400;
+
+    let rest = rest.trim_start_matches([' ', 'T']);
+    if rest.is_empty() {
+        return Some(secs);
+    }
+
+    // Split off a trailing UTC offset ("Z", "+02:00", "-0500")
+    let (clock, offset) = match rest.rfind(['Z', '+', '-']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    let clock = clock.split('.').next().unwrap_or("");
+    let mut hms = clock.split(':').map(|p| p.trim().parse::<i64>().ok());
+    let h = hms.next()??;
+    let min = hms.next().flatten().unwrap_or(0);
+    let sec = hms.next().flatten().unwrap_or(0);
+    secs += h * 3600 + min * 60 + sec;
+
+    if let Some(sign) = offset.chars().next().filter(|c| *c != 'Z') {
+        let digits: String = offset[1..].chars().filter(|c| c.is_ascii_digit()).collect();
+        if digits.len() < 2 {
+            return None;
+        }
+        let oh: i64 = digits[..2].parse().ok()?;
+        let om: i64 = digits.get(2..4).and_then(|m| m.parse().ok()).unwrap_or(0);
+        let off = oh * 3600 + om * 60;
+        secs += if sign == '+' { -off } else { off };
+    }
+    Some(secs)
+}
+
+fn parse_time(raw: &str, format: TimeFormat) -> Option<i64> {
+    match format {
+        TimeFormat::Iso => parse_iso_time(raw),
+        TimeFormat::UnixSeconds => parse_number(raw, None).map(|v| v as i64),
+        TimeFormat::UnixMillis => parse_number(raw, None).map(|v| (v / 1000.0) as i64),
+    }
+}
+
+/// Parse fills from CSV data using a column mapping.
+/// Rows that can't be parsed are reported in the returned skip list rather than failing the import.
+pub fn parse_fills<R: Read>(reader: R, profile: &ImportProfile) -> Result<(Vec<Fill>, Vec<SkippedRow>), ImportError> {
+    profile.validate()?;
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(profile.delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = rdr.headers()?.clone();
+    let col = |name: &str| -> Result<usize, ImportError> {
+        headers
+            .iter()
+            .position(|h| h.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+            .ok_or_else(|| ImportError::MissingColumn(name.to_string()))
+    };
+    let time_i = col(&profile.time_column)?;
+    let symbol_i = col(&profile.symbol_column)?;
+    let side_i = col(&profile.side_column)?;
+    let price_i = col(&profile.price_column)?;
+    let qty_i = col(&profile.quantity_column)?;
+    let fee_i = profile.fee_column.as_deref().map(col).transpose()?;
+
+    let mut fills = Vec::new();
+    let mut skipped = Vec::new();
+    for (idx, record) in rdr.records().enumerate() {
+        let line = idx + 2;
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                skipped.push(SkippedRow { line, reason: e.to_string() });
+                continue;
+            }
+        };
+        if record.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+        let field = |i: usize| record.get(i).unwrap_or("");
+
+        let parsed = (|| -> Result<Fill, String> {
+            let time = parse_time(field(time_i), profile.time_format)
+                .ok_or_else(|| format!("invalid time '{}'", field(time_i)))?;
+            let symbol = field(symbol_i).to_string();
+            if symbol.is_empty() {
+                return Err("empty symbol".into());
+            }
+            let side = parse_side(field(side_i))
+                .ok_or_else(|| format!("unknown side '{}'", field(side_i)))?;
+            let price = parse_number(field(price_i), profile.decimal_mark)
+                .filter(|p| *p > 0.0)
+                .ok_or_else(|| format!("invalid price '{}'", field(price_i)))?;
+            let quantity = parse_number(field(qty_i), profile.decimal_mark)
+                .map(f64::abs)
+                .filter(|q| *q > 0.0)
+                .ok_or_else(|| format!("invalid quantity '{}'", field(qty_i)))?;
+            let fee = match fee_i {
+                Some(i) if !field(i).is_empty() => parse_number(field(i), profile.decimal_mark)
+                    .ok_or_else(|| format!("invalid fee '{}'", field(i)))?,
+                _ => 0.0,
+            };
+            Ok(Fill { time, symbol, side, price, quantity, fee })
+        })();
+
+        match parsed {
+            Ok(fill) => fills.push(fill),
+            Err(reason) => skipped.push(SkippedRow { line, reason }),
+        }
+    }
+    Ok((fills, skipped))
+}
+
+/// Normalize a symbol for comparison: "btc/usdt", "BTC-USDT" and "BTCUSDT" are all the same market
+pub fn normalize_symbol(symbol: &str) -> String {
+    symbol
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+struct OpenTrip {
+    side: TradeSide,
+    open_time: i64,
+    remaining: f64,
+    entry_qty: f64,
+    entry_notional: f64,
+    exit_qty: f64,
+    exit_notional: f64,
+    fees: f64,
+    fill_count: usize,
+}
+
+impl OpenTrip {
+    fn new(side: TradeSide, time: i64, price: f64, qty: f64, fee: f64) -> Self {
+        Self {
+            side,
+            open_time: time,
+            remaining: qty,
+            entry_qty: qty,
+            entry_notional: price * qty,
+            exit_qty: 0.0,
+            exit_notional: 0.0,
+            fees: fee,
+            fill_count: 1,
+        }
+    }
+
+    fn close(self, symbol: &str, close_time: i64) -> RoundTrip {
+        let avg_entry = self.entry_notional / self.entry_qty;
+        let avg_exit = self.exit_notional / self.exit_qty;
+        let direction = if self.side == TradeSide::Long { 1.0 } else { -1.0 };
+        let gross_pnl = (avg_exit - avg_entry) * self.exit_qty * direction;
+        RoundTrip {
+            symbol: symbol.to_string(),
+            side: self.side,
+            open_time: self.open_time,
+            close_time,
+            quantity: self.entry_qty,
+            avg_entry,
+            avg_exit,
+            fees: self.fees,
+            gross_pnl,
+            realized_pnl: gross_pnl - self.fees,
+            fill_count: self.fill_count,
+        }
+    }
+}
+
+/// Group fills into round-trip trades per symbol.
+/// A fill that takes the position through flat is split: the closing part
+/// completes the current trip and the remainder opens a new one in the
+/// opposite direction, with its fee split pro rata.
+pub fn group_round_trips(fills: &[Fill]) -> (Vec<RoundTrip>, Vec<OpenPosition>) {
+    const EPS: f64 = 1e-9;
+
+    let mut sorted: Vec<&Fill> = fills.iter().collect();
+    sorted.sort_by_key(|f| f.time);
+
+    let mut open: HashMap<String, (String, OpenTrip)> = HashMap::new();
+    let mut trips = Vec::new();
+
+    for fill in sorted {
+        let key = normalize_symbol(&fill.symbol);
+        let fill_side = match fill.side {
+            FillSide::Buy => TradeSide::Long,
+            FillSide::Sell => TradeSide::Short,
+        };
+
+        let Some((symbol, mut trip)) = open.remove(&key) else {
+            open.insert(
+                key,
+                (fill.symbol.clone(), OpenTrip::new(fill_side, fill.time, fill.price, fill.quantity, fill.fee)),
+            );
+            continue;
+        };
+
+        trip.fill_count += 1;
+        if trip.side == fill_side {
+            trip.remaining += fill.quantity;
+            trip.entry_qty += fill.quantity;
+            trip.entry_notional += fill.price * fill.quantity;
+            trip.fees += fill.fee;
+            open.insert(key, (symbol, trip));
+            continue;
+        }
+
+        let closing = fill.quantity.min(trip.remaining);
+        let closing_fee = fill.fee * closing / fill.quantity;
+        trip.remaining -= closing;
+        trip.exit_qty += closing;
+        trip.exit_notional += fill.price * closing;
+        trip.fees += closing_fee;
+
+        if trip.remaining > EPS * trip.entry_qty.max(1.0) {
+            open.insert(key, (symbol, trip));
+            continue;
+        }
+
+        trips.push(trip.close(&symbol, fill.time));
+
+        let leftover = fill.quantity - closing;
+        if leftover > EPS * fill.quantity.max(1.0) {
+            open.insert(
+                key,
+                (fill.symbol.clone(), OpenTrip::new(fill_side, fill.time, fill.price, leftover, fill.fee - closing_fee)),
+            );
+        }
+    }
+
+    let mut positions: Vec<OpenPosition> = open
+        .into_values()
+        .map(|(symbol, trip)| OpenPosition {
+            symbol,
+            side: trip.side,
+            open_time: trip.open_time,
+            quantity: trip.remaining,
+            avg_entry: trip.entry_notional / trip.entry_qty,
+            fees: trip.fees,
+        })
+        .collect();
+    positions.sort_by_key(|p| p.open_time);
+    trips.sort_by_key(|t| t.open_time);
+
+    (trips, positions)
+}
+
+/// Match trades to planned trades with the same symbol (and side, when the
+/// plan has one) whose open time is within `window_secs` of the plan.
+/// Closest pairs are matched first and each side is used at most once.
+pub fn match_planned(trades: &[RoundTrip], planned: &[PlannedTrade], window_secs: i64) -> Vec<TradeMatch> {
+    let mut candidates: Vec<(i64, usize, usize)> = Vec::new();
+    for (ti, trade) in trades.iter().enumerate() {
+        let symbol = normalize_symbol(&trade.symbol);
+        for (pi, plan) in planned.iter().enumerate() {
+            if normalize_symbol(&plan.symbol) != symbol {
+                continue;
+            }
+            if plan.side.is_some_and(|s| s != trade.side) {
+                continue;
+            }
+            let delta = trade.open_time - plan.planned_at;
+            if delta.abs() <= window_secs {
+                candidates.push((delta.abs(), ti, pi));
+            }
+        }
+    }
+    candidates.sort();
+
+    let mut trade_used = vec![false; trades.len()];
+    let mut plan_used = vec![false; planned.len()];
+    let mut matches = Vec::new();
+    for (_, ti, pi) in candidates {
+        if trade_used[ti] || plan_used[pi] {
+            continue;
+        }
+        trade_used[ti] = true;
+        plan_used[pi] = true;
+        matches.push(TradeMatch {
+            trade_index: ti,
+            planned_id: planned[pi].id.clone(),
+            time_delta: trades[ti].open_time - planned[pi].planned_at,
+        });
+    }
+    matches.sort_by_key(|m| m.trade_index);
+    matches
+}
+
+/// Run the full pipeline on CSV data: parse fills, group round trips, match plans
+pub fn import_trades<R: Read>(
+    reader: R,
+    profile: &ImportProfile,
+    planned: &[PlannedTrade],
+    window_secs: i64,
+) -> Result<ImportResult, ImportError> {
+    let (fills, skipped) = parse_fills(reader, profile)?;
+    let (trades, open_positions) = group_round_trips(&fills);
+    let matches = match_planned(&trades, planned, window_secs);
+    Ok(ImportResult { trades, open_positions, matches, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINANCE_CSV: &str = include_str!("../tests/fixtures/trade_import/binance.csv");
+    const SEMICOLON_CSV: &str = include_str!("../tests/fixtures/trade_import/semicolon_millis.csv");
+
+    fn profile(name: &str) -> ImportProfile {
+        builtin_profiles().into_iter().find(|p| p.name == name).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    fn fill(time: i64, symbol: &str, side: FillSide, price: f64, quantity: f64, fee: f64) -> Fill {
+        Fill { time, symbol: symbol.to_string(), side, price, quantity, fee }
+    }
+
+    fn plan(id: &str, symbol: &str, side: Option<TradeSide>, planned_at: i64) -> PlannedTrade {
+        PlannedTrade { id: id.to_string(), symbol: symbol.to_string(), side, planned_at }
+    }
+
+    #[test]
+    fn parses_binance_export_and_reports_bad_rows() {
+        let (fills, skipped) = parse_fills(BINANCE_CSV.as_bytes(), &profile("binance")).unwrap();
+
+        assert_eq!(fills.len(), 4);
+        let first = &fills[0];
+        assert_eq!(first.time, 1_709_301_909);
+        assert_eq!(first.symbol, "BTCUSDT");
+        assert_eq!(first.side, FillSide::Buy);
+        assert!(close(first.price, 60_000.0));
+        assert!(close(first.quantity, 0.01));
+        assert!(close(first.fee, 0.6));
+
+        let lines: Vec<usize> = skipped.iter().map(|s| s.line).collect();
+        assert_eq!(lines, vec![6, 7]);
+        assert!(skipped[0].reason.contains("invalid time"));
+        assert!(skipped[1].reason.contains("unknown side"));
+    }
+
+    #[test]
+    fn maps_custom_columns_with_semicolons_and_millis() {
+        let profile = ImportProfile {
+            name: "custom".to_string(),
+            delimiter: ';',
+            time_column: "timestamp".to_string(),
+            time_format: TimeFormat::UnixMillis,
+            symbol_column: "market".to_string(),
+            side_column: "action".to_string(),
+            price_column: "fill price".to_string(),
+            quantity_column: "amount".to_string(),
+            fee_column: None,
+            decimal_mark: None,
+            builtin: false,
+        };
+        let (fills, skipped) = parse_fills(SEMICOLON_CSV.as_bytes(), &profile).unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(fills.len(), 3);
+        assert_eq!(fills[0].time, 1_709_301_909);
+        assert_eq!(fills[1].side, FillSide::Sell);
+        assert!(fills.iter().all(|f| f.fee == 0.0));
+
+        // "btc/usdt" and "BTC-USDT" are the same market
+        let (trips, open) = group_round_trips(&fills);
+        assert!(open.is_empty());
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].side, TradeSide::Long);
+        assert_eq!(trips[0].fill_count, 3);
+        assert!(close(trips[0].avg_exit, 59_300.0));
+        assert!(close(trips[0].gross_pnl, -350.0));
+    }
+
+    #[test]
+    fn missing_column_fails_the_import() {
+        let mut profile = profile("binance");
+        profile.fee_column = Some("Commission".to_string());
+        let err = parse_fills(BINANCE_CSV.as_bytes(), &profile).unwrap_err();
+        assert!(matches!(err, ImportError::MissingColumn(name) if name == "Commission"));
+    }
+
+    #[test]
+    fn rejects_non_ascii_delimiter() {
+        let mut profile = profile("generic");
+        profile.delimiter = '¦';
+        assert!(matches!(profile.validate(), Err(ImportError::InvalidDelimiter('¦'))));
+        assert!(matches!(parse_fills("".as_bytes(), &profile), Err(ImportError::InvalidDelimiter(_))));
+    }
+
+    #[test]
+    fn rejects_unknown_decimal_mark() {
+        let mut profile = profile("generic");
+        profile.decimal_mark = Some(';');
+        assert!(matches!(profile.validate(), Err(ImportError::InvalidDecimalMark(';'))));
+    }
+
+    #[test]
+    fn parses_numbers_with_either_decimal_mark() {
+        let cases = [
+            ("3,151.25", None, Some(3151.25)),
+            ("3.151,25", None, Some(3151.25)),
+            ("59300,5", None, Some(59300.5)),
+            ("60,000.00", None, Some(60000.0)),
+            ("1 234,5", None, Some(1234.5)),
+            ("$3151.250", None, Some(3151.25)),
+            ("0.010BTC", None, Some(0.01)),
+            ("-0.12 USDT", None, Some(-0.12)),
+            ("+5", None, Some(5.0)),
+            // A lone comma before three digits is grouping unless the profile says otherwise
+            ("3,151", None, Some(3151.0)),
+            ("3,151", Some(','), Some(3.151)),
+            ("3.151", Some(','), Some(3151.0)),
+            ("2024.01.05", None, None),
+            ("USDT", None, None),
+            ("", None, None),
+        ];
+        for (raw, mark, expected) in cases {
+            assert_eq!(parse_number(raw, mark), expected, "{raw:?} {mark:?}");
+        }
+    }
+
+    #[test]
+    fn imports_comma_decimal_exports() {
+        let csv = "Time;Symbol;Side;Price;Quantity;Fee\n\
+                   2024-03-01 14:05:09;BTCUSDT;buy;59.300,5;0,010;0,593\n\
+                   2024-03-01 14:20:00;BTCUSDT;sell;60.000;0,010;0,6\n";
+        let mut profile = profile("generic");
+        profile.delimiter = ';';
+        profile.decimal_mark = Some(',');
+        let (fills, skipped) = parse_fills(csv.as_bytes(), &profile).unwrap();
+
+        assert!(skipped.is_empty(), "{skipped:?}");
+        assert!(close(fills[0].price, 59_300.5));
+        assert!(close(fills[0].quantity, 0.01));
+        assert!(close(fills[0].fee, 0.593));
+        assert!(close(fills[1].price, 60_000.0));
+    }
+
+    #[test]
+    fn groups_binance_fills_into_round_trips() {
+        let (fills, _) = parse_fills(BINANCE_CSV.as_bytes(), &profile("binance")).unwrap();
+        let (trips, open) = group_round_trips(&fills);
+
+        assert_eq!(trips.len(), 2);
+        let btc = &trips[0];
+        assert_eq!(btc.side, TradeSide::Long);
+        assert_eq!((btc.open_time, btc.close_time), (1_709_301_909, 1_709_302_800));
+        assert!(close(btc.gross_pnl, 10.0));
+        assert!(close(btc.realized_pnl, 8.79));
+
+        // The 1.5 ETH buy closes the 1 ETH short and opens 0.5 long
+        let eth = &trips[1];
+        assert_eq!(eth.side, TradeSide::Short);
+        assert!(close(eth.gross_pnl, 100.0));
+        assert!(close(eth.fees, 3.0));
+        assert!(close(eth.realized_pnl, 97.0));
+
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, TradeSide::Long);
+        assert!(close(open[0].quantity, 0.5));
+        assert!(close(open[0].avg_entry, 2_900.0));
+        assert!(close(open[0].fees, 0.75));
+    }
+
+    #[test]
+    fn scales_in_and_closes_partially() {
+        let fills = vec![
+            fill(300, "ETHUSDT", FillSide::Sell, 2_000.0, 1.0, 0.0),
+            fill(100, "ETHUSDT", FillSide::Buy, 1_800.0, 1.0, 0.0),
+            fill(200, "ETHUSDT", FillSide::Buy, 1_900.0, 1.0, 0.0),
+        ];
+        let (trips, open) = group_round_trips(&fills);
+
+        // Fills are sorted by time, so the sell is a partial close
+        assert!(trips.is_empty());
+        assert_eq!(open.len(), 1);
+        assert!(close(open[0].quantity, 1.0));
+        assert!(close(open[0].avg_entry, 1_850.0));
+        assert_eq!(open[0].open_time, 100);
+    }
+
+    #[test]
+    fn matches_closest_plan_first() {
+        let (fills, _) = parse_fills(BINANCE_CSV.as_bytes(), &profile("binance")).unwrap();
+        let (trips, _) = group_round_trips(&fills);
+        let btc_open = trips[0].open_time;
+        let eth_open = trips[1].open_time;
+
+        let planned = vec![
+            plan("far", "BTC/USDT", None, btc_open - 600),
+            plan("near", "btc-usdt", Some(TradeSide::Long), btc_open - 60),
+            plan("wrong-side", "BTCUSDT", Some(TradeSide::Short), btc_open),
+            plan("eth", "ETHUSDT", None, eth_open + 30),
+            plan("stale", "ETHUSDT", None, eth_open - 7_200),
+        ];
+        let matches = match_planned(&trips, &planned, 3_600);
+
+        let pairs: Vec<(usize, &str, i64)> =
+            matches.iter().map(|m| (m.trade_index, m.planned_id.as_str(), m.time_delta)).collect();
+        assert_eq!(pairs, vec![(0, "near", 60), (1, "eth", -30)]);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::config::{self, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PlanError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanSide {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanFibLevel {
+    pub level: f64,
+    pub price: f64,
+}
+
+/// A sized trade as computed by the position sizer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePlan {
+    pub symbol: String,
+    pub side: PlanSide,
+    pub entry: f64,
+    pub take_profit: f64,
+    pub stop_loss: f64,
+    /// Position size in quote currency (1x notional)
+    pub position_size: f64,
+    pub quantity: f64,
+    pub leverage: f64,
+    pub rr_ratio: f64,
+    #[serde(default)]
+    pub fib_levels: Vec<PlanFibLevel>,
+    #[serde(default)]
+    pub wyckoff_bias: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanFormat {
+    Text,
+    Markdown,
+    Json,
+    Pine,
+}
+
+/// User-editable templates for the text formats.
+/// `{{name}}` placeholders are substituted; a line whose placeholders all
+/// render empty (e.g. `{{bias}}` without a Wyckoff bias) is dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanTemplates {
+    pub text: String,
+    pub markdown: String,
+}
+
+impl Default for PlanTemplates {
+    fn default() -> Self {
+        Self {
+            text: "{{symbol}} {{SIDE}}\n\
+                   Entry: {{entry}}\n\
+                   TP: {{tp}}\n\
+                   SL: {{sl}}\n\
+                   Size: {{size}} ({{quantity}} units @ {{leverage}}x)\n\
+                   R:R: {{rr}}\n\
+                   Bias: {{bias}}\n\
+                   {{fibs}}"
+                .to_string(),
+            markdown: "**{{symbol}} {{SIDE}}**\n\n\
+                       | | |\n\
+                       |---|---|\n\
+                       | Entry | `{{entry}}` |\n\
+                       | TP | `{{tp}}` |\n\
+                       | SL | `{{sl}}` |\n\
+                       | Size | {{size}} ({{quantity}} units @ {{leverage}}x) |\n\
+                       | R:R | {{rr}} |\n\
+                       | Bias | {{bias}} |\n\n\
+                       {{fibs}}"
+                .to_string(),
+        }
+    }
+}
+
+fn templates_path() -> Result<PathBuf, PlanError> {
+    Ok(config::get_app_dir()?.join("plan_templates.json"))
+}
+
+pub fn load_templates() -> Result<PlanTemplates, PlanError> {
+    let path = templates_path()?;
+    if !path.exists() {
+        return Ok(PlanTemplates::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_templates(templates: &PlanTemplates) -> Result<(), PlanError> {
+    fs::write(templates_path()?, serde_json::to_string_pretty(templates)?)?;
+    Ok(())
+}
+
+/// Format a price without trailing zeros (up to 8 decimals)
+fn fmt_price(v: f64) -> String {
+    let s = format!("{:.8}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn side_label(side: PlanSide) -> &'static str {
+    match side {
+        PlanSide::Long => "long",
+        PlanSide::Short => "short",
+    }
+}
+
+struct RenderCtx<'a> {
+    plan: &'a TradePlan,
+    format: PlanFormat,
+}
+
+fn placeholder(ctx: &RenderCtx, name: &str) -> Option<String> {
+    let p = ctx.plan;
+    let value = match name {
+        "symbol" => p.symbol.clone(),
+        "side" => side_label(p.side).to_string(),
+        "SIDE" => side_label(p.side).to_uppercase(),
+        "entry" => fmt_price(p.entry),
+        "tp" => fmt_price(p.take_profit),
+        "sl" => fmt_price(p.stop_loss),
+        "size" => format!("{:.2}", p.position_size),
+        "quantity" => fmt_price(p.quantity),
+        "leverage" => fmt_price(p.leverage),
+        "rr" => format!("{:.2}", p.rr_ratio),
+        "bias" => p.wyckoff_bias.clone().unwrap_or_default(),
+        "fibs" => {
+            let mut levels = p.fib_levels.clone();
+            levels.sort_by(|a, b| a.level.total_cmp(&b.level));
+            levels
+                .iter()
+                .map(|f| match ctx.format {
+                    PlanFormat::Markdown => format!("- **{}**: `{}`", fmt_price(f.level), fmt_price(f.price)),
+                    _ => format!("Fib {}: {}", fmt_price(f.level), fmt_price(f.price)),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Substitute `{{name}}` placeholders line by line. Unknown placeholders are left as-is.
+fn render_template(template: &str, ctx: &RenderCtx) -> String {
+    let mut out = Vec::new();
+    for line in template.lines() {
+        let mut rendered = String::new();
+        let mut rest = line;
+        let mut any_placeholder = false;
+        let mut any_value = false;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else { break };
+            let name = rest[start + 2..start + 2 + len].trim();
+            rendered.push_str(&rest[..start]);
+            match placeholder(ctx, name) {
+                Some(value) => {
+                    any_placeholder = true;
+                    any_value |= !value.is_empty();
+                    rendered.push_str(&value);
+                }
+                None => rendered.push_str(&rest[start..start + len + 4]),
+            }
+            rest = &rest[start + len + 4..];
+        }
+        rendered.push_str(rest);
+        if any_placeholder && !any_value {
+            continue;
+        }
+        out.push(rendered);
+    }
+    out.join("\n").trim_end().to_string()
+}
+
+/// TradingView Pine v5 indicator drawing the plan's levels as horizontal lines
+fn render_pine(plan: &TradePlan) -> String {
+    let mut lines = vec![
+        "//@version=5".to_string(),
+        format!(
+            "indicator(\"QuantHUD {} {}\", overlay=true)",
+            plan.symbol.replace('"', ""),
+            side_label(plan.side)
+        ),
+        format!("hline({}, \"Entry\", color=color.blue, linestyle=hline.style_solid)", fmt_price(plan.entry)),
+        format!("hline({}, \"TP\", color=color.green, linestyle=hline.style_solid)", fmt_price(plan.take_profit)),
+        format!("hline({}, \"SL\", color=color.red, linestyle=hline.style_solid)", fmt_price(plan.stop_loss)),
+    ];
+    for f in &plan.fib_levels {
+        lines.push(format!(
+            "hline({}, \"Fib {}\", color=color.gray, linestyle=hline.style_dotted)",
+            fmt_price(f.price),
+            fmt_price(f.level)
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn render_plan(plan: &TradePlan, format: PlanFormat, templates: &PlanTemplates) -> Result<String, PlanError> {
+    let ctx = RenderCtx { plan, format };
+    Ok(match format {
+        PlanFormat::Text => render_template(&templates.text, &ctx),
+        PlanFormat::Markdown => render_template(&templates.markdown, &ctx),
+        PlanFormat::Json => serde_json::to_string_pretty(plan)?,
+        PlanFormat::Pine => render_pine(plan),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> TradePlan {
+        TradePlan {
+            symbol: "BTCUSDT".to_string(),
+            side: PlanSide::Long,
+            entry: 60000.0,
+            take_profit: 63000.0,
+            stop_loss: 58800.0,
+            position_size: 4807.692307692308,
+            quantity: 0.08012820512820512,
+            leverage: 5.0,
+            rr_ratio: 2.3653846153846154,
+            fib_levels: vec![],
+            wyckoff_bias: None,
+        }
+    }
+
+    fn with_extras() -> TradePlan {
+        TradePlan {
+            fib_levels: vec![PlanFibLevel { level: 0.618, price: 61854.6 }, PlanFibLevel { level: 0.5, price: 61500.0 }],
+            wyckoff_bias: Some("Accumulation".to_string()),
+            ..plan()
+        }
+    }
+
+    fn render(plan: &TradePlan, format: PlanFormat) -> String {
+        render_plan(plan, format, &PlanTemplates::default()).unwrap()
+    }
+
+    #[test]
+    fn fmt_price_trims_trailing_zeros() {
+        let cases = [(60000.0, "60000"), (100.0, "100"), (0.1, "0.1"), (-2.50, "-2.5"), (1.23456789123, "1.23456789"), (0.000000001, "0")];
+        for (value, expected) in cases {
+            assert_eq!(fmt_price(value), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn text_drops_lines_without_values() {
+        assert_eq!(
+            render(&plan(), PlanFormat::Text),
+            "BTCUSDT LONG\nEntry: 60000\nTP: 63000\nSL: 58800\nSize: 4807.69 (0.08012821 units @ 5x)\nR:R: 2.37"
+        );
+        assert_eq!(
+            render(&with_extras(), PlanFormat::Text),
+            "BTCUSDT LONG\nEntry: 60000\nTP: 63000\nSL: 58800\nSize: 4807.69 (0.08012821 units @ 5x)\nR:R: 2.37\n\
+             Bias: Accumulation\nFib 0.5: 61500\nFib 0.618: 61854.6"
+        );
+    }
+
+    #[test]
+    fn markdown_lists_fibs_as_bullets() {
+        let bare = render(&plan(), PlanFormat::Markdown);
+        assert!(bare.starts_with("**BTCUSDT LONG**\n\n| | |\n|---|---|\n| Entry | `60000` |"), "{bare}");
+        assert!(bare.ends_with("| R:R | 2.37 |"), "{bare}");
+        assert!(!bare.contains("Bias"));
+
+        let full = render(&with_extras(), PlanFormat::Markdown);
+        assert!(full.ends_with("| Bias | Accumulation |\n\n- **0.5**: `61500`\n- **0.618**: `61854.6`"), "{full}");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_left_alone() {
+        let ctx = RenderCtx { plan: &plan(), format: PlanFormat::Text };
+        assert_eq!(render_template("{{symbol}} {{unknown}} {{ side }}", &ctx), "BTCUSDT {{unknown}} long");
+        // Only known placeholders can empty a line
+        assert_eq!(render_template("Note: {{note}}\nBias: {{bias}}", &ctx), "Note: {{note}}");
+        assert_eq!(render_template("{{symbol} {{symbol", &ctx), "{{symbol} {{symbol");
+        let custom = PlanTemplates { text: "{{SIDE}} {{symbol}} @ {{entry}}".to_string(), ..Default::default() };
+        assert_eq!(render_plan(&plan(), PlanFormat::Text, &custom).unwrap(), "LONG BTCUSDT @ 60000");
+    }
+
+    #[test]
+    fn pine_draws_every_level() {
+        let short = TradePlan { symbol: "ETH\"USDT".to_string(), side: PlanSide::Short, ..with_extras() };
+        assert_eq!(
+            render(&short, PlanFormat::Pine),
+            "//@version=5\n\
+             indicator(\"QuantHUD ETHUSDT short\", overlay=true)\n\
+             hline(60000, \"Entry\", color=color.blue, linestyle=hline.style_solid)\n\
+             hline(63000, \"TP\", color=color.green, linestyle=hline.style_solid)\n\
+             hline(58800, \"SL\", color=color.red, linestyle=hline.style_solid)\n\
+             hline(61854.6, \"Fib 0.618\", color=color.gray, linestyle=hline.style_dotted)\n\
+             hline(61500, \"Fib 0.5\", color=color.gray, linestyle=hline.style_dotted)"
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let json = render(&with_extras(), PlanFormat::Json);
+        let parsed: TradePlan = serde_json::from_str(&json).unwrap();
+        assert_eq!((parsed.side, parsed.fib_levels.len()), (PlanSide::Long, 2));
+        assert!(json.contains("\"side\": \"long\""));
+    }
+}
@@ -0,0 +1,265 @@
+use crate::layout::Rect;
+
+/// Taskbar height assumed when the platform can't report a work area
+const FALLBACK_TASKBAR: f64 = 48.0;
+
+/// Space a panel/dock reserves along one screen edge, in root-window pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strut {
+    Left(Rect),
+    Right(Rect),
+    Top(Rect),
+    Bottom(Rect),
+}
+
+/// Work area of the monitor with `bounds` (physical px). Asks the OS first
+/// and falls back to assuming a bottom taskbar when it can't answer.
+pub fn work_area(bounds: Rect, scale_factor: f64) -> Rect {
+    detect(bounds, scale_factor)
+        .filter(|area| is_plausible(&bounds, area))
+        .unwrap_or_else(|| fallback(bounds, scale_factor))
+}
+
+/// Full screen minus a 48 logical-px taskbar along the bottom
+pub fn fallback(bounds: Rect, scale_factor: f64) -> Rect {
+    Rect { height: bounds.height - (FALLBACK_TASKBAR * scale_factor) as i32, ..bounds }
+}
+
+/// A reported work area is only trusted when it is non-empty and inside the monitor
+pub fn is_plausible(bounds: &Rect, area: &Rect) -> bool {
+    !area.is_empty() && bounds.intersect(area) == Some(*area)
+}
+
+/// Decode `_NET_WM_STRUT_PARTIAL` (12 values) or `_NET_WM_STRUT` (4 values,
+/// full-length) into the rects each edge reserves
+pub fn struts_from_property(root_width: i32, root_height: i32, values: &[u32]) -> Vec<Strut> {
+    let v = |i: usize| values[i] as i32;
+    let (spans, [left, right, top, bottom]) = match values.len() {
+        12 => (
+            [(v(4), v(5)), (v(6), v(7)), (v(8), v(9)), (v(10), v(11))],
+            [v(0), v(1), v(2), v(3)],
+        ),
+        4 => (
+            [(0, root_height - 1), (0, root_height - 1), (0, root_width - 1), (0, root_width - 1)],
+            [v(0), v(1), v(2), v(3)],
+        ),
+        _ => return Vec::new(),
+    };
+    // Spans are inclusive start/end coordinates
+    let len = |(start, end): (i32, i32)| end - start + 1;
+
+    let mut struts = Vec::new();
+    if left > 0 {
+        struts.push(Strut::Left(Rect { x: 0, y: spans[0].0, width: left, height: len(spans[0]) }));
+    }
+    if right > 0 {
+        struts.push(Strut::Right(Rect { x: root_width - right, y: spans[1].0, width: right, height: len(spans[1]) }));
+    }
+    if top > 0 {
+        struts.push(Strut::Top(Rect { x: spans[2].0, y: 0, width: len(spans[2]), height: top }));
+    }
+    if bottom > 0 {
+        struts.push(Strut::Bottom(Rect { x: spans[3].0, y: root_height - bottom, width: len(spans[3]), height: bottom }));
+    }
+    struts
+}
+
+/// Shrink a monitor's bounds by every strut that overlaps it
+pub fn subtract_struts(bounds: Rect, struts: &[Strut]) -> Rect {
+    let (mut left, mut top, mut right, mut bottom) = (bounds.x, bounds.y, bounds.right(), bounds.bottom());
+    for strut in struts {
+        match strut {
+            Strut::Left(r) if bounds.intersect(r).is_some() => left = left.max(r.right()),
+            Strut::Right(r) if bounds.intersect(r).is_some() => right = right.min(r.x),
+            Strut::Top(r) if bounds.intersect(r).is_some() => top = top.max(r.bottom()),
+            Strut::Bottom(r) if bounds.intersect(r).is_some() => bottom = bottom.min(r.y),
+            _ => {}
+        }
+    }
+    Rect { x: left, y: top, width: right - left, height: bottom - top }
+}
+
+/// `rcWork` of the monitor under the centre of `bounds`. The process is
+/// per-monitor DPI aware, so this is already in physical pixels.
+#[cfg(target_os = "windows")]
+pub fn detect(bounds: Rect, _scale_factor: f64) -> Option<Rect> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONULL};
+
+    let center = POINT { x: bounds.x + bounds.width / 2, y: bounds.y + bounds.height / 2 };
+    let monitor = unsafe { MonitorFromPoint(center, MONITOR_DEFAULTTONULL) };
+    if monitor.is_invalid() {
+        return None;
+    }
+    let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        return None;
+    }
+    let work = info.rcWork;
+    Some(Rect { x: work.left, y: work.top, width: work.right - work.left, height: work.bottom - work.top })
+}
+
+/// Subtract the struts panels and docks reserve. `_NET_WORKAREA` is one rect
+/// over the whole virtual screen, so it's only used when no window reports
+/// struts (e.g. shells that draw panels without client windows).
+#[cfg(target_os = "linux")]
+pub fn detect(bounds: Rect, _scale_factor: f64) -> Option<Rect> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    let (conn, screen) = x11rb::connect(None).ok()?;
+    let root = &conn.setup().roots[screen];
+    let (root_window, root_width, root_height) = (root.root, root.width_in_pixels as i32, root.height_in_pixels as i32);
+
+    let atom = |name: &str| -> Option<u32> {
+        let atom = conn.intern_atom(true, name.as_bytes()).ok()?.reply().ok()?.atom;
+        (atom != u32::from(AtomEnum::NONE)).then_some(atom)
+    };
+    let cardinals = |window: u32, property: u32, kind: AtomEnum, length: u32| -> Vec<u32> {
+        conn.get_property(false, window, property, kind, 0, length)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    };
+
+    let strut_partial = atom("_NET_WM_STRUT_PARTIAL");
+    let strut = atom("_NET_WM_STRUT");
+    let mut struts = Vec::new();
+    if let Some(client_list) = atom("_NET_CLIENT_LIST") {
+        for window in cardinals(root_window, client_list, AtomEnum::WINDOW, u32::MAX) {
+            let mut values = strut_partial.map(|p| cardinals(window, p, AtomEnum::CARDINAL, 12)).unwrap_or_default();
+            if values.is_empty() {
+                values = strut.map(|p| cardinals(window, p, AtomEnum::CARDINAL, 4)).unwrap_or_default();
+            }
+            struts.extend(struts_from_property(root_width, root_height, &values));
+        }
+    }
+    if !struts.is_empty() {
+        return Some(subtract_struts(bounds, &struts));
+    }
+
+    let desktop = atom("_NET_CURRENT_DESKTOP")
+        .and_then(|p| cardinals(root_window, p, AtomEnum::CARDINAL, 1).first().copied())
+        .unwrap_or(0) as usize;
+    let areas = cardinals(root_window, atom("_NET_WORKAREA")?, AtomEnum::CARDINAL, u32::MAX);
+    let area = areas.chunks_exact(4).nth(desktop).or_else(|| areas.chunks_exact(4).next())?;
+    let area = Rect { x: area[0] as i32, y: area[1] as i32, width: area[2] as i32, height: area[3] as i32 };
+    bounds.intersect(&area)
+}
+
+/// `visibleFrame` of the matching NSScreen, converted from bottom-left
+/// points to top-left physical pixels. AppKit must be queried on the main
+/// thread; returns None anywhere else.
+#[cfg(target_os = "macos")]
+pub fn detect(bounds: Rect, scale_factor: f64) -> Option<Rect> {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSScreen;
+
+    let mtm = MainThreadMarker::new()?;
+    let screens = NSScreen::screens(mtm);
+    let primary_height = screens.iter().next()?.frame().size.height;
+    let to_px = |points: f64| (points * scale_factor).round() as i32;
+
+    screens.iter().find_map(|screen| {
+        let frame = screen.frame();
+        let top = primary_height - (frame.origin.y + frame.size.height);
+        // Allow a pixel of rounding between Tauri's physical position and ours
+        if (to_px(frame.origin.x) - bounds.x).abs() > 1 || (to_px(top) - bounds.y).abs() > 1 {
+            return None;
+        }
+        let visible = screen.visibleFrame();
+        let visible_top = primary_height - (visible.origin.y + visible.size.height);
+        Some(Rect {
+            x: to_px(visible.origin.x),
+            y: to_px(visible_top),
+            width: to_px(visible.size.width),
+            height: to_px(visible.size.height),
+        })
+    })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub fn detect(_bounds: Rect, _scale_factor: f64) -> Option<Rect> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn struts_from_partial_property_use_the_spans() {
+        // 40px top panel on the left monitor of a 3840x1080 root, 64px dock on the right monitor
+        let values = [0, 0, 40, 64, 0, 0, 0, 0, 0, 1919, 1920, 3839];
+        assert_eq!(
+            struts_from_property(3840, 1080, &values),
+            vec![Strut::Top(rect(0, 0, 1920, 40)), Strut::Bottom(rect(1920, 1016, 1920, 64))]
+        );
+
+        let values = [48, 30, 0, 0, 100, 699, 0, 1079, 0, 0, 0, 0];
+        assert_eq!(
+            struts_from_property(1920, 1080, &values),
+            vec![Strut::Left(rect(0, 100, 48, 600)), Strut::Right(rect(1890, 0, 30, 1080))]
+        );
+    }
+
+    #[test]
+    fn struts_from_legacy_property_span_the_whole_edge() {
+        assert_eq!(
+            struts_from_property(1920, 1080, &[0, 0, 0, 32]),
+            vec![Strut::Bottom(rect(0, 1048, 1920, 32))]
+        );
+        assert_eq!(struts_from_property(1920, 1080, &[0, 0, 0, 0]), Vec::new());
+    }
+
+    #[test]
+    fn struts_from_property_ignores_other_lengths() {
+        assert!(struts_from_property(1920, 1080, &[]).is_empty());
+        assert!(struts_from_property(1920, 1080, &[10, 0, 0]).is_empty());
+        assert!(struts_from_property(1920, 1080, &[10; 8]).is_empty());
+    }
+
+    #[test]
+    fn subtract_struts_only_applies_overlapping_struts() {
+        let struts = struts_from_property(3840, 1080, &[0, 0, 40, 64, 0, 0, 0, 0, 0, 1919, 1920, 3839]);
+        let left_monitor = rect(0, 0, 1920, 1080);
+        let right_monitor = rect(1920, 0, 1920, 1080);
+        assert_eq!(subtract_struts(left_monitor, &struts), rect(0, 40, 1920, 1040));
+        assert_eq!(subtract_struts(right_monitor, &struts), rect(1920, 0, 1920, 1016));
+    }
+
+    #[test]
+    fn subtract_struts_handles_every_edge_and_overlapping_panels() {
+        let bounds = rect(0, 0, 1920, 1080);
+        let struts = [
+            Strut::Left(rect(0, 0, 48, 1080)),
+            Strut::Left(rect(0, 0, 60, 200)),
+            Strut::Right(rect(1880, 0, 40, 1080)),
+            Strut::Top(rect(0, 0, 1920, 24)),
+            Strut::Bottom(rect(0, 1040, 1920, 40)),
+        ];
+        assert_eq!(subtract_struts(bounds, &struts), rect(60, 24, 1820, 1016));
+        assert_eq!(subtract_struts(bounds, &[]), bounds);
+    }
+
+    #[test]
+    fn is_plausible_requires_a_non_empty_area_inside_the_monitor() {
+        let bounds = rect(-1920, 0, 1920, 1080);
+        assert!(is_plausible(&bounds, &rect(-1920, 0, 1920, 1040)));
+        assert!(is_plausible(&bounds, &bounds));
+        assert!(!is_plausible(&bounds, &rect(-1920, 0, 0, 1080)));
+        assert!(!is_plausible(&bounds, &rect(0, 0, 1920, 1040)));
+        assert!(!is_plausible(&bounds, &rect(-1920, 0, 3840, 1080)));
+    }
+
+    #[test]
+    fn fallback_reserves_a_scaled_bottom_taskbar() {
+        assert_eq!(fallback(rect(0, 0, 1920, 1080), 1.0), rect(0, 0, 1920, 1032));
+        assert_eq!(fallback(rect(1920, -100, 3840, 2160), 2.0), rect(1920, -100, 3840, 2064));
+    }
+}